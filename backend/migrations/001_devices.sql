@@ -112,29 +112,12 @@ END$$
 
 DELIMITER ;
 
--- ========================================
--- Sample Data (測試用)
--- ========================================
-
--- 測試設備
-INSERT INTO devices (device_id, platform, has_used_free_trial, is_banned)
-VALUES 
-    ('test-device-001', 'android', 0, 0),
-    ('test-device-002', 'ios', 1, 0),
-    ('test-device-003', 'web', 0, 1)
-ON DUPLICATE KEY UPDATE updated_at = NOW();
-
--- 測試使用記錄
-INSERT INTO device_usage_logs (device_id, event_type, app_version, platform)
-VALUES 
-    ('test-device-001', 'APP_OPENED', '1.0.0', 'android'),
-    ('test-device-002', 'TRIAL_STARTED', '1.0.0', 'ios'),
-    ('test-device-003', 'APP_OPENED', '1.0.0', 'web');
-
 -- ========================================
 -- Usage Examples
 -- ========================================
 
+-- 測試設備見 seeds/dev_devices.sql (只在開發環境手動匯入)
+
 -- 檢查設備狀態
 -- CALL sp_check_device_free_trial('test-device-001');
 
@@ -0,0 +1,37 @@
+-- ========================================
+-- User Account Schema for Nice_Speak
+-- ========================================
+
+-- 用戶表
+CREATE TABLE IF NOT EXISTS `users` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `email` VARCHAR(255) NOT NULL COMMENT '登入信箱 (小寫)',
+    `password_hash` VARCHAR(255) NOT NULL COMMENT 'Argon2 密碼雜湊',
+    `name` VARCHAR(100) NULL COMMENT '顯示名稱',
+    `avatar_url` VARCHAR(500) NULL COMMENT '頭像網址',
+    `free_trial_used` TINYINT(1) NOT NULL DEFAULT 0 COMMENT '是否使用過免費試用',
+    `registered_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '註冊時間',
+    `last_login_at` DATETIME NULL COMMENT '最後登入時間',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_users_email` (`email`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='用戶表';
+
+-- 訂閱表
+CREATE TABLE IF NOT EXISTS `subscriptions` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `user_id` CHAR(36) NOT NULL COMMENT '用戶 ID',
+    `tier` VARCHAR(50) NOT NULL COMMENT '方案: free, evaluation, basic, advanced, premium, platinum, unlimited',
+    `status` VARCHAR(20) NOT NULL COMMENT '狀態: active, expired, cancelled',
+    `started_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '開始時間',
+    `expires_at` DATETIME NULL COMMENT '到期時間',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    INDEX `idx_subscriptions_user_id` (`user_id`),
+    INDEX `idx_subscriptions_status` (`status`),
+    CONSTRAINT `fk_subscriptions_user` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='訂閱表';
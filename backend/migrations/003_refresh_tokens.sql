@@ -0,0 +1,21 @@
+-- ========================================
+-- Refresh Token Schema for Nice_Speak
+-- ========================================
+
+-- Refresh Token 表 (一次性使用，同一次登入輪替出的 token 屬於同一個 family)
+CREATE TABLE IF NOT EXISTS `refresh_tokens` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `user_id` CHAR(36) NOT NULL COMMENT '用戶 ID',
+    `family_id` CHAR(36) NOT NULL COMMENT 'Token 家族 ID (同一次登入)',
+    `token_hash` CHAR(64) NOT NULL COMMENT 'Token SHA-256',
+    `expires_at` DATETIME NOT NULL COMMENT '到期時間',
+    `used_at` DATETIME NULL COMMENT '已輪替時間',
+    `revoked_at` DATETIME NULL COMMENT '撤銷時間',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_refresh_tokens_hash` (`token_hash`),
+    INDEX `idx_refresh_tokens_user_id` (`user_id`),
+    INDEX `idx_refresh_tokens_family_id` (`family_id`),
+    CONSTRAINT `fk_refresh_tokens_user` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='Refresh Token 表';
@@ -0,0 +1,21 @@
+-- ========================================
+-- Device Account Binding for Nice_Speak
+-- ========================================
+
+-- 綁定時間 (devices.user_id 已於 001 建立)
+ALTER TABLE `devices`
+    ADD COLUMN `bound_at` DATETIME NULL COMMENT '綁定帳號時間' AFTER `user_id`;
+
+-- 設備轉移記錄表
+CREATE TABLE IF NOT EXISTS `device_transfers` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `device_id` VARCHAR(64) NOT NULL COMMENT '設備 ID',
+    `from_user_id` CHAR(36) NULL COMMENT '原綁定用戶',
+    `to_user_id` CHAR(36) NOT NULL COMMENT '新綁定用戶',
+    `had_used_free_trial` TINYINT(1) NOT NULL DEFAULT 0 COMMENT '轉移時是否已使用免費試用',
+    `transferred_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '轉移時間',
+    PRIMARY KEY (`id`),
+    INDEX `idx_device_transfers_device_id` (`device_id`),
+    INDEX `idx_device_transfers_to_user_id` (`to_user_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='設備轉移記錄表';
@@ -0,0 +1,9 @@
+-- ========================================
+-- Device Usage Event Ingestion for Nice_Speak
+-- ========================================
+
+-- 客戶端事件 ID 用於去重；舊資料沒有事件 ID (NULL 不受唯一鍵限制)
+ALTER TABLE `device_usage_logs`
+    ADD COLUMN `client_event_id` VARCHAR(64) NULL COMMENT '客戶端事件 ID' AFTER `device_id`,
+    ADD COLUMN `occurred_at` DATETIME NULL COMMENT '客戶端事件發生時間' AFTER `metadata`,
+    ADD UNIQUE KEY `uk_device_usage_logs_event` (`device_id`, `client_event_id`);
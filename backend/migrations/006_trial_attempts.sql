@@ -0,0 +1,24 @@
+-- ========================================
+-- Free Trial Abuse Detection for Nice_Speak
+-- ========================================
+
+-- 試用申請記錄表 (含被拒絕與待審核的申請)
+CREATE TABLE IF NOT EXISTS `trial_attempts` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `user_id` CHAR(36) NOT NULL COMMENT '申請用戶',
+    `device_id` VARCHAR(64) NOT NULL COMMENT '申請設備',
+    `email_identity` VARCHAR(255) NOT NULL COMMENT '正規化後的信箱',
+    `ip_address` VARCHAR(45) NULL COMMENT '客戶端 IP',
+    `ip_subnet` VARCHAR(50) NULL COMMENT 'IP 網段 (/24 或 /64)',
+    `score` INT NOT NULL COMMENT '風險分數',
+    `decision` VARCHAR(10) NOT NULL COMMENT '結果: allow, review, deny',
+    `reasons` JSON NULL COMMENT '計分原因',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    INDEX `idx_trial_attempts_user_id` (`user_id`),
+    INDEX `idx_trial_attempts_device_id` (`device_id`),
+    INDEX `idx_trial_attempts_email_identity` (`email_identity`),
+    INDEX `idx_trial_attempts_ip` (`ip_address`, `created_at`),
+    INDEX `idx_trial_attempts_subnet` (`ip_subnet`, `created_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='試用申請記錄表';
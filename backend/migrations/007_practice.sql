@@ -0,0 +1,91 @@
+-- ========================================
+-- Scenario Practice Schema for Nice_Speak
+-- ========================================
+
+-- 情境表
+CREATE TABLE IF NOT EXISTS `scenarios` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `code` VARCHAR(50) NOT NULL COMMENT '情境代碼',
+    `name` VARCHAR(100) NOT NULL COMMENT '情境名稱',
+    `description` TEXT NULL COMMENT '情境描述',
+    `role_1` VARCHAR(50) NOT NULL COMMENT 'AI 扮演的角色',
+    `role_2` VARCHAR(50) NULL COMMENT '用戶扮演的角色',
+    `category` VARCHAR(50) NOT NULL COMMENT '分類',
+    `difficulty` INT NOT NULL COMMENT '難度 1-5',
+    `dialogue_count` INT NOT NULL DEFAULT 5 COMMENT '對話輪數',
+    `tier_required` VARCHAR(50) NOT NULL DEFAULT 'free' COMMENT '最低方案',
+    `is_active` TINYINT(1) NOT NULL DEFAULT 1 COMMENT '是否上架',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_scenarios_code` (`code`),
+    INDEX `idx_scenarios_tier` (`tier_required`),
+    INDEX `idx_scenarios_category` (`category`),
+    INDEX `idx_scenarios_difficulty` (`difficulty`),
+    CONSTRAINT `chk_scenarios_difficulty` CHECK (`difficulty` BETWEEN 1 AND 5)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='情境表';
+
+-- 對話內容表
+CREATE TABLE IF NOT EXISTS `dialogues` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `scenario_id` CHAR(36) NOT NULL COMMENT '情境 ID',
+    `sequence_number` INT NOT NULL COMMENT '對話順序',
+    `speaker_role` VARCHAR(50) NOT NULL COMMENT '說話角色',
+    `content` TEXT NOT NULL COMMENT '對話內容',
+    `audio_url` VARCHAR(500) NULL COMMENT '語音檔網址',
+    `vocabulary` JSON NULL COMMENT '重點單字',
+    `evaluation_points` JSON NULL COMMENT '評估重點',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_dialogues_sequence` (`scenario_id`, `sequence_number`),
+    CONSTRAINT `fk_dialogues_scenario` FOREIGN KEY (`scenario_id`) REFERENCES `scenarios` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='對話內容表';
+
+-- 練習記錄表
+CREATE TABLE IF NOT EXISTS `practice_records` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `user_id` CHAR(36) NOT NULL COMMENT '用戶 ID',
+    `scenario_id` CHAR(36) NOT NULL COMMENT '情境 ID',
+    `started_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '開始時間',
+    `last_activity_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '最後作答時間',
+    `completed_at` DATETIME NULL COMMENT '完成時間',
+    `total_score` INT NULL COMMENT '總分',
+    `pronunciation_score` INT NULL COMMENT '發音 (0-30)',
+    `grammar_score` INT NULL COMMENT '文法 (0-30)',
+    `vocabulary_score` INT NULL COMMENT '詞彙 (0-20)',
+    `fluency_score` INT NULL COMMENT '流暢度 (0-20)',
+    `transcript` TEXT NULL COMMENT '完整逐字稿',
+    `feedback` TEXT NULL COMMENT '整體回饋',
+    `status` VARCHAR(20) NOT NULL DEFAULT 'in_progress' COMMENT '狀態: in_progress, completed',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    INDEX `idx_practice_records_user_id` (`user_id`),
+    INDEX `idx_practice_records_scenario_id` (`scenario_id`),
+    INDEX `idx_practice_records_status` (`status`),
+    CONSTRAINT `fk_practice_records_user` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE,
+    CONSTRAINT `fk_practice_records_scenario` FOREIGN KEY (`scenario_id`) REFERENCES `scenarios` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='練習記錄表';
+
+-- 每輪作答記錄 (斷線重連時依此恢復進度)
+CREATE TABLE IF NOT EXISTS `practice_turns` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `practice_id` CHAR(36) NOT NULL COMMENT '練習記錄 ID',
+    `dialogue_id` CHAR(36) NOT NULL COMMENT '回應的對話 ID',
+    `sequence_number` INT NOT NULL COMMENT '對話順序',
+    `transcript` TEXT NULL COMMENT '用戶回答逐字稿',
+    `audio_bytes` INT NOT NULL DEFAULT 0 COMMENT '音訊大小',
+    `pronunciation_score` INT NOT NULL COMMENT '發音 (0-30)',
+    `grammar_score` INT NOT NULL COMMENT '文法 (0-30)',
+    `vocabulary_score` INT NOT NULL COMMENT '詞彙 (0-20)',
+    `fluency_score` INT NOT NULL COMMENT '流暢度 (0-20)',
+    `total_score` INT NOT NULL COMMENT '總分',
+    `feedback` TEXT NULL COMMENT '回饋',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_practice_turns_dialogue` (`practice_id`, `dialogue_id`),
+    CONSTRAINT `fk_practice_turns_practice` FOREIGN KEY (`practice_id`) REFERENCES `practice_records` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='練習每輪作答記錄';
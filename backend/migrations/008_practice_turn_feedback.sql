@@ -0,0 +1,8 @@
+-- ========================================
+-- Practice Turn Feedback for Nice_Speak
+-- ========================================
+
+-- 每輪評估的建議與錯誤項目 (JSON 陣列)
+ALTER TABLE `practice_turns`
+    ADD COLUMN `suggestions` JSON NULL COMMENT '改進建議' AFTER `feedback`,
+    ADD COLUMN `errors` JSON NULL COMMENT '錯誤項目: type, original, suggested' AFTER `suggestions`;
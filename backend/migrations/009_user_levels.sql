@@ -0,0 +1,22 @@
+-- ========================================
+-- User Level Progression for Nice_Speak
+-- ========================================
+
+-- 用戶等級表 (規則見 Document/LEVEL_SYSTEM.md)
+CREATE TABLE IF NOT EXISTS `user_levels` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `user_id` CHAR(36) NOT NULL COMMENT '用戶 ID',
+    `current_level` INT NOT NULL DEFAULT 0 COMMENT '目前等級 0-10',
+    `total_score` INT NOT NULL DEFAULT 0 COMMENT '歷次練習總分合計',
+    `consecutive_wins` INT NOT NULL DEFAULT 0 COMMENT '本等級連續達標次數',
+    `cumulative_practices` INT NOT NULL DEFAULT 0 COMMENT '本等級累計達標次數',
+    `last_practice_at` DATETIME NULL COMMENT '最後練習時間',
+    `level_5_unlocked` TINYINT(1) NOT NULL DEFAULT 0 COMMENT '已解鎖 5 級折扣',
+    `level_10_unlocked` TINYINT(1) NOT NULL DEFAULT 0 COMMENT '已解鎖 10 級折扣',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_user_levels_user_id` (`user_id`),
+    CONSTRAINT `fk_user_levels_user` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='用戶等級表';
@@ -0,0 +1,10 @@
+-- ========================================
+-- Practice Lifecycle for Nice_Speak
+-- ========================================
+
+-- 練習狀態加入 abandoned / expired；完成時的等級變化保留下來，重複完成時原樣回傳
+ALTER TABLE `practice_records`
+    MODIFY COLUMN `status` VARCHAR(20) NOT NULL DEFAULT 'in_progress' COMMENT '狀態: in_progress, completed, abandoned, expired',
+    ADD COLUMN `level_up` JSON NULL COMMENT '完成時的等級變化' AFTER `feedback`,
+    ADD COLUMN `ended_at` DATETIME NULL COMMENT '放棄或逾時的時間' AFTER `completed_at`,
+    ADD INDEX `idx_practice_records_expiry` (`status`, `last_activity_at`);
@@ -0,0 +1,24 @@
+-- ========================================
+-- Subscription Entitlements for Nice_Speak
+-- ========================================
+
+-- 訂閱是否自動續訂
+ALTER TABLE `subscriptions`
+    ADD COLUMN `auto_renew` TINYINT(1) NOT NULL DEFAULT 0 COMMENT '是否自動續訂' AFTER `expires_at`;
+
+-- 免費版 / 評估版選擇的角色 (6 選 1)
+ALTER TABLE `users`
+    ADD COLUMN `practice_role` VARCHAR(50) NULL COMMENT '免費版/評估版選擇的角色' AFTER `free_trial_used`;
+
+-- 進階版自選的對話角色 (每個角色最多 3 個)
+CREATE TABLE IF NOT EXISTS `user_dialogue_partners` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `user_id` CHAR(36) NOT NULL COMMENT '用戶 ID',
+    `learner_role` VARCHAR(50) NOT NULL COMMENT '用戶扮演的角色',
+    `partner_role` VARCHAR(50) NOT NULL COMMENT '對話角色',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_user_dialogue_partners` (`user_id`, `learner_role`, `partner_role`),
+    CONSTRAINT `fk_user_dialogue_partners_user` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='用戶自選對話角色表';
@@ -0,0 +1,53 @@
+-- ========================================
+-- Payments and Promotions for Nice_Speak
+-- ========================================
+
+-- 優惠碼表
+CREATE TABLE IF NOT EXISTS `promotions` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `code` VARCHAR(50) NOT NULL COMMENT '優惠碼 (大寫)',
+    `description` VARCHAR(255) NULL COMMENT '說明',
+    `percent_off` INT NOT NULL DEFAULT 0 COMMENT '折扣百分比',
+    `amount_off` INT NOT NULL DEFAULT 0 COMMENT '折抵金額 (NT$)，percent_off 為 0 時使用',
+    `starts_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '開始時間',
+    `ends_at` DATETIME NULL COMMENT '結束時間',
+    `max_redemptions` INT NULL COMMENT '可使用次數上限',
+    `redemptions` INT NOT NULL DEFAULT 0 COMMENT '已使用次數',
+    `is_active` TINYINT(1) NOT NULL DEFAULT 1 COMMENT '是否啟用',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_promotions_code` (`code`),
+    CONSTRAINT `chk_promotions_percent_off` CHECK (`percent_off` BETWEEN 0 AND 100)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='優惠碼表';
+
+-- 支付記錄表 (報價鎖定後即建立，狀態 quoted → pending → paid / failed；報價逾時為 expired)
+CREATE TABLE IF NOT EXISTS `payments` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `user_id` CHAR(36) NOT NULL COMMENT '用戶 ID',
+    `subscription_id` CHAR(36) NULL COMMENT '付款後建立的訂閱',
+    `tier` VARCHAR(50) NOT NULL COMMENT '購買方案',
+    `billing_cycle` VARCHAR(20) NOT NULL COMMENT '付款週期: monthly, yearly, one_time',
+    `list_price` INT NOT NULL COMMENT '原價小計 (NT$)',
+    `cycle_discount` INT NOT NULL DEFAULT 0 COMMENT '年繳折扣 (NT$)',
+    `level_discount_percentage` INT NOT NULL DEFAULT 0 COMMENT '等級折扣百分比',
+    `level_discount` INT NOT NULL DEFAULT 0 COMMENT '等級折扣 (NT$)',
+    `promo_code` VARCHAR(50) NULL COMMENT '使用的優惠碼',
+    `promo_discount` INT NOT NULL DEFAULT 0 COMMENT '優惠碼折扣 (NT$)',
+    `amount` INT NOT NULL COMMENT '應付金額 (NT$)',
+    `currency` VARCHAR(3) NOT NULL DEFAULT 'TWD',
+    `line_items` JSON NOT NULL COMMENT '報價明細',
+    `payment_method` VARCHAR(50) NULL COMMENT '付款方式',
+    `transaction_id` VARCHAR(255) NULL COMMENT '金流交易編號',
+    `status` VARCHAR(20) NOT NULL COMMENT '狀態: quoted, pending, paid, failed, expired',
+    `quote_expires_at` DATETIME NOT NULL COMMENT '報價鎖定到期時間',
+    `paid_at` DATETIME NULL COMMENT '付款時間',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    INDEX `idx_payments_user_id` (`user_id`),
+    INDEX `idx_payments_status` (`status`),
+    CONSTRAINT `fk_payments_user` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE,
+    CONSTRAINT `fk_payments_subscription` FOREIGN KEY (`subscription_id`) REFERENCES `subscriptions` (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='支付記錄表';
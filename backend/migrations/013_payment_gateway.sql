@@ -0,0 +1,9 @@
+-- ========================================
+-- Payment Gateway for Nice_Speak
+-- ========================================
+
+-- 綠界特店交易編號 (英數 20 字內) 與付款失敗原因；交易編號用來對應付款結果通知
+ALTER TABLE `payments`
+    ADD COLUMN `merchant_trade_no` VARCHAR(20) NULL COMMENT '綠界特店交易編號' AFTER `payment_method`,
+    ADD COLUMN `failure_reason` VARCHAR(255) NULL COMMENT '付款失敗原因' AFTER `status`,
+    ADD UNIQUE KEY `uk_payments_merchant_trade_no` (`merchant_trade_no`);
@@ -0,0 +1,20 @@
+-- ========================================
+-- Subscription Lifecycle for Nice_Speak
+-- ========================================
+
+-- 訂閱狀態加入 past_due (自動續訂扣款未完成，寬限期內保留權益)；
+-- 取消時保留到期前的權益，到期後改為 cancelled
+ALTER TABLE `subscriptions`
+    MODIFY COLUMN `status` VARCHAR(20) NOT NULL COMMENT '狀態: active, past_due, expired, cancelled',
+    ADD COLUMN `billing_cycle` VARCHAR(20) NULL COMMENT '付款週期: monthly, yearly, one_time' AFTER `tier`,
+    ADD COLUMN `period_trade_no` VARCHAR(20) NULL COMMENT '綠界定期定額的特店交易編號' AFTER `auto_renew`,
+    ADD COLUMN `cancel_at_period_end` TINYINT(1) NOT NULL DEFAULT 0 COMMENT '到期後不再續訂' AFTER `period_trade_no`,
+    ADD COLUMN `cancelled_at` DATETIME NULL COMMENT '申請取消的時間' AFTER `cancel_at_period_end`,
+    ADD COLUMN `grace_until` DATETIME NULL COMMENT '寬限期結束時間' AFTER `cancelled_at`,
+    ADD COLUMN `expiry_notified_at` DATETIME NULL COMMENT '到期提醒發送時間' AFTER `grace_until`,
+    ADD INDEX `idx_subscriptions_lifecycle` (`status`, `expires_at`),
+    ADD INDEX `idx_subscriptions_period_trade_no` (`period_trade_no`);
+
+-- 購買時是否選擇自動續訂；定期定額的後續扣款也記錄在付款表 (transaction_id 為授權單號)
+ALTER TABLE `payments`
+    ADD COLUMN `auto_renew` TINYINT(1) NOT NULL DEFAULT 0 COMMENT '是否以定期定額自動續訂' AFTER `billing_cycle`;
@@ -0,0 +1,23 @@
+-- ========================================
+-- Trial Periods for Nice_Speak
+-- ========================================
+
+-- 系統參數 (管理後台 settings/parameters)
+CREATE TABLE IF NOT EXISTS `system_parameters` (
+    `name` VARCHAR(64) NOT NULL COMMENT '參數名稱',
+    `value` INT NOT NULL COMMENT '參數值',
+    `description` VARCHAR(255) NULL COMMENT '說明',
+    `updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (`name`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='系統參數表';
+
+INSERT INTO system_parameters (name, value, description)
+VALUES
+    ('free_trial_days', 3, '免費版使用天數 (從註冊開始計算)'),
+    ('evaluation_trial_days', 7, '評估版使用天數 (從購買開始計算)')
+ON DUPLICATE KEY UPDATE name = name;
+
+-- 評估版每個帳號與設備只能購買一次
+ALTER TABLE `devices`
+    ADD COLUMN `has_purchased_evaluation` TINYINT(1) NOT NULL DEFAULT 0 COMMENT '是否購買過評估版' AFTER `has_used_free_trial`;
@@ -0,0 +1,43 @@
+-- ========================================
+-- Vocabulary Review Schema for Nice_Speak
+-- ========================================
+
+-- 單字庫 (由情境對話的 dialogues.vocabulary 收錄)
+CREATE TABLE IF NOT EXISTS `vocabulary` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `word` VARCHAR(100) NOT NULL COMMENT '單字或片語',
+    `phonetic` VARCHAR(50) NULL COMMENT '音標',
+    `definition` TEXT NULL COMMENT '解釋',
+    `example_sentence` TEXT NULL COMMENT '例句',
+    `audio_url` VARCHAR(500) NULL COMMENT '發音檔網址',
+    `category` VARCHAR(50) NULL COMMENT '分類',
+    `difficulty` INT NULL COMMENT '難度 1-5',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_vocabulary_word` (`word`),
+    INDEX `idx_vocabulary_category` (`category`),
+    CONSTRAINT `chk_vocabulary_difficulty` CHECK (`difficulty` BETWEEN 1 AND 5)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='單字庫';
+
+-- 用戶單字本 (SM-2 間隔複習)
+CREATE TABLE IF NOT EXISTS `user_vocabulary` (
+    `id` CHAR(36) NOT NULL DEFAULT (UUID()),
+    `user_id` CHAR(36) NOT NULL COMMENT '用戶 ID',
+    `vocabulary_id` CHAR(36) NOT NULL COMMENT '單字 ID',
+    `mastery_level` INT NOT NULL DEFAULT 0 COMMENT '熟練度 0-5 (連續答對次數，最多 5)',
+    `last_reviewed_at` DATETIME NULL COMMENT '最後複習時間',
+    `review_count` INT NOT NULL DEFAULT 0 COMMENT '複習次數',
+    `ease_factor` DOUBLE NOT NULL DEFAULT 2.5 COMMENT 'SM-2 難易度係數 (最低 1.3)',
+    `interval_days` INT NOT NULL DEFAULT 0 COMMENT '目前的複習間隔天數',
+    `repetitions` INT NOT NULL DEFAULT 0 COMMENT '連續答對次數',
+    `due_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '下次複習時間',
+    `scenario_id` CHAR(36) NULL COMMENT '收錄來源情境',
+    `created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `uk_user_vocab` (`user_id`, `vocabulary_id`),
+    INDEX `idx_user_vocab_due` (`user_id`, `due_at`),
+    CONSTRAINT `fk_user_vocab_user` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE,
+    CONSTRAINT `fk_user_vocab_vocabulary` FOREIGN KEY (`vocabulary_id`) REFERENCES `vocabulary` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='用戶單字本';
@@ -0,0 +1,7 @@
+-- ========================================
+-- Learner Timezone for Nice_Speak
+-- ========================================
+
+-- 統計的連續練習天數依用戶時區的日期計算
+ALTER TABLE `users`
+    ADD COLUMN `timezone` VARCHAR(64) NULL COMMENT 'IANA 時區，例如 Asia/Taipei (未設定時為台灣時間)' AFTER `avatar_url`;
@@ -0,0 +1,10 @@
+-- ========================================
+-- Scenario Catalog Cache Support for Nice_Speak
+-- ========================================
+
+-- 對話修改時間：情境列表快取依情境與對話的最後更新時間判斷內容是否變更
+ALTER TABLE `dialogues`
+    ADD COLUMN `updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP AFTER `created_at`;
+
+-- 情境列表依上架狀態、難度與代碼排序
+CREATE INDEX `idx_scenarios_catalog` ON `scenarios` (`is_active`, `difficulty`, `code`);
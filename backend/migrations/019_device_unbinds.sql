@@ -0,0 +1,7 @@
+-- ========================================
+-- Device Unbind Tracking for Nice_Speak
+-- ========================================
+
+-- 解除綁定的帳號：其他帳號之後綁定時視為轉移，套用轉移冷卻期
+ALTER TABLE `devices`
+    ADD COLUMN `unbound_user_id` CHAR(36) NULL COMMENT '最後解除綁定的用戶' AFTER `bound_at`;
@@ -0,0 +1,10 @@
+-- ========================================
+-- Remove Sample Devices for Nice_Speak
+-- ========================================
+
+-- 舊版 001 會寫入測試設備，已移到 seeds/dev_devices.sql；清除已套用環境中未綁定的測試資料
+DELETE FROM `device_usage_logs`
+WHERE `device_id` IN ('test-device-001', 'test-device-002', 'test-device-003') AND `client_event_id` IS NULL;
+
+DELETE FROM `devices`
+WHERE `device_id` IN ('test-device-001', 'test-device-002', 'test-device-003') AND `user_id` IS NULL;
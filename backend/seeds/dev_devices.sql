@@ -0,0 +1,21 @@
+-- ========================================
+-- Development Sample Devices for Nice_Speak
+-- ========================================
+
+-- 只用於本機與測試環境，不屬於遷移，需在服務套用遷移後手動匯入：
+--   mysql -u root -p nice_speak_dev < backend/seeds/dev_devices.sql
+-- 可重複執行，已存在的資料會略過。
+
+-- 測試設備
+INSERT IGNORE INTO devices (device_id, platform, has_used_free_trial, is_banned)
+VALUES
+    ('test-device-001', 'android', 0, 0),
+    ('test-device-002', 'ios', 1, 0),
+    ('test-device-003', 'web', 0, 1);
+
+-- 測試使用記錄 (以 client_event_id 避免重複匯入)
+INSERT IGNORE INTO device_usage_logs (device_id, client_event_id, event_type, app_version, platform)
+VALUES
+    ('test-device-001', 'seed-001', 'APP_OPENED', '1.0.0', 'android'),
+    ('test-device-002', 'seed-002', 'TRIAL_STARTED', '1.0.0', 'ios'),
+    ('test-device-003', 'seed-003', 'APP_OPENED', '1.0.0', 'web');
@@ -0,0 +1,94 @@
+// src/auth/extractor.rs
+
+use super::jwt::{self, TOKEN_TYPE_ACCESS};
+use crate::database::redis_key;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts},
+    http::{header::AUTHORIZATION, request::Parts},
+};
+use redis::AsyncCommands;
+
+/// 已驗證的登入用戶 (由 `Authorization: Bearer <access_token>` 取得)
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub user_id: String,
+    pub email: String,
+    pub token: String,
+    pub expires_at: i64,
+}
+
+impl AuthUser {
+    /// 驗證 access token：簽章、有效期、token 類型，以及是否已列入黑名單
+    pub async fn from_token(state: &AppState, token: &str) -> AppResult<Self> {
+        let claims = jwt::decode_token(&state.config.jwt, token)
+            .map_err(|_| AppError::Unauthorized("Invalid or expired token".to_string()))?;
+
+        if claims.token_type != TOKEN_TYPE_ACCESS {
+            return Err(AppError::Unauthorized("Invalid token type".to_string()));
+        }
+
+        if is_blacklisted(state, token).await? {
+            return Err(AppError::Unauthorized("Token has been revoked".to_string()));
+        }
+
+        Ok(Self {
+            user_id: claims.sub,
+            email: claims.email,
+            token: token.to_string(),
+            expires_at: claims.exp,
+        })
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AuthUser
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state = AppState::from_ref(state);
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| AppError::Unauthorized("Missing bearer token".to_string()))?;
+
+        AuthUser::from_token(&state, token).await
+    }
+}
+
+/// 將 access token 加入黑名單 (`auth:{token}`)，保留到 token 原本的到期時間
+pub async fn blacklist_token(state: &AppState, token: &str, expires_at: i64) -> AppResult<()> {
+    let ttl = expires_at - chrono::Utc::now().timestamp();
+    if ttl <= 0 {
+        return Ok(());
+    }
+
+    let key = blacklist_key(state, token);
+    let mut redis = state.redis.clone();
+    redis
+        .set_ex::<_, _, ()>(key, 1, ttl as u64)
+        .await
+        .map_err(anyhow::Error::from)?;
+    Ok(())
+}
+
+async fn is_blacklisted(state: &AppState, token: &str) -> AppResult<bool> {
+    let key = blacklist_key(state, token);
+    let mut redis = state.redis.clone();
+    let exists: bool = redis.exists(key).await.map_err(anyhow::Error::from)?;
+    Ok(exists)
+}
+
+fn blacklist_key(state: &AppState, token: &str) -> String {
+    redis_key(&state.config.redis.key_prefix, &format!("auth:{}", token))
+}
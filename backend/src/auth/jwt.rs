@@ -0,0 +1,84 @@
+// src/auth/jwt.rs
+
+use crate::config::JwtConfig;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+pub const TOKEN_TYPE_ACCESS: &str = "access";
+
+/// JWT Claims
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub email: String,
+    pub token_type: String,
+    pub jti: String,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+/// 簽發 access token，有效期為 `JwtConfig.expires_in` 秒
+///
+/// Refresh token 不是 JWT，而是存在資料庫的一次性隨機字串 (見 `auth::refresh`)。
+pub fn issue_access_token(config: &JwtConfig, user_id: &str, email: &str) -> anyhow::Result<String> {
+    let now = chrono::Utc::now().timestamp();
+    let claims = Claims {
+        sub: user_id.to_string(),
+        email: email.to_string(),
+        token_type: TOKEN_TYPE_ACCESS.to_string(),
+        jti: uuid::Uuid::new_v4().to_string(),
+        iat: now,
+        exp: now + config.expires_in,
+    };
+
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config.secret.as_bytes()),
+    )?;
+    Ok(token)
+}
+
+/// 驗證簽章與有效期並取出 Claims
+pub fn decode_token(config: &JwtConfig, token: &str) -> anyhow::Result<Claims> {
+    let data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(config.secret.as_bytes()),
+        &Validation::default(),
+    )?;
+    Ok(data.claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jwt_config() -> JwtConfig {
+        JwtConfig {
+            secret: "unit-test-secret-unit-test-secret".to_string(),
+            expires_in: 60,
+            refresh_expires_in: 120,
+        }
+    }
+
+    #[test]
+    fn test_access_token_roundtrip() {
+        let config = jwt_config();
+        let token = issue_access_token(&config, "user-1", "user@example.com").unwrap();
+        let claims = decode_token(&config, &token).unwrap();
+        assert_eq!(claims.sub, "user-1");
+        assert_eq!(claims.token_type, TOKEN_TYPE_ACCESS);
+        assert_eq!(claims.exp - claims.iat, 60);
+    }
+
+    #[test]
+    fn test_rejects_wrong_secret() {
+        let config = jwt_config();
+        let token = issue_access_token(&config, "user-1", "user@example.com").unwrap();
+        let other = JwtConfig {
+            secret: "another-secret".to_string(),
+            ..config
+        };
+        assert!(decode_token(&other, &token).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+// src/auth/mod.rs
+
+pub mod extractor;
+pub mod jwt;
+pub mod password;
+pub mod refresh;
+
+pub use extractor::AuthUser;
+
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use crate::statistics;
+use crate::subscription;
+use axum::{extract::State, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+use std::sync::OnceLock;
+use validator::Validate;
+
+/// 找不到帳號時仍執行一次雜湊比對，避免以回應時間探測信箱是否註冊
+static DUMMY_PASSWORD_HASH: OnceLock<String> = OnceLock::new();
+
+// ==================== TYPES ====================
+
+/// 註冊請求
+#[derive(Deserialize, Validate)]
+pub struct RegisterRequest {
+    #[validate(email)]
+    pub email: String,
+    #[validate(length(min = 8, max = 128))]
+    pub password: String,
+    #[validate(length(max = 100))]
+    pub name: Option<String>,
+    /// IANA 時區 (統計的連續天數依此計算)
+    pub timezone: Option<String>,
+}
+
+/// 登入請求
+#[derive(Deserialize)]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+/// 刷新 Token 請求
+#[derive(Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// 刷新 Token 回應 (refresh token 一次性使用，每次都會換發新的)
+#[derive(Serialize)]
+pub struct RefreshResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// 登出請求 (帶上 refresh token 時一併撤銷該次登入的所有 refresh token)
+#[derive(Deserialize)]
+pub struct LogoutRequest {
+    pub refresh_token: Option<String>,
+}
+
+/// 註冊 / 登入回應
+#[derive(Serialize)]
+pub struct AuthResponse {
+    pub user: AuthUserResponse,
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+#[derive(Serialize)]
+pub struct AuthUserResponse {
+    pub id: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub tier: String,
+    pub registered_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct UserRow {
+    id: String,
+    email: String,
+    password_hash: String,
+    name: Option<String>,
+    registered_at: DateTime<Utc>,
+}
+
+// ==================== HANDLERS ====================
+
+/// 註冊新帳號
+pub async fn register(
+    State(state): State<AppState>,
+    Json(payload): Json<RegisterRequest>,
+) -> AppResult<Json<AuthResponse>> {
+    payload.validate()?;
+
+    let email = normalize_email(&payload.email);
+    let name = payload
+        .name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+
+    let timezone = match payload.timezone.as_deref().map(str::trim).filter(|tz| !tz.is_empty()) {
+        Some(name) => Some(
+            statistics::parse_timezone(name)
+                .ok_or_else(|| AppError::Validation(format!("Unknown timezone {}", name)))?
+                .name()
+                .to_string(),
+        ),
+        None => None,
+    };
+
+    let exists: Option<String> = sqlx::query_scalar("SELECT id FROM users WHERE email = ?")
+        .bind(&email)
+        .fetch_optional(&state.pool)
+        .await?;
+    if exists.is_some() {
+        return Err(AppError::Conflict("Email already registered".to_string()));
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    // Argon2 會佔用 CPU 數十毫秒，移到 blocking 執行緒避免卡住其他請求
+    let plain = payload.password;
+    let password_hash = tokio::task::spawn_blocking(move || password::hash_password(&plain))
+        .await
+        .map_err(anyhow::Error::from)??;
+    let registered_at = Utc::now();
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO users (id, email, password_hash, name, timezone, registered_at, last_login_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&email)
+    .bind(&password_hash)
+    .bind(&name)
+    .bind(&timezone)
+    .bind(registered_at)
+    .bind(registered_at)
+    .execute(&state.pool)
+    .await;
+
+    if let Err(err) = inserted {
+        let duplicate = err
+            .as_database_error()
+            .map(|db| db.is_unique_violation())
+            .unwrap_or(false);
+        return Err(if duplicate {
+            AppError::Conflict("Email already registered".to_string())
+        } else {
+            err.into()
+        });
+    }
+
+    let user = AuthUserResponse {
+        id,
+        email,
+        name,
+        tier: "free".to_string(),
+        registered_at,
+    };
+    issue_tokens(&state, user).await.map(Json)
+}
+
+/// 登入
+pub async fn login(
+    State(state): State<AppState>,
+    Json(payload): Json<LoginRequest>,
+) -> AppResult<Json<AuthResponse>> {
+    let email = normalize_email(&payload.email);
+
+    let user: Option<UserRow> = sqlx::query_as(
+        "SELECT id, email, password_hash, name, registered_at FROM users WHERE email = ?",
+    )
+    .bind(&email)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let plain = payload.password;
+    let stored_hash = user.as_ref().map(|user| user.password_hash.clone());
+    let matched = tokio::task::spawn_blocking(move || match stored_hash {
+        Some(hash) => password::verify_password(&plain, &hash),
+        None => {
+            let dummy = DUMMY_PASSWORD_HASH
+                .get_or_init(|| password::hash_password("dummy-password").unwrap_or_default());
+            password::verify_password(&plain, dummy);
+            false
+        }
+    })
+    .await
+    .map_err(anyhow::Error::from)?;
+    let user = match user {
+        Some(user) if matched => user,
+        _ => return Err(invalid_credentials()),
+    };
+
+    sqlx::query("UPDATE users SET last_login_at = ? WHERE id = ?")
+        .bind(Utc::now())
+        .bind(&user.id)
+        .execute(&state.pool)
+        .await?;
+
+    let tier = current_tier(&state.pool, &user.id).await?;
+    let response = AuthUserResponse {
+        id: user.id,
+        email: user.email,
+        name: user.name,
+        tier,
+        registered_at: user.registered_at,
+    };
+    issue_tokens(&state, response).await.map(Json)
+}
+
+/// 以 refresh token 換發新的 access token 與 refresh token
+pub async fn refresh(
+    State(state): State<AppState>,
+    Json(payload): Json<RefreshRequest>,
+) -> AppResult<Json<RefreshResponse>> {
+    let rotated = refresh::rotate(
+        &state.pool,
+        payload.refresh_token.trim(),
+        state.config.jwt.refresh_expires_in,
+    )
+    .await?;
+
+    let email: Option<String> = sqlx::query_scalar("SELECT email FROM users WHERE id = ?")
+        .bind(&rotated.user_id)
+        .fetch_optional(&state.pool)
+        .await?;
+    let email = email.ok_or_else(|| AppError::Unauthorized("Account no longer exists".to_string()))?;
+
+    let access_token = jwt::issue_access_token(&state.config.jwt, &rotated.user_id, &email)?;
+    Ok(Json(RefreshResponse {
+        access_token,
+        refresh_token: rotated.refresh_token,
+    }))
+}
+
+/// 登出：目前的 access token 列入黑名單
+pub async fn logout(
+    State(state): State<AppState>,
+    user: AuthUser,
+    payload: Option<Json<LogoutRequest>>,
+) -> AppResult<Json<serde_json::Value>> {
+    extractor::blacklist_token(&state, &user.token, user.expires_at).await?;
+
+    if let Some(refresh_token) = payload.and_then(|Json(body)| body.refresh_token) {
+        refresh::revoke_family_of(&state.pool, &user.user_id, refresh_token.trim()).await?;
+    }
+
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+// ==================== HELPERS ====================
+
+/// 信箱一律以去除空白、小寫的形式儲存與比對
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// 取得用戶目前有效的訂閱方案 (含寬限期)，沒有有效訂閱時為 `free`
+pub async fn current_tier(pool: &MySqlPool, user_id: &str) -> AppResult<String> {
+    let tier: Option<String> = sqlx::query_scalar(&format!(
+        r#"
+        SELECT tier FROM subscriptions
+        WHERE user_id = ? AND {}
+        ORDER BY started_at DESC
+        LIMIT 1
+        "#,
+        subscription::CURRENT_SUBSCRIPTION
+    ))
+    .bind(user_id)
+    .bind(Utc::now())
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(tier.unwrap_or_else(|| "free".to_string()))
+}
+
+async fn issue_tokens(state: &AppState, user: AuthUserResponse) -> AppResult<AuthResponse> {
+    let jwt_config = &state.config.jwt;
+    let access_token = jwt::issue_access_token(jwt_config, &user.id, &user.email)?;
+    let refresh_token =
+        refresh::create_family(&state.pool, &user.id, jwt_config.refresh_expires_in).await?;
+
+    Ok(AuthResponse {
+        user,
+        access_token,
+        refresh_token,
+    })
+}
+
+fn invalid_credentials() -> AppError {
+    AppError::Unauthorized("Invalid email or password".to_string())
+}
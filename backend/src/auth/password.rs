@@ -0,0 +1,37 @@
+// src/auth/password.rs
+
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+/// 以 Argon2id 雜湊密碼 (PHC 字串格式)
+pub fn hash_password(password: &str) -> anyhow::Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| anyhow::anyhow!("failed to hash password: {}", e))?;
+    Ok(hash.to_string())
+}
+
+/// 驗證密碼；雜湊格式錯誤時視為不相符
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    match PasswordHash::new(password_hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify() {
+        let hash = hash_password("password123").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("password123", &hash));
+        assert!(!verify_password("password124", &hash));
+        assert!(!verify_password("password123", "not-a-hash"));
+    }
+}
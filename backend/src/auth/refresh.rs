@@ -0,0 +1,179 @@
+// src/auth/refresh.rs
+
+use crate::error::{AppError, AppResult};
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+
+/// 輪替後的新 refresh token
+pub struct RotatedToken {
+    pub user_id: String,
+    pub refresh_token: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct RefreshTokenRow {
+    id: String,
+    user_id: String,
+    family_id: String,
+    expires_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+/// 登入時建立新的 token family，回傳明文 refresh token (資料庫只存雜湊)
+pub async fn create_family(pool: &MySqlPool, user_id: &str, ttl_secs: i64) -> AppResult<String> {
+    let family_id = uuid::Uuid::new_v4().to_string();
+    let token = generate_token();
+
+    insert_token(pool, user_id, &family_id, &token, ttl_secs).await?;
+    Ok(token)
+}
+
+/// 以 refresh token 換發新 token
+///
+/// 每個 token 只能使用一次；已使用過的 token 再次出現代表可能外洩，整個 family 立即撤銷。
+pub async fn rotate(pool: &MySqlPool, token: &str, ttl_secs: i64) -> AppResult<RotatedToken> {
+    let mut tx = pool.begin().await?;
+
+    let row: Option<RefreshTokenRow> = sqlx::query_as(
+        r#"
+        SELECT id, user_id, family_id, expires_at, used_at, revoked_at
+        FROM refresh_tokens
+        WHERE token_hash = ?
+        FOR UPDATE
+        "#,
+    )
+    .bind(hash_token(token))
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let row = row.ok_or_else(invalid_refresh_token)?;
+
+    if row.revoked_at.is_some() {
+        return Err(invalid_refresh_token());
+    }
+
+    if row.used_at.is_some() {
+        log::warn!(
+            "refresh token reuse detected for user {}, revoking family {}",
+            row.user_id,
+            row.family_id
+        );
+        revoke_family_in(&mut tx, &row.family_id).await?;
+        tx.commit().await?;
+        return Err(invalid_refresh_token());
+    }
+
+    if row.expires_at <= Utc::now() {
+        return Err(invalid_refresh_token());
+    }
+
+    sqlx::query("UPDATE refresh_tokens SET used_at = ? WHERE id = ?")
+        .bind(Utc::now())
+        .bind(&row.id)
+        .execute(&mut *tx)
+        .await?;
+
+    let refresh_token = generate_token();
+    insert_token(&mut *tx, &row.user_id, &row.family_id, &refresh_token, ttl_secs).await?;
+
+    tx.commit().await?;
+
+    Ok(RotatedToken {
+        user_id: row.user_id,
+        refresh_token,
+    })
+}
+
+/// 撤銷 token 所屬的整個 family (僅限該用戶自己的 token)
+pub async fn revoke_family_of(pool: &MySqlPool, user_id: &str, token: &str) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE refresh_tokens
+        SET revoked_at = ?
+        WHERE revoked_at IS NULL
+          AND family_id = (
+              SELECT family_id FROM (
+                  SELECT family_id FROM refresh_tokens WHERE token_hash = ? AND user_id = ?
+              ) AS owned
+          )
+        "#,
+    )
+    .bind(Utc::now())
+    .bind(hash_token(token))
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+async fn revoke_family_in(
+    tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    family_id: &str,
+) -> AppResult<()> {
+    sqlx::query("UPDATE refresh_tokens SET revoked_at = ? WHERE family_id = ? AND revoked_at IS NULL")
+        .bind(Utc::now())
+        .bind(family_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+async fn insert_token<'e, E>(
+    executor: E,
+    user_id: &str,
+    family_id: &str,
+    token: &str,
+    ttl_secs: i64,
+) -> AppResult<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::MySql>,
+{
+    sqlx::query(
+        "INSERT INTO refresh_tokens (id, user_id, family_id, token_hash, expires_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(user_id)
+    .bind(family_id)
+    .bind(hash_token(token))
+    .bind(Utc::now() + Duration::seconds(ttl_secs))
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// 兩組 UUID v4 組成的隨機字串 (244 bits 亂數)
+fn generate_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn invalid_refresh_token() -> AppError {
+    AppError::Unauthorized("Invalid or expired refresh token".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_tokens_are_unique() {
+        let a = generate_token();
+        let b = generate_token();
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, b);
+        assert_eq!(hash_token(&a), hash_token(&a));
+        assert_ne!(hash_token(&a), hash_token(&b));
+    }
+}
@@ -1,23 +1,129 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// 支援的環境代號
+pub const ENVIRONMENTS: [&str; 4] = ["dev", "test", "pp", "prod"];
+/// `pp` / `prod` 的 JWT 密鑰最短長度
+pub const MIN_JWT_SECRET_CHARS: usize = 32;
+/// 未設定 `JWT_SECRET` 時的開發用密鑰 (`pp` / `prod` 不接受)
+const DEV_JWT_SECRET: &str = "nice-speak-dev-only-jwt-secret";
+/// 綠界測試環境的公開特店 (`dev` / `test` 未設定金流參數時使用，`pp` / `prod` 不接受)
+const ECPAY_STAGE_MERCHANT_ID: &str = "3002607";
+const ECPAY_STAGE_HASH_KEY: &str = "pwFHCqoQZGmho4w6";
+const ECPAY_STAGE_HASH_IV: &str = "EkRm7iFt8PBc7l9v";
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub app_env: String,
     pub app_name: String,
     pub app_url: String,
-    
+
+    pub server: ServerConfig,
     pub database: DatabaseConfig,
     pub jwt: JwtConfig,
     pub redis: RedisConfig,
     pub mongodb: MongoConfig,
     pub external: ExternalConfig,
+    pub trial: TrialConfig,
+    pub practice: PracticeConfig,
+    pub subscription: SubscriptionConfig,
     pub logging: LoggingConfig,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerConfig {
+    /// 監聽位址 (`SERVER_HOST`)
+    pub host: IpAddr,
+    /// 監聽埠 (`PORT`)
+    pub port: u16,
+    /// 可信任的反向代理 (`TRUSTED_PROXIES`)，只有來自這些位址的 `X-Forwarded-For` 會被採用
+    pub trusted_proxies: TrustedProxies,
+}
+
+/// 逗號分隔的 IP 或 CIDR 網段 (例如 `10.0.0.0/8, 127.0.0.1`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct TrustedProxies(Vec<(IpAddr, u8)>);
+
+impl TrustedProxies {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.0.iter().any(|(network, prefix)| match (network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => prefix_matches(&network.octets(), &ip.octets(), *prefix),
+            (IpAddr::V6(network), IpAddr::V6(ip)) => prefix_matches(&network.octets(), &ip.octets(), *prefix),
+            _ => false,
+        })
+    }
+}
+
+impl FromStr for TrustedProxies {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut networks = Vec::new();
+        for entry in value.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let invalid = || format!("invalid proxy {:?}, expected an IP or CIDR", entry);
+            let (ip, prefix) = match entry.split_once('/') {
+                Some((ip, prefix)) => (ip, Some(prefix)),
+                None => (entry, None),
+            };
+            let ip: IpAddr = ip.parse().map_err(|_| invalid())?;
+            let max_prefix = if ip.is_ipv4() { 32 } else { 128 };
+            let prefix = match prefix {
+                Some(prefix) => prefix.parse::<u8>().ok().filter(|prefix| *prefix <= max_prefix).ok_or_else(invalid)?,
+                None => max_prefix,
+            };
+            networks.push((ip, prefix));
+        }
+        Ok(Self(networks))
+    }
+}
+
+/// 兩個位址的前 `prefix` 個位元是否相同
+fn prefix_matches(network: &[u8], ip: &[u8], prefix: u8) -> bool {
+    let bytes = usize::from(prefix / 8);
+    let bits = prefix % 8;
+    network[..bytes] == ip[..bytes] && (bits == 0 || (network[bytes] ^ ip[bytes]) >> (8 - bits) == 0)
+}
+
+/// 資料庫驅動 (`DATABASE_TYPE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseDriver {
+    MySql,
+}
+
+impl DatabaseDriver {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DatabaseDriver::MySql => "mysql",
+        }
+    }
+}
+
+impl FromStr for DatabaseDriver {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "mysql" => Ok(DatabaseDriver::MySql),
+            // 查詢與遷移皆為 MySQL 語法
+            "sqlite" => Err("sqlite is not supported, queries and migrations use MySQL syntax".to_string()),
+            _ => Err("expected mysql".to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct DatabaseConfig {
-    pub db_type: String,
+    pub driver: DatabaseDriver,
+    /// 完整連線字串 (`DATABASE_URL`)，設定時取代個別的連線欄位
+    pub url: Option<String>,
     pub host: String,
     pub port: u16,
     pub database: String,
@@ -25,6 +131,8 @@ pub struct DatabaseConfig {
     pub password: String,
     pub max_connections: u32,
     pub min_connections: u32,
+    /// 取得連線的等待上限 (秒)
+    pub acquire_timeout_secs: u64,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -53,11 +161,50 @@ pub struct MongoConfig {
 pub struct ExternalConfig {
     pub stt_provider: String,
     pub stt_api_key: String,
+    pub stt_language: String,
+    pub stt_region: String,
+    pub stt_fixture_dir: String,
     pub tts_provider: String,
     pub tts_api_key: String,
+    pub tts_region: String,
+    pub tts_voice: String,
+    pub tts_voice_map: String,
+    pub tts_rate: f32,
+    pub tts_cache_dir: String,
     pub ai_provider: String,
     pub ai_api_key: String,
+    pub ai_model: String,
+    pub ai_base_url: String,
+    pub ai_max_retries: u32,
     pub payment_provider: String,
+    pub payment_merchant_id: String,
+    pub payment_hash_key: String,
+    pub payment_hash_iv: String,
+    pub payment_base_url: String,
+    pub push_provider: String,
+    pub fcm_credentials_file: String,
+    pub push_outbox_file: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrialConfig {
+    pub review_score: u32,
+    pub deny_score: u32,
+    pub ban_score: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PracticeConfig {
+    /// 練習閒置超過這段時間即視為逾時 (分鐘)
+    pub timeout_minutes: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubscriptionConfig {
+    /// 自動續訂扣款未完成時保留權益的天數
+    pub grace_period_days: i64,
+    /// 不會自動續訂的訂閱在到期前幾天發送提醒
+    pub expiry_notice_days: i64,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -67,82 +214,245 @@ pub struct LoggingConfig {
 }
 
 impl Config {
+    /// 依序疊加設定：預設值 → TOML 設定檔 → `.env.{APP_ENV}` → 程序環境變數
+    ///
+    /// TOML 設定檔由 `CONFIG_FILE` 指定 (未指定時讀取 `config/{APP_ENV}.toml`，不存在則略過)。
+    /// 所有欄位的錯誤會一起回報；`pp` / `prod` 另外檢查密鑰 (見 `validate`)。
     pub fn from_env() -> anyhow::Result<Self> {
-        let app_env = env::var("APP_ENV").unwrap_or_else(|_| "dev".to_string());
-        
-        // 根據環境載入對應的配置文件
-        let config_file = match app_env.as_str() {
-            "test" => ".env.test",
-            "pp" => ".env.pp",
-            "prod" => ".env.prod",
-            _ => ".env.dev", // 預設為 dev
+        // `.env` 是優先序最低的檔案，也可以在裡面指定 APP_ENV / CONFIG_FILE
+        let dotenv = read_env_file(".env")?.unwrap_or_default();
+        let lookup = |key: &str| {
+            env::var(key).ok().or_else(|| {
+                dotenv
+                    .iter()
+                    .rev()
+                    .find(|(name, _)| name == key)
+                    .map(|(_, value)| value.clone())
+            })
         };
-        
-        // 讀取環境變數
-        Ok(Self {
-            app_env: app_env.clone(),
-            app_name: env::var("APP_NAME").unwrap_or_else(|_| "Nice Speak".to_string()),
-            app_url: env::var("APP_URL").unwrap_or_else(|_| "http://localhost:3000".to_string()),
-            
+
+        let app_env = lookup("APP_ENV").unwrap_or_else(|| "dev".to_string());
+        if !ENVIRONMENTS.contains(&app_env.as_str()) {
+            anyhow::bail!("APP_ENV must be one of {}, got {:?}", ENVIRONMENTS.join(", "), app_env);
+        }
+        let (config_file, required) = match lookup("CONFIG_FILE") {
+            Some(path) if !path.trim().is_empty() => (PathBuf::from(path.trim()), true),
+            _ => (Path::new("config").join(format!("{}.toml", app_env)), false),
+        };
+
+        let mut sources = ConfigSources::default();
+        sources.push_layer(".env", dotenv);
+        if required || config_file.exists() {
+            let content = fs::read_to_string(&config_file)
+                .map_err(|err| anyhow::anyhow!("failed to read {}: {}", config_file.display(), err))?;
+            sources.push_layer(&config_file.display().to_string(), parse_toml(&content)?);
+        }
+
+        let env_file = format!(".env.{}", app_env);
+        if let Some(values) = read_env_file(&env_file)? {
+            sources.push_layer(&env_file, values);
+        }
+        sources.push_layer("environment", env::vars());
+
+        let config = Self::from_sources(&app_env, &sources)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 由已疊加的設定來源建立設定，回報所有無法解析的欄位
+    pub fn from_sources(app_env: &str, sources: &ConfigSources) -> Result<Self, ConfigError> {
+        let mut fields = FieldReader::new(sources);
+        // 金流參數只有 dev / test 有預設值，推播也預設寫入本機檔案
+        let sandbox = !matches!(app_env, "pp" | "prod");
+        let stage_default = |value: &'static str| if sandbox { value } else { "" };
+
+        let config = Self {
+            app_env: app_env.to_string(),
+            app_name: fields.string("APP_NAME", "Nice Speak"),
+            app_url: fields.string("APP_URL", "http://localhost:3000"),
+
+            server: ServerConfig {
+                host: fields.parse("SERVER_HOST", IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+                port: fields.parse("PORT", 3000),
+                trusted_proxies: fields.parse("TRUSTED_PROXIES", TrustedProxies::default()),
+            },
+
             database: DatabaseConfig {
-                db_type: env::var("DATABASE_TYPE").unwrap_or_else(|_| "mysql".to_string()),
-                host: env::var("MYSQL_HOST").unwrap_or_else(|_| "103.251.113.34".to_string()),
-                port: env::var("MYSQL_PORT").unwrap_or_else(|_| "31001".to_string()).parse()?,
-                database: format!("nice_speak_{}", app_env),
-                username: env::var("MYSQL_USER").unwrap_or_else(|_| "root".to_string()),
-                password: env::var("MYSQL_PASSWORD").unwrap_or_else(|_| "".to_string()),
-                max_connections: env::var("MYSQL_MAX_CONN").unwrap_or_else(|_| "20".to_string()).parse()?,
-                min_connections: env::var("MYSQL_MIN_CONN").unwrap_or_else(|_| "5".to_string()).parse()?,
+                driver: fields.parse("DATABASE_TYPE", DatabaseDriver::MySql),
+                url: fields.optional("DATABASE_URL"),
+                host: fields.string("MYSQL_HOST", "127.0.0.1"),
+                port: fields.parse("MYSQL_PORT", 3306),
+                database: fields.string("MYSQL_DATABASE", &format!("nice_speak_{}", app_env)),
+                username: fields.string("MYSQL_USER", "root"),
+                password: fields.string("MYSQL_PASSWORD", ""),
+                max_connections: fields.parse("DATABASE_MAX_CONN", 20),
+                min_connections: fields.parse("DATABASE_MIN_CONN", 5),
+                acquire_timeout_secs: fields.parse("DATABASE_ACQUIRE_TIMEOUT", 10),
             },
-            
+
             jwt: JwtConfig {
-                secret: env::var("JWT_SECRET").unwrap_or_else(|_| "your-secret-key".to_string()),
-                expires_in: env::var("JWT_EXPIRES_IN").unwrap_or_else(|_| "86400".to_string()).parse()?,
-                refresh_expires_in: env::var("JWT_REFRESH_EXPIRES_IN").unwrap_or_else(|_| "604800".to_string()).parse()?,
+                secret: fields.string("JWT_SECRET", DEV_JWT_SECRET),
+                expires_in: fields.parse("JWT_EXPIRES_IN", 86400),
+                refresh_expires_in: fields.parse("JWT_REFRESH_EXPIRES_IN", 604800),
             },
-            
+
             redis: RedisConfig {
-                host: env::var("REDIS_HOST").unwrap_or_else(|_| "103.251.113.34".to_string()),
-                port: env::var("REDIS_PORT").unwrap_or_else(|_| "31002".to_string()).parse()?,
-                password: env::var("REDIS_PASSWORD").unwrap_or_else(|_| "".to_string()),
-                db: env::var("REDIS_DB").unwrap_or_else(|_| "0".to_string()).parse()?,
-                key_prefix: format!("nice_speak:{}:", app_env),
+                host: fields.string("REDIS_HOST", "127.0.0.1"),
+                port: fields.parse("REDIS_PORT", 6379),
+                password: fields.string("REDIS_PASSWORD", ""),
+                db: fields.parse("REDIS_DB", 0),
+                key_prefix: fields.string("REDIS_KEY_PREFIX", &format!("nice_speak:{}:", app_env)),
             },
-            
+
             mongodb: MongoConfig {
-                uri: env::var("MONGODB_URI").unwrap_or_else(|_| "mongodb://admin:@103.251.113.34:31003".to_string()),
-                database: format!("nice_speak_{}", app_env),
+                uri: fields.string("MONGODB_URI", "mongodb://127.0.0.1:27017"),
+                database: fields.string("MONGODB_DATABASE", &format!("nice_speak_{}", app_env)),
             },
-            
+
             external: ExternalConfig {
-                stt_provider: env::var("STT_PROVIDER").unwrap_or_else(|_| "google".to_string()),
-                stt_api_key: env::var("STT_API_KEY").unwrap_or_else(|_| "".to_string()),
-                tts_provider: env::var("TTS_PROVIDER").unwrap_or_else(|_| "azure".to_string()),
-                tts_api_key: env::var("TTS_API_KEY").unwrap_or_else(|_| "".to_string()),
-                ai_provider: env::var("AI_PROVIDER").unwrap_or_else(|_| "openai".to_string()),
-                ai_api_key: env::var("AI_API_KEY").unwrap_or_else(|_| "".to_string()),
-                payment_provider: env::var("PAYMENT_PROVIDER").unwrap_or_else(|_| "ecpay".to_string()),
+                stt_provider: fields.string("STT_PROVIDER", "google"),
+                stt_api_key: fields.string("STT_API_KEY", ""),
+                stt_language: fields.string("STT_LANGUAGE", "en-US"),
+                stt_region: fields.string("STT_REGION", "eastasia"),
+                stt_fixture_dir: fields.string("STT_FIXTURE_DIR", ""),
+                tts_provider: fields.string("TTS_PROVIDER", "azure"),
+                tts_api_key: fields.string("TTS_API_KEY", ""),
+                tts_region: fields.string("TTS_REGION", "eastasia"),
+                tts_voice: fields.string("TTS_VOICE", "en-US-JennyNeural"),
+                tts_voice_map: fields.string("TTS_VOICE_MAP", ""),
+                tts_rate: fields.parse("TTS_RATE", 1.0),
+                tts_cache_dir: fields.string("TTS_CACHE_DIR", "media/tts"),
+                ai_provider: fields.string("AI_PROVIDER", "openai"),
+                ai_api_key: fields.string("AI_API_KEY", ""),
+                ai_model: fields.string("AI_MODEL", "gpt-4o-mini"),
+                ai_base_url: fields.string("AI_BASE_URL", "https://api.openai.com/v1"),
+                ai_max_retries: fields.parse("AI_MAX_RETRIES", 2),
+                payment_provider: fields.string("PAYMENT_PROVIDER", "ecpay"),
+                payment_merchant_id: fields.string("PAYMENT_MERCHANT_ID", stage_default(ECPAY_STAGE_MERCHANT_ID)),
+                payment_hash_key: fields.string("PAYMENT_HASH_KEY", stage_default(ECPAY_STAGE_HASH_KEY)),
+                payment_hash_iv: fields.string("PAYMENT_HASH_IV", stage_default(ECPAY_STAGE_HASH_IV)),
+                payment_base_url: fields.string("PAYMENT_BASE_URL", "https://payment-stage.ecpay.com.tw"),
+                push_provider: fields.string("PUSH_PROVIDER", if sandbox { "file" } else { "fcm" }),
+                fcm_credentials_file: fields.string("FCM_CREDENTIALS_FILE", ""),
+                push_outbox_file: fields.string("PUSH_OUTBOX_FILE", "push_outbox.jsonl"),
+            },
+
+            trial: TrialConfig {
+                review_score: fields.parse("TRIAL_REVIEW_SCORE", 30),
+                deny_score: fields.parse("TRIAL_DENY_SCORE", 60),
+                ban_score: fields.parse("TRIAL_BAN_SCORE", 120),
+            },
+
+            practice: PracticeConfig {
+                timeout_minutes: fields.parse("PRACTICE_TIMEOUT_MINUTES", 30),
             },
-            
+
+            subscription: SubscriptionConfig {
+                grace_period_days: fields.parse("SUBSCRIPTION_GRACE_DAYS", 3),
+                expiry_notice_days: fields.parse("SUBSCRIPTION_EXPIRY_NOTICE_DAYS", 3),
+            },
+
             logging: LoggingConfig {
-                level: env::var("LOG_LEVEL").unwrap_or_else(|_| "debug".to_string()),
-                format: env::var("LOG_FORMAT").unwrap_or_else(|_| "json".to_string()),
+                level: fields.string("LOG_LEVEL", "debug"),
+                format: fields.string("LOG_FORMAT", "json"),
             },
-        })
+        };
+        fields.finish()?;
+        Ok(config)
     }
-    
-    /// 取得 MySQL 連線字串
-    pub fn mysql_url(&self) -> String {
-        format!(
-            "mysql://{}:{}@{}:{}/{}",
-            self.database.username,
-            self.database.password,
-            self.database.host,
-            self.database.port,
-            self.database.database
-        )
+
+    /// 啟動前檢查；`pp` / `prod` 拒絕範本佔位字、空白的資料庫密碼與過短的 JWT 密鑰
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+        if self.jwt.secret.trim().is_empty() {
+            problems.push("JWT_SECRET must not be empty".to_string());
+        }
+        if self.database.min_connections > self.database.max_connections {
+            problems.push(format!(
+                "DATABASE_MIN_CONN ({}) must not exceed DATABASE_MAX_CONN ({})",
+                self.database.min_connections, self.database.max_connections
+            ));
+        }
+        if self.jwt.expires_in <= 0 || self.jwt.refresh_expires_in <= 0 {
+            problems.push("JWT_EXPIRES_IN and JWT_REFRESH_EXPIRES_IN must be positive".to_string());
+        }
+        let services = [
+            ("APP_URL", &self.app_url),
+            ("AI_BASE_URL", &self.external.ai_base_url),
+            ("PAYMENT_BASE_URL", &self.external.payment_base_url),
+        ];
+        for (key, url) in services {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                problems.push(format!("{} must be an http(s) URL, got {:?}", key, url));
+            }
+        }
+        if let Some(url) = &self.database.url {
+            let scheme = format!("{}://", self.database.driver.as_str());
+            if !url.starts_with(&scheme) {
+                problems.push(format!(
+                    "DATABASE_URL must start with {} to match DATABASE_TYPE",
+                    scheme
+                ));
+            }
+        }
+
+        if matches!(self.app_env.as_str(), "pp" | "prod") {
+            if is_placeholder(&self.jwt.secret) || self.jwt.secret == DEV_JWT_SECRET {
+                problems.push("JWT_SECRET is a placeholder, set a generated secret".to_string());
+            } else if self.jwt.secret.chars().count() < MIN_JWT_SECRET_CHARS {
+                problems.push(format!("JWT_SECRET must be at least {} characters", MIN_JWT_SECRET_CHARS));
+            }
+            if self.database.url.is_none() && self.database.password.is_empty() {
+                problems.push("MYSQL_PASSWORD must not be empty".to_string());
+            }
+            if mongo_password_is_empty(&self.mongodb.uri) {
+                problems.push("MONGODB_URI has an empty password".to_string());
+            }
+            let secrets = [
+                ("MYSQL_PASSWORD", &self.database.password),
+                ("REDIS_PASSWORD", &self.redis.password),
+                ("MONGODB_URI", &self.mongodb.uri),
+                ("STT_API_KEY", &self.external.stt_api_key),
+                ("TTS_API_KEY", &self.external.tts_api_key),
+                ("AI_API_KEY", &self.external.ai_api_key),
+                ("PAYMENT_MERCHANT_ID", &self.external.payment_merchant_id),
+                ("PAYMENT_HASH_KEY", &self.external.payment_hash_key),
+                ("PAYMENT_HASH_IV", &self.external.payment_hash_iv),
+            ];
+            for (key, value) in secrets {
+                if is_placeholder(value) {
+                    problems.push(format!("{} is still the template placeholder", key));
+                }
+            }
+            let payment = [
+                ("PAYMENT_MERCHANT_ID", &self.external.payment_merchant_id, ECPAY_STAGE_MERCHANT_ID),
+                ("PAYMENT_HASH_KEY", &self.external.payment_hash_key, ECPAY_STAGE_HASH_KEY),
+                ("PAYMENT_HASH_IV", &self.external.payment_hash_iv, ECPAY_STAGE_HASH_IV),
+            ];
+            for (key, value, stage) in payment {
+                if value.trim().is_empty() {
+                    problems.push(format!("{} must be set", key));
+                } else if value == stage {
+                    problems.push(format!("{} is the ECPay stage credential, set the merchant's own", key));
+                }
+            }
+            if self.external.push_provider == "fcm" && self.external.fcm_credentials_file.trim().is_empty() {
+                problems.push("FCM_CREDENTIALS_FILE must be set when PUSH_PROVIDER is fcm".to_string());
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError(problems))
+        }
     }
-    
+
+    /// 服務監聽位址
+    pub fn listen_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.server.host, self.server.port)
+    }
+
     /// 取得 Redis 連線字串
     pub fn redis_url(&self) -> String {
         if self.redis.password.is_empty() {
@@ -173,6 +483,178 @@ pub fn get_env_prefix(env: &str) -> String {
     }
 }
 
+/// 設定錯誤 (每個欄位一行)
+#[derive(Debug, thiserror::Error)]
+#[error("invalid configuration:\n  - {}", .0.join("\n  - "))]
+pub struct ConfigError(pub Vec<String>);
+
+/// 疊加的設定來源，後加入的來源覆蓋先前的值
+#[derive(Debug, Default)]
+pub struct ConfigSources {
+    /// key → (值, 來源名稱)
+    values: HashMap<String, (String, String)>,
+}
+
+impl ConfigSources {
+    pub fn push_layer(&mut self, source: &str, values: impl IntoIterator<Item = (String, String)>) {
+        for (key, value) in values {
+            self.values.insert(key, (value, source.to_string()));
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|(value, _)| value.as_str())
+    }
+
+    /// 設定值的來源，錯誤訊息用
+    pub fn source(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|(_, source)| source.as_str())
+    }
+}
+
+/// 讀取欄位並收集解析錯誤，解析失敗的欄位先以預設值代替
+struct FieldReader<'a> {
+    sources: &'a ConfigSources,
+    problems: Vec<String>,
+}
+
+impl<'a> FieldReader<'a> {
+    fn new(sources: &'a ConfigSources) -> Self {
+        Self {
+            sources,
+            problems: Vec::new(),
+        }
+    }
+
+    fn string(&self, key: &str, default: &str) -> String {
+        self.sources.get(key).unwrap_or(default).to_string()
+    }
+
+    /// 未設定或為空字串時回傳 None
+    fn optional(&self, key: &str) -> Option<String> {
+        self.sources
+            .get(key)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    }
+
+    fn parse<T>(&mut self, key: &str, default: T) -> T
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some(value) = self.sources.get(key) else {
+            return default;
+        };
+        match value.trim().parse() {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                self.problems.push(format!(
+                    "{}={:?} (from {}): {}",
+                    key,
+                    value,
+                    self.sources.source(key).unwrap_or("default"),
+                    err
+                ));
+                default
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), ConfigError> {
+        if self.problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError(self.problems))
+        }
+    }
+}
+
+/// 解析 TOML 設定檔，巢狀表格以底線串接成環境變數名稱 (`[mysql] host` → `MYSQL_HOST`)
+pub fn parse_toml(content: &str) -> anyhow::Result<Vec<(String, String)>> {
+    fn flatten(prefix: &str, table: &toml::Table, values: &mut Vec<(String, String)>) -> anyhow::Result<()> {
+        for (key, value) in table {
+            let key = if prefix.is_empty() {
+                key.to_uppercase()
+            } else {
+                format!("{}_{}", prefix, key.to_uppercase())
+            };
+            match value {
+                toml::Value::Table(table) => flatten(&key, table, values)?,
+                toml::Value::String(text) => values.push((key, text.clone())),
+                toml::Value::Array(_) => anyhow::bail!("{}: arrays are not supported", key),
+                other => values.push((key, other.to_string())),
+            }
+        }
+        Ok(())
+    }
+
+    let table: toml::Table = content.parse()?;
+    let mut values = Vec::new();
+    flatten("", &table, &mut values)?;
+    Ok(values)
+}
+
+/// 解析 `.env` 格式 (`KEY=value`，支援 `export`、引號與 `#` 註解)
+pub fn parse_env_file(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut values = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected KEY=value", index + 1))?;
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("line {}: invalid key {:?}", index + 1, key));
+        }
+
+        let value = value.trim();
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..]
+                .find(quote)
+                .map(|end| &value[1..end + 1])
+                .ok_or_else(|| format!("line {}: unterminated quote for {}", index + 1, key))?,
+            _ => value.split(" #").next().unwrap_or_default().trim_end(),
+        };
+        values.push((key.to_string(), value.to_string()));
+    }
+    Ok(values)
+}
+
+/// `.env.example` 範本中的佔位字 (例如 `your_password_here`、`YOUR_SECURE_PASSWORD_HERE`)
+fn is_placeholder(value: &str) -> bool {
+    let value = value.to_lowercase();
+    ["your_", "your-", "change-in-production", "changeme", "change_me", "/path/to/"]
+        .iter()
+        .any(|marker| value.contains(marker))
+}
+
+/// 讀取 `.env` 格式的檔案，不存在時回傳 None
+fn read_env_file(path: &str) -> anyhow::Result<Option<Vec<(String, String)>>> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path).map_err(|err| anyhow::anyhow!("failed to read {}: {}", path, err))?;
+    let values = parse_env_file(&content).map_err(|err| anyhow::anyhow!("{}: {}", path, err))?;
+    Ok(Some(values))
+}
+
+/// MongoDB 連線字串有帳號但密碼為空 (`mongodb://admin:@host`)
+fn mongo_password_is_empty(uri: &str) -> bool {
+    let rest = uri.split_once("://").map_or(uri, |(_, rest)| rest);
+    match rest.split_once('@') {
+        Some((credentials, _)) => credentials
+            .split_once(':')
+            .is_some_and(|(_, password)| password.is_empty()),
+        None => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -196,4 +678,216 @@ mod tests {
         let prefix = format!("nice_speak:{}:", env);
         assert_eq!(prefix, "nice_speak:test:");
     }
+
+    fn sources(layers: &[(&str, &[(&str, &str)])]) -> ConfigSources {
+        let mut sources = ConfigSources::default();
+        for (name, values) in layers {
+            sources.push_layer(
+                name,
+                values.iter().map(|(key, value)| (key.to_string(), value.to_string())),
+            );
+        }
+        sources
+    }
+
+    const PROD_SECRETS: &[(&str, &str)] = &[
+        ("JWT_SECRET", "3f1c9a7e2b8d4f60a5e1c7b9d2f4a6e8"),
+        ("MYSQL_PASSWORD", "s3cure"),
+        ("PAYMENT_MERCHANT_ID", "2000132"),
+        ("PAYMENT_HASH_KEY", "prodHashKey12345"),
+        ("PAYMENT_HASH_IV", "prodHashIv123456"),
+        ("FCM_CREDENTIALS_FILE", "/etc/nice_speak/fcm.json"),
+    ];
+
+    #[test]
+    fn test_later_layers_override() {
+        let sources = sources(&[
+            (".env", &[("MYSQL_PORT", "3308"), ("MYSQL_USER", "shared")]),
+            ("config/dev.toml", &[("MYSQL_HOST", "toml-host"), ("MYSQL_PORT", "3307")]),
+            (".env.dev", &[("MYSQL_HOST", "dotenv-host")]),
+            ("environment", &[("REDIS_KEY_PREFIX", "custom:")]),
+        ]);
+        let config = Config::from_sources("dev", &sources).unwrap();
+        assert_eq!(config.database.host, "dotenv-host");
+        assert_eq!(config.database.port, 3307);
+        assert_eq!(config.database.username, "shared");
+        assert_eq!(config.database.database, "nice_speak_dev");
+        assert_eq!(config.redis.key_prefix, "custom:");
+        assert_eq!(config.redis.host, "127.0.0.1");
+    }
+
+    #[test]
+    fn test_reports_every_invalid_field() {
+        let sources = sources(&[
+            (".env.test", &[("MYSQL_PORT", "abc")]),
+            ("environment", &[("TTS_RATE", "fast")]),
+        ]);
+        let err = Config::from_sources("test", &sources).unwrap_err();
+        assert_eq!(err.0.len(), 2);
+        assert!(err.0[0].starts_with("MYSQL_PORT=\"abc\" (from .env.test)"));
+        assert!(err.0[1].starts_with("TTS_RATE=\"fast\" (from environment)"));
+    }
+
+    #[test]
+    fn test_prod_rejects_placeholders() {
+        let config = Config::from_sources("prod", &ConfigSources::default()).unwrap();
+        let err = config.validate().unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("JWT_SECRET is a placeholder"));
+        assert!(message.contains("MYSQL_PASSWORD must not be empty"));
+        assert!(message.contains("PAYMENT_MERCHANT_ID must be set"));
+        assert!(message.contains("PAYMENT_HASH_IV must be set"));
+
+        let placeholders = sources(&[
+            ("config/pp.toml", PROD_SECRETS),
+            (
+                ".env.pp",
+                &[
+                    ("JWT_SECRET", "short-key"),
+                    ("MONGODB_URI", "mongodb://admin:YOUR_PASSWORD@db:27017"),
+                ],
+            ),
+        ]);
+        let err = Config::from_sources("pp", &placeholders)
+            .unwrap()
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            err.0,
+            vec![
+                format!("JWT_SECRET must be at least {} characters", MIN_JWT_SECRET_CHARS),
+                "MONGODB_URI is still the template placeholder".to_string(),
+            ]
+        );
+
+        let config = Config::from_sources("prod", &sources(&[("environment", PROD_SECRETS)])).unwrap();
+        assert!(config.validate().is_ok());
+        let stage = sources(&[
+            ("config/pp.toml", PROD_SECRETS),
+            ("environment", &[("PAYMENT_HASH_KEY", ECPAY_STAGE_HASH_KEY)]),
+        ]);
+        let err = Config::from_sources("pp", &stage).unwrap().validate().unwrap_err();
+        assert_eq!(
+            err.0,
+            vec!["PAYMENT_HASH_KEY is the ECPay stage credential, set the merchant's own".to_string()]
+        );
+        // 開發環境可以使用預設值
+        assert!(Config::from_sources("dev", &ConfigSources::default()).unwrap().validate().is_ok());
+    }
+
+    #[test]
+    fn test_push_provider_defaults() {
+        let dev = Config::from_sources("dev", &ConfigSources::default()).unwrap();
+        assert_eq!(dev.external.push_provider, "file");
+
+        let prod = Config::from_sources("prod", &ConfigSources::default()).unwrap();
+        assert_eq!(prod.external.push_provider, "fcm");
+        let missing = sources(&[
+            ("environment", PROD_SECRETS),
+            ("config/prod.toml", &[("FCM_CREDENTIALS_FILE", "")]),
+        ]);
+        let err = Config::from_sources("prod", &missing).unwrap().validate().unwrap_err();
+        assert_eq!(err.0, vec!["FCM_CREDENTIALS_FILE must be set when PUSH_PROVIDER is fcm".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_env_file() {
+        let values = parse_env_file(
+            "# comment\nAPP_NAME=\"Nice Speak Dev\"\nexport MYSQL_PORT=31001 # jump host\nREDIS_PASSWORD=\nTTS_VOICE_MAP='Tech Lead=en-US-GuyNeural'\n",
+        )
+        .unwrap();
+        assert_eq!(
+            values,
+            vec![
+                ("APP_NAME".to_string(), "Nice Speak Dev".to_string()),
+                ("MYSQL_PORT".to_string(), "31001".to_string()),
+                ("REDIS_PASSWORD".to_string(), "".to_string()),
+                ("TTS_VOICE_MAP".to_string(), "Tech Lead=en-US-GuyNeural".to_string()),
+            ]
+        );
+        assert!(parse_env_file("NOT A LINE").is_err());
+        assert!(parse_env_file("JWT_SECRET=\"open").is_err());
+    }
+
+    #[test]
+    fn test_parse_toml_flattens_tables() {
+        let values = parse_toml("app_name = \"Nice Speak\"\n[mysql]\nport = 3307\n[jwt]\nsecret = \"abc\"\n").unwrap();
+        let values: HashMap<_, _> = values.into_iter().collect();
+        assert_eq!(values["APP_NAME"], "Nice Speak");
+        assert_eq!(values["MYSQL_PORT"], "3307");
+        assert_eq!(values["JWT_SECRET"], "abc");
+        assert!(parse_toml("[mysql]\nhosts = [\"a\"]").is_err());
+    }
+
+    #[test]
+    fn test_server_and_database_settings() {
+        let config = Config::from_sources("dev", &ConfigSources::default()).unwrap();
+        assert_eq!(config.listen_addr().to_string(), "0.0.0.0:3000");
+        assert_eq!(config.database.driver, DatabaseDriver::MySql);
+        assert_eq!(config.database.url, None);
+
+        let custom = sources(&[(
+            "environment",
+            &[
+                ("SERVER_HOST", "127.0.0.1"),
+                ("PORT", "8080"),
+                ("DATABASE_TYPE", "MySQL"),
+                ("DATABASE_URL", " "),
+            ],
+        )]);
+        let config = Config::from_sources("test", &custom).unwrap();
+        assert_eq!(config.listen_addr().to_string(), "127.0.0.1:8080");
+        assert_eq!(config.database.driver, DatabaseDriver::MySql);
+        assert_eq!(config.database.url, None);
+
+        let invalid = sources(&[(
+            "environment",
+            &[("DATABASE_TYPE", "postgres"), ("SERVER_HOST", "localhost")],
+        )]);
+        let err = Config::from_sources("dev", &invalid).unwrap_err();
+        assert!(err.0[0].starts_with("SERVER_HOST=\"localhost\""));
+        assert!(err.0[1].contains("expected mysql"));
+
+        let sqlite = sources(&[("environment", &[("DATABASE_TYPE", "sqlite")])]);
+        let err = Config::from_sources("dev", &sqlite).unwrap_err();
+        assert!(err.0[0].contains("sqlite is not supported"));
+    }
+
+    #[test]
+    fn test_trusted_proxies() {
+        let proxies: TrustedProxies = "10.0.0.0/8, 127.0.0.1,2001:db8::/32".parse().unwrap();
+        assert!(proxies.contains("10.20.30.40".parse().unwrap()));
+        assert!(proxies.contains("::ffff:10.0.0.1".parse().unwrap()));
+        assert!(proxies.contains("127.0.0.1".parse().unwrap()));
+        assert!(!proxies.contains("127.0.0.2".parse().unwrap()));
+        assert!(proxies.contains("2001:db8:1::1".parse().unwrap()));
+        assert!(!proxies.contains("11.0.0.1".parse().unwrap()));
+        assert!(!TrustedProxies::default().contains("127.0.0.1".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<TrustedProxies>().is_err());
+        assert!("proxy.local".parse::<TrustedProxies>().is_err());
+    }
+
+    #[test]
+    fn test_database_url_must_match_driver() {
+        let mismatched = sources(&[(
+            "environment",
+            &[("DATABASE_URL", "sqlite://local.db"), ("AI_BASE_URL", "api.openai.com")],
+        )]);
+        let err = Config::from_sources("dev", &mismatched).unwrap().validate().unwrap_err();
+        assert_eq!(
+            err.0,
+            vec![
+                "AI_BASE_URL must be an http(s) URL, got \"api.openai.com\"".to_string(),
+                "DATABASE_URL must start with mysql:// to match DATABASE_TYPE".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mongo_password_is_empty() {
+        assert!(mongo_password_is_empty("mongodb://admin:@103.251.113.34:31003"));
+        assert!(!mongo_password_is_empty("mongodb://admin:secret@db:27017"));
+        assert!(!mongo_password_is_empty("mongodb://127.0.0.1:27017"));
+    }
 }
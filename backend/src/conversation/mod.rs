@@ -0,0 +1,7 @@
+// src/conversation/mod.rs
+
+mod protocol;
+mod ws;
+
+pub use protocol::{ClientMessage, ServerMessage};
+pub use ws::practice_ws;
@@ -0,0 +1,115 @@
+// src/conversation/protocol.rs
+
+use crate::practice::{Dialogue, PracticeSummary};
+use crate::evaluation::Evaluation;
+use serde::{Deserialize, Serialize};
+
+/// 客戶端 → 伺服器 (文字訊框，格式見 Document/API.md「WebSocket API」)
+///
+/// 音訊也可直接以二進位訊框傳送，視為目前這一輪的回答。
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// 第一個訊框必須是 auth；帶 `practice_id` 表示斷線後恢復該次練習
+    Auth {
+        token: String,
+        #[serde(default)]
+        practice_id: Option<String>,
+    },
+    /// 開始新的情境練習
+    Start { scenario_id: String },
+    /// 一輪回答的語音 (base64)
+    Audio {
+        audio: String,
+        dialogue_id: String,
+        /// wav (預設), ogg_opus, webm_opus
+        #[serde(default)]
+        format: Option<String>,
+        /// 客戶端已辨識的文字 (選填)
+        #[serde(default)]
+        transcript: Option<String>,
+    },
+    Ping,
+}
+
+/// 伺服器 → 客戶端
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    AuthOk {
+        user_id: String,
+    },
+    /// 練習已建立或已恢復
+    Session {
+        practice_id: String,
+        scenario_id: String,
+        total_dialogues: usize,
+        completed_dialogues: usize,
+    },
+    Evaluation {
+        dialogue_id: String,
+        #[serde(flatten)]
+        evaluation: Evaluation,
+    },
+    NextDialogue {
+        dialogue: Dialogue,
+    },
+    Completed {
+        #[serde(flatten)]
+        summary: PracticeSummary,
+    },
+    Error {
+        code: String,
+        message: String,
+    },
+    Pong,
+}
+
+impl ServerMessage {
+    pub fn error(code: &str, message: impl Into<String>) -> Self {
+        ServerMessage::Error {
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_client_messages() {
+        let auth: ClientMessage =
+            serde_json::from_str(r#"{"type":"auth","token":"jwt_token"}"#).unwrap();
+        assert!(matches!(auth, ClientMessage::Auth { practice_id: None, .. }));
+
+        let audio: ClientMessage = serde_json::from_str(
+            r#"{"type":"audio","audio":"AAAA","dialogue_id":"d1"}"#,
+        )
+        .unwrap();
+        assert!(matches!(audio, ClientMessage::Audio { transcript: None, .. }));
+    }
+
+    #[test]
+    fn test_evaluation_matches_documented_shape() {
+        let message = ServerMessage::Evaluation {
+            dialogue_id: "d1".to_string(),
+            evaluation: Evaluation {
+                pronunciation: 28,
+                grammar: 29,
+                vocabulary: 18,
+                fluency: 17,
+                total: 92,
+                feedback: "Great".to_string(),
+                suggestions: Vec::new(),
+                errors: Vec::new(),
+            },
+        };
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["type"], "evaluation");
+        assert_eq!(json["dialogue_id"], "d1");
+        assert_eq!(json["pronunciation"], 28);
+        assert_eq!(json["total"], 92);
+    }
+}
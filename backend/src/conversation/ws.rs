@@ -0,0 +1,234 @@
+// src/conversation/ws.rs
+
+use super::protocol::{ClientMessage, ServerMessage};
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::practice::{
+    complete_practice, current_dialogue, decode_audio, load_session, parse_audio_format,
+    start_practice, submit_turn, PracticeSession, MAX_AUDIO_BYTES,
+};
+use crate::speech::AudioFormat;
+use crate::state::AppState;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use std::time::{Duration, Instant};
+
+/// 連線後需在這段時間內送出 auth
+const AUTH_TIMEOUT_SECS: u64 = 10;
+/// 伺服器送出 Ping 的間隔
+const HEARTBEAT_INTERVAL_SECS: u64 = 20;
+/// 超過這段時間沒有收到任何訊框 (含 Pong) 即斷線
+const IDLE_TIMEOUT_SECS: u64 = 60;
+
+/// `GET /ws/practice` 情境練習 WebSocket
+///
+/// 流程：auth → start (或 auth 時帶 practice_id 恢復) → 每輪 audio → evaluation + next_dialogue，
+/// 最後一輪作答後回傳 completed。斷線重連時以 practice_id 從下一個未作答的對話繼續。
+pub async fn practice_ws(State(state): State<AppState>, ws: WebSocketUpgrade) -> Response {
+    // base64 約為原始大小的 4/3，再預留 JSON 欄位的空間
+    ws.max_message_size(MAX_AUDIO_BYTES * 2)
+        .on_upgrade(move |socket| async move {
+            let mut connection = Connection {
+                state,
+                socket,
+                session: None,
+            };
+            connection.run().await;
+        })
+}
+
+struct Connection {
+    state: AppState,
+    socket: WebSocket,
+    session: Option<PracticeSession>,
+}
+
+impl Connection {
+    async fn run(&mut self) {
+        let user = match self.authenticate().await {
+            Ok(user) => user,
+            Err(err) => {
+                let _ = self.send_error(&err).await;
+                let _ = self.socket.send(Message::Close(None)).await;
+                return;
+            }
+        };
+
+        let mut heartbeat = tokio::time::interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
+        heartbeat.tick().await;
+        let mut last_seen = Instant::now();
+
+        loop {
+            tokio::select! {
+                frame = self.socket.recv() => {
+                    let frame = match frame {
+                        Some(Ok(frame)) => frame,
+                        _ => break,
+                    };
+                    last_seen = Instant::now();
+
+                    if chrono::Utc::now().timestamp() >= user.expires_at {
+                        let err = AppError::Unauthorized("Token expired, reconnect with practice_id".to_string());
+                        let _ = self.send_error(&err).await;
+                        break;
+                    }
+
+                    let result = match frame {
+                        Message::Text(text) => self.handle_text(&user, &text).await,
+                        Message::Binary(audio) => {
+                            self.handle_audio(None, audio, AudioFormat::default(), None).await
+                        }
+                        Message::Close(_) => break,
+                        // Ping 由 axum 自動回覆 Pong
+                        Message::Ping(_) | Message::Pong(_) => Ok(()),
+                    };
+                    if let Err(err) = result {
+                        if self.send_error(&err).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                _ = heartbeat.tick() => {
+                    if last_seen.elapsed() > Duration::from_secs(IDLE_TIMEOUT_SECS) {
+                        log::info!("practice socket for user {} timed out", user.user_id);
+                        break;
+                    }
+                    if self.socket.send(Message::Ping(Vec::new())).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let _ = self.socket.send(Message::Close(None)).await;
+    }
+
+    /// 第一個訊框必須是 auth
+    async fn authenticate(&mut self) -> AppResult<AuthUser> {
+        let first = tokio::time::timeout(Duration::from_secs(AUTH_TIMEOUT_SECS), self.socket.recv())
+            .await
+            .map_err(|_| AppError::Unauthorized("Authentication timed out".to_string()))?;
+
+        let (token, practice_id) = match first {
+            Some(Ok(Message::Text(text))) => match serde_json::from_str(&text) {
+                Ok(ClientMessage::Auth { token, practice_id }) => (token, practice_id),
+                _ => return Err(AppError::Unauthorized("First message must be auth".to_string())),
+            },
+            _ => return Err(AppError::Unauthorized("First message must be auth".to_string())),
+        };
+
+        let user = AuthUser::from_token(&self.state, &token).await?;
+        self.send(&ServerMessage::AuthOk {
+            user_id: user.user_id.clone(),
+        })
+        .await?;
+
+        if let Some(practice_id) = practice_id {
+            let session = load_session(&self.state, &user.user_id, &practice_id).await?;
+            self.announce(session).await?;
+        }
+
+        Ok(user)
+    }
+
+    async fn handle_text(&mut self, user: &AuthUser, text: &str) -> AppResult<()> {
+        let message: ClientMessage = serde_json::from_str(text)
+            .map_err(|err| AppError::Validation(format!("Invalid message: {}", err)))?;
+
+        match message {
+            ClientMessage::Auth { .. } => Err(AppError::Conflict("Already authenticated".to_string())),
+            ClientMessage::Start { scenario_id } => {
+                let session = start_practice(&self.state, &user.user_id, scenario_id.trim()).await?;
+                self.announce(session).await
+            }
+            ClientMessage::Audio {
+                audio,
+                dialogue_id,
+                format,
+                transcript,
+            } => {
+                let format = parse_audio_format(format.as_deref())?;
+                let audio = decode_audio(&audio)?;
+                self.handle_audio(Some(dialogue_id), audio, format, transcript).await
+            }
+            ClientMessage::Ping => self.send(&ServerMessage::Pong).await,
+        }
+    }
+
+    /// 辨識並評分目前這一輪，再推送下一句對話；`dialogue_id` 為 None 時 (二進位訊框) 視為目前這一輪
+    async fn handle_audio(
+        &mut self,
+        dialogue_id: Option<String>,
+        audio: Vec<u8>,
+        format: AudioFormat,
+        client_transcript: Option<String>,
+    ) -> AppResult<()> {
+        let session = self
+            .session
+            .as_mut()
+            .ok_or_else(|| AppError::Validation("No practice in progress, send start first".to_string()))?;
+        let turn = submit_turn(
+            &self.state,
+            session,
+            dialogue_id.as_deref(),
+            &audio,
+            format,
+            client_transcript,
+        )
+        .await?;
+
+        self.send(&ServerMessage::Evaluation {
+            dialogue_id: turn.dialogue_id,
+            evaluation: turn.evaluation,
+        })
+        .await?;
+        self.advance().await
+    }
+
+    /// 回報練習進度並推送目前這一輪
+    async fn announce(&mut self, session: PracticeSession) -> AppResult<()> {
+        self.send(&ServerMessage::Session {
+            practice_id: session.practice_id.clone(),
+            scenario_id: session.scenario_id.clone(),
+            total_dialogues: session.dialogues.len(),
+            completed_dialogues: session.answered,
+        })
+        .await?;
+        self.session = Some(session);
+        self.advance().await
+    }
+
+    /// 還有對話就推送 next_dialogue，否則結算練習
+    async fn advance(&mut self) -> AppResult<()> {
+        let Some(session) = self.session.as_mut() else {
+            return Ok(());
+        };
+
+        if let Some(dialogue) = current_dialogue(&self.state, session).await {
+            return self.send(&ServerMessage::NextDialogue { dialogue }).await;
+        }
+
+        let summary = complete_practice(&self.state, &session.user_id, &session.practice_id).await?;
+        self.session = None;
+        self.send(&ServerMessage::Completed { summary }).await
+    }
+
+    async fn send(&mut self, message: &ServerMessage) -> AppResult<()> {
+        let text = serde_json::to_string(message).map_err(anyhow::Error::from)?;
+        self.socket
+            .send(Message::Text(text))
+            .await
+            .map_err(anyhow::Error::from)?;
+        Ok(())
+    }
+
+    async fn send_error(&mut self, err: &AppError) -> AppResult<()> {
+        let message = ServerMessage::error(err.code(), err.public_message());
+        self.send(&message).await
+    }
+}
@@ -0,0 +1,9 @@
+// src/database/mod.rs
+
+mod mongo;
+mod mysql;
+mod redis;
+
+pub use mongo::create_mongo;
+pub use mysql::{create_pool, run_migrations, Migration, MIGRATIONS};
+pub use self::redis::{create_redis, redis_key};
@@ -0,0 +1,10 @@
+// src/database/mongo.rs
+
+use crate::config::Config;
+use mongodb::{Client, Database};
+
+/// 建立 MongoDB 連線 (Client 內含連線池，第一次操作時才實際連線，可直接 clone 共用)
+pub async fn create_mongo(config: &Config) -> anyhow::Result<Database> {
+    let client = Client::with_uri_str(&config.mongodb.uri).await?;
+    Ok(client.database(&config.mongodb.database))
+}
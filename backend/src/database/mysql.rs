@@ -0,0 +1,348 @@
+// src/database/mysql.rs
+
+use crate::config::DatabaseConfig;
+use anyhow::{anyhow, bail};
+use sha2::{Digest, Sha256};
+use sqlx::mysql::{MySqlConnectOptions, MySqlPoolOptions};
+use sqlx::{Executor, MySqlPool, Row};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// 遷移期間持有的 MySQL 具名鎖，避免多個實例同時套用遷移
+const MIGRATION_LOCK: &str = "nice_speak_schema_migrations";
+
+const CREATE_SCHEMA_MIGRATIONS: &str = r#"
+CREATE TABLE IF NOT EXISTS `schema_migrations` (
+    `version` BIGINT NOT NULL COMMENT '遷移版本號',
+    `name` VARCHAR(100) NOT NULL COMMENT '遷移名稱',
+    `checksum` CHAR(64) NOT NULL COMMENT 'SQL 檔 SHA-256',
+    `applied_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '套用時間',
+    PRIMARY KEY (`version`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+COMMENT='資料庫遷移紀錄'
+"#;
+
+/// 內嵌於執行檔的資料庫遷移
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+impl Migration {
+    /// SQL 內容的 SHA-256 (hex)，用來偵測已套用的遷移是否被修改
+    pub fn checksum(&self) -> String {
+        Sha256::digest(self.sql.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// 已套用的 checksum 是否為這個遷移目前或先前發佈的內容
+    pub fn accepts(&self, checksum: &str) -> bool {
+        self.checksum() == checksum
+            || LEGACY_CHECKSUMS
+                .iter()
+                .any(|(version, legacy)| *version == self.version && *legacy == checksum)
+    }
+}
+
+/// 已套用過的舊版遷移內容 (版本, checksum)，修改已發佈的遷移時在此保留舊 checksum
+///
+/// - 001: 移除測試設備資料前的版本，測試資料改由 `seeds/dev_devices.sql` 匯入
+const LEGACY_CHECKSUMS: &[(i64, &str)] = &[(
+    1,
+    "2d155680ae2cd09d53646c0566f1bb071273e0aeb655f205554099075e83629c",
+)];
+
+/// `backend/migrations` 內的遷移檔，依版本號排序 (新增檔案請加在最後)
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "devices",
+        sql: include_str!("../../migrations/001_devices.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "users",
+        sql: include_str!("../../migrations/002_users.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "refresh_tokens",
+        sql: include_str!("../../migrations/003_refresh_tokens.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "device_bindings",
+        sql: include_str!("../../migrations/004_device_bindings.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "device_usage_events",
+        sql: include_str!("../../migrations/005_device_usage_events.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "trial_attempts",
+        sql: include_str!("../../migrations/006_trial_attempts.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "practice",
+        sql: include_str!("../../migrations/007_practice.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "practice_turn_feedback",
+        sql: include_str!("../../migrations/008_practice_turn_feedback.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "user_levels",
+        sql: include_str!("../../migrations/009_user_levels.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "practice_lifecycle",
+        sql: include_str!("../../migrations/010_practice_lifecycle.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "subscription_entitlements",
+        sql: include_str!("../../migrations/011_subscription_entitlements.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "payments",
+        sql: include_str!("../../migrations/012_payments.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "payment_gateway",
+        sql: include_str!("../../migrations/013_payment_gateway.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "subscription_lifecycle",
+        sql: include_str!("../../migrations/014_subscription_lifecycle.sql"),
+    },
+    Migration {
+        version: 15,
+        name: "trial_periods",
+        sql: include_str!("../../migrations/015_trial_periods.sql"),
+    },
+    Migration {
+        version: 16,
+        name: "vocabulary",
+        sql: include_str!("../../migrations/016_vocabulary.sql"),
+    },
+    Migration {
+        version: 17,
+        name: "user_timezone",
+        sql: include_str!("../../migrations/017_user_timezone.sql"),
+    },
+    Migration {
+        version: 18,
+        name: "dialogue_updated_at",
+        sql: include_str!("../../migrations/018_dialogue_updated_at.sql"),
+    },
+    Migration {
+        version: 19,
+        name: "device_unbinds",
+        sql: include_str!("../../migrations/019_device_unbinds.sql"),
+    },
+    Migration {
+        version: 20,
+        name: "remove_sample_devices",
+        sql: include_str!("../../migrations/020_remove_sample_devices.sql"),
+    },
+];
+
+/// 依 `Config.database` 建立 MySQL 連線池 (`DATABASE_URL` 優先於個別欄位)
+pub async fn create_pool(config: &DatabaseConfig) -> anyhow::Result<MySqlPool> {
+    let options = match &config.url {
+        Some(url) => MySqlConnectOptions::from_str(url)?,
+        None => MySqlConnectOptions::new()
+            .host(&config.host)
+            .port(config.port)
+            .username(&config.username)
+            .password(&config.password)
+            .database(&config.database),
+    }
+    .charset("utf8mb4");
+
+    let pool = MySqlPoolOptions::new()
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+        .connect_with(options)
+        .await?;
+
+    Ok(pool)
+}
+
+/// 套用尚未執行的遷移
+///
+/// 已套用的遷移若 checksum 與內嵌內容不符，直接回傳錯誤讓服務拒絕啟動。
+pub async fn run_migrations(pool: &MySqlPool) -> anyhow::Result<()> {
+    let mut conn = pool.acquire().await?;
+
+    let locked: Option<i64> = sqlx::query_scalar("SELECT GET_LOCK(?, 60)")
+        .bind(MIGRATION_LOCK)
+        .fetch_one(&mut *conn)
+        .await?;
+    if locked != Some(1) {
+        bail!("timed out waiting for migration lock `{}`", MIGRATION_LOCK);
+    }
+
+    let result = apply_pending(&mut conn).await;
+
+    sqlx::query("SELECT RELEASE_LOCK(?)")
+        .bind(MIGRATION_LOCK)
+        .execute(&mut *conn)
+        .await?;
+
+    result
+}
+
+async fn apply_pending(conn: &mut sqlx::MySqlConnection) -> anyhow::Result<()> {
+    conn.execute(CREATE_SCHEMA_MIGRATIONS).await?;
+
+    let applied: Vec<(i64, String, String)> =
+        sqlx::query("SELECT version, name, checksum FROM schema_migrations ORDER BY version")
+            .fetch_all(&mut *conn)
+            .await?
+            .iter()
+            .map(|row| (row.get("version"), row.get("name"), row.get("checksum")))
+            .collect();
+
+    for (version, name, checksum) in &applied {
+        match MIGRATIONS.iter().find(|m| m.version == *version) {
+            Some(migration) if !migration.accepts(checksum) => bail!(
+                "migration {:03}_{} was modified after it was applied (checksum mismatch)",
+                version,
+                name
+            ),
+            Some(_) => {}
+            None => log::warn!(
+                "migration {:03}_{} is recorded in the database but not embedded in this build",
+                version,
+                name
+            ),
+        }
+    }
+
+    for migration in MIGRATIONS {
+        if applied.iter().any(|(version, _, _)| *version == migration.version) {
+            continue;
+        }
+
+        log::info!("Applying migration {:03}_{}", migration.version, migration.name);
+        for statement in split_statements(migration.sql) {
+            conn.execute(statement.as_str()).await.map_err(|e| {
+                anyhow!(
+                    "migration {:03}_{} failed: {}",
+                    migration.version,
+                    migration.name,
+                    e
+                )
+            })?;
+        }
+
+        sqlx::query("INSERT INTO schema_migrations (version, name, checksum) VALUES (?, ?, ?)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(migration.checksum())
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// 將遷移檔切成單一語句
+///
+/// 支援 mysql client 的 `DELIMITER` 指令 (預存程序使用 `$$`)，並略過整行註解。
+fn split_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut delimiter = ";".to_string();
+    let mut current = String::new();
+
+    for line in sql.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("--") {
+            continue;
+        }
+
+        if let Some(keyword) = trimmed.get(..10) {
+            if keyword.eq_ignore_ascii_case("DELIMITER ") {
+                delimiter = trimmed[10..].trim().to_string();
+                continue;
+            }
+        }
+
+        match line.trim_end().strip_suffix(delimiter.as_str()) {
+            Some(rest) => {
+                current.push_str(rest);
+                let statement = current.trim();
+                if !statement.is_empty() {
+                    statements.push(statement.to_string());
+                }
+                current.clear();
+            }
+            None => {
+                current.push_str(line);
+                current.push('\n');
+            }
+        }
+    }
+
+    if !current.trim().is_empty() {
+        statements.push(current.trim().to_string());
+    }
+
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements_with_delimiter() {
+        let sql = "-- comment\nCREATE TABLE a (id INT);\n\nDELIMITER $$\nCREATE PROCEDURE p()\nBEGIN\n    SELECT 1;\nEND$$\nDELIMITER ;\nINSERT INTO a VALUES (1);\n";
+        let statements = split_statements(sql);
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0], "CREATE TABLE a (id INT)");
+        assert!(statements[1].starts_with("CREATE PROCEDURE p()"));
+        assert!(statements[1].contains("SELECT 1;"));
+        assert!(statements[1].ends_with("END"));
+        assert_eq!(statements[2], "INSERT INTO a VALUES (1)");
+    }
+
+    #[test]
+    fn test_devices_migration_statements() {
+        // 2 張表 + 4 組 DROP/CREATE PROCEDURE，測試資料在 seeds/dev_devices.sql
+        let statements = split_statements(MIGRATIONS[0].sql);
+        assert_eq!(statements.len(), 10);
+        assert!(!statements.iter().any(|statement| statement.starts_with("INSERT")));
+    }
+
+    #[test]
+    fn test_legacy_checksum_is_accepted() {
+        let devices = &MIGRATIONS[0];
+        assert!(devices.accepts(&devices.checksum()));
+        assert!(devices.accepts(LEGACY_CHECKSUMS[0].1));
+        assert!(!MIGRATIONS[1].accepts(LEGACY_CHECKSUMS[0].1));
+        assert!(!devices.accepts(&"0".repeat(64)));
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        for pair in MIGRATIONS.windows(2) {
+            assert!(pair[0].version < pair[1].version);
+        }
+        assert_eq!(MIGRATIONS[0].checksum().len(), 64);
+    }
+}
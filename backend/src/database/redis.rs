@@ -0,0 +1,26 @@
+// src/database/redis.rs
+
+use crate::config::Config;
+use redis::aio::ConnectionManager;
+
+/// 建立 Redis 連線 (ConnectionManager 斷線時會自動重連，可直接 clone 共用)
+pub async fn create_redis(config: &Config) -> anyhow::Result<ConnectionManager> {
+    let client = redis::Client::open(config.redis_url())?;
+    let manager = ConnectionManager::new(client).await?;
+    Ok(manager)
+}
+
+/// 加上環境前綴的 Redis key，例如 `nice_speak:dev:auth:{token}`
+pub fn redis_key(prefix: &str, key: &str) -> String {
+    format!("{}{}", prefix, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redis_key() {
+        assert_eq!(redis_key("nice_speak:test:", "auth:abc"), "nice_speak:test:auth:abc");
+    }
+}
@@ -0,0 +1,504 @@
+// src/device/binding.rs
+
+use super::{validate_device_id, DeviceBindRequest, DeviceIdRequest};
+use crate::auth::{self, AuthUser};
+use crate::database::redis_key;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use axum::{extract::State, Json};
+use chrono::{DateTime, Duration, Utc};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySql, Transaction};
+
+/// 同一台設備兩次轉移之間至少間隔的天數
+const TRANSFER_COOLDOWN_DAYS: i64 = 30;
+/// 轉移碼的有效秒數
+const TRANSFER_CODE_TTL_SECS: u64 = 10 * 60;
+
+/// 各方案可同時綁定的設備數
+pub fn device_limit(tier: &str) -> i64 {
+    match tier {
+        "basic" => 2,
+        "advanced" | "premium" => 3,
+        "platinum" | "unlimited" => 5,
+        _ => 1, // free, evaluation
+    }
+}
+
+/// 已綁定設備
+#[derive(Serialize, sqlx::FromRow)]
+pub struct BoundDevice {
+    pub device_id: String,
+    pub platform: String,
+    pub has_used_free_trial: bool,
+    pub bound_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// 我的設備列表回應
+#[derive(Serialize)]
+pub struct DeviceListResponse {
+    pub devices: Vec<BoundDevice>,
+    pub limit: i64,
+    pub used: i64,
+}
+
+/// 核發轉移碼的回應
+#[derive(Serialize)]
+pub struct TransferCodeResponse {
+    pub device_id: String,
+    pub transfer_code: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 設備擁有者核發的轉移碼 (`device:{device_id}:transfer`)，使用一次即失效
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct TransferGrant {
+    owner_id: String,
+    code: String,
+}
+
+/// 轉移碼需由設備目前的擁有者核發，且與提交的相同
+fn check_transfer_grant(grant: Option<&TransferGrant>, owner_id: &str, code: Option<&str>) -> AppResult<()> {
+    let code = code.map(str::trim).filter(|code| !code.is_empty());
+    match (grant, code) {
+        (Some(grant), Some(code)) if grant.owner_id == owner_id && grant.code == code => Ok(()),
+        _ => Err(AppError::Forbidden(
+            "Device belongs to another account, ask its owner for a transfer code".to_string(),
+        )),
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct LockedDevice {
+    user_id: Option<String>,
+    unbound_user_id: Option<String>,
+    has_used_free_trial: bool,
+    has_purchased_evaluation: bool,
+    is_banned: bool,
+    ban_reason: Option<String>,
+}
+
+/// 設備或帳號的試用紀錄，綁定時兩邊互相同步 (只會由未使用變為已使用)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct TrialHistory {
+    free_trial_used: bool,
+    evaluation_purchased: bool,
+}
+
+impl TrialHistory {
+    fn merge(self, other: Self) -> Self {
+        Self {
+            free_trial_used: self.free_trial_used || other.free_trial_used,
+            evaluation_purchased: self.evaluation_purchased || other.evaluation_purchased,
+        }
+    }
+}
+
+impl LockedDevice {
+    /// 設備目前的擁有者，未綁定時為最後解除綁定的帳號
+    fn owner(&self) -> Option<&str> {
+        self.user_id.as_deref().or(self.unbound_user_id.as_deref())
+    }
+}
+
+/// 列出目前帳號綁定的設備
+pub async fn list_devices(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<DeviceListResponse>> {
+    let devices: Vec<BoundDevice> = sqlx::query_as(
+        r#"
+        SELECT device_id, platform, has_used_free_trial, bound_at, last_used_at
+        FROM devices
+        WHERE user_id = ?
+        ORDER BY bound_at ASC
+        "#,
+    )
+    .bind(&user.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let tier = auth::current_tier(&state.pool, &user.user_id).await?;
+    Ok(Json(DeviceListResponse {
+        used: devices.len() as i64,
+        limit: device_limit(&tier),
+        devices,
+    }))
+}
+
+/// 將未綁定的設備綁到目前帳號
+///
+/// 設備最後由其他帳號解除綁定時視為轉移：需該帳號核發的轉移碼，同樣受轉移冷卻期限制並記錄轉移。
+pub async fn bind_device(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<DeviceBindRequest>,
+) -> AppResult<Json<BoundDevice>> {
+    let device_id = validate_device_id(&payload.device_id)?;
+    let tier = auth::current_tier(&state.pool, &user.user_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+    let device = lock_device(&mut tx, device_id).await?;
+
+    match device.user_id.as_deref() {
+        Some(owner) if owner == user.user_id => {}
+        Some(_) => {
+            return Err(AppError::Conflict(
+                "Device is bound to another account, use transfer instead".to_string(),
+            ))
+        }
+        None => {
+            let previous_owner = device
+                .unbound_user_id
+                .as_deref()
+                .filter(|previous| *previous != user.user_id);
+            if let Some(previous) = previous_owner {
+                ensure_transfer_approved(&state, device_id, previous, payload.transfer_code.as_deref()).await?;
+                ensure_transfer_allowed(&mut tx, device_id).await?;
+            }
+            attach(&mut tx, device_id, &device, &user.user_id, &tier).await?;
+            if let Some(previous) = previous_owner {
+                record_transfer(&mut tx, device_id, &device, Some(previous), &user.user_id).await?;
+            }
+        }
+    }
+
+    tx.commit().await?;
+    fetch_bound(&state, device_id).await.map(Json)
+}
+
+/// 解除目前帳號與設備的綁定 (試用紀錄保留在設備上)
+pub async fn unbind_device(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<DeviceIdRequest>,
+) -> AppResult<Json<serde_json::Value>> {
+    let device_id = validate_device_id(&payload.device_id)?;
+
+    let result = sqlx::query(
+        // MySQL 依序賦值，需先記下原帳號再清除
+        "UPDATE devices SET unbound_user_id = user_id, user_id = NULL, bound_at = NULL WHERE device_id = ? AND user_id = ?",
+    )
+    .bind(device_id)
+    .bind(&user.user_id)
+    .execute(&state.pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            "Device is not bound to this account".to_string(),
+        ));
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "device_id": device_id
+    })))
+}
+
+/// 核發轉移碼，讓其他帳號接手目前帳號的設備 (解除綁定後仍由原帳號核發)
+pub async fn issue_transfer_code(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<DeviceIdRequest>,
+) -> AppResult<Json<TransferCodeResponse>> {
+    let device_id = validate_device_id(&payload.device_id)?;
+
+    let device: Option<(Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT user_id, unbound_user_id FROM devices WHERE device_id = ?")
+            .bind(device_id)
+            .fetch_optional(&state.pool)
+            .await?;
+    let owner = device.and_then(|(user_id, unbound_user_id)| user_id.or(unbound_user_id));
+    if owner.as_deref() != Some(user.user_id.as_str()) {
+        return Err(AppError::NotFound(
+            "Device is not bound to this account".to_string(),
+        ));
+    }
+
+    let grant = TransferGrant {
+        owner_id: user.user_id.clone(),
+        code: format!("{:08}", uuid::Uuid::new_v4().as_u128() % 100_000_000),
+    };
+    let mut redis = state.redis.clone();
+    redis
+        .set_ex::<_, _, ()>(
+            transfer_key(&state, device_id),
+            serde_json::to_string(&grant).map_err(anyhow::Error::from)?,
+            TRANSFER_CODE_TTL_SECS,
+        )
+        .await
+        .map_err(anyhow::Error::from)?;
+
+    Ok(Json(TransferCodeResponse {
+        device_id: device_id.to_string(),
+        transfer_code: grant.code,
+        expires_at: Utc::now() + Duration::seconds(TRANSFER_CODE_TTL_SECS as i64),
+    }))
+}
+
+/// 以擁有者核發的轉移碼將其他帳號的設備轉移到目前帳號
+///
+/// 設備的 `has_used_free_trial` 不會因轉移重置，並會同步到新帳號的 `free_trial_used`。
+pub async fn transfer_device(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<DeviceBindRequest>,
+) -> AppResult<Json<BoundDevice>> {
+    let device_id = validate_device_id(&payload.device_id)?;
+    let tier = auth::current_tier(&state.pool, &user.user_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+    let device = lock_device(&mut tx, device_id).await?;
+
+    if device.user_id.as_deref() == Some(user.user_id.as_str()) {
+        tx.commit().await?;
+        return fetch_bound(&state, device_id).await.map(Json);
+    }
+
+    if let Some(owner) = device.owner().filter(|owner| *owner != user.user_id) {
+        ensure_transfer_approved(&state, device_id, owner, payload.transfer_code.as_deref()).await?;
+    }
+    ensure_transfer_allowed(&mut tx, device_id).await?;
+    attach(&mut tx, device_id, &device, &user.user_id, &tier).await?;
+    record_transfer(&mut tx, device_id, &device, device.user_id.as_deref(), &user.user_id).await?;
+    tx.commit().await?;
+
+    log::info!(
+        "device {} transferred from {:?} to {}",
+        device_id,
+        device.user_id,
+        user.user_id
+    );
+    fetch_bound(&state, device_id).await.map(Json)
+}
+
+fn transfer_key(state: &AppState, device_id: &str) -> String {
+    redis_key(&state.config.redis.key_prefix, &format!("device:{}:transfer", device_id))
+}
+
+/// 取出 (並作廢) 設備的轉移碼後檢查；提交錯誤的轉移碼也會作廢，擁有者需重新核發
+async fn ensure_transfer_approved(
+    state: &AppState,
+    device_id: &str,
+    owner_id: &str,
+    code: Option<&str>,
+) -> AppResult<()> {
+    let mut redis = state.redis.clone();
+    let stored: Option<String> = redis
+        .get_del(transfer_key(state, device_id))
+        .await
+        .map_err(anyhow::Error::from)?;
+    let grant = stored.and_then(|stored| serde_json::from_str::<TransferGrant>(&stored).ok());
+    check_transfer_grant(grant.as_ref(), owner_id, code)
+}
+
+/// 同一台設備上次轉移後需滿 `TRANSFER_COOLDOWN_DAYS` 天才能再轉移
+async fn ensure_transfer_allowed(tx: &mut Transaction<'_, MySql>, device_id: &str) -> AppResult<()> {
+    let last_transfer: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT MAX(transferred_at) FROM device_transfers WHERE device_id = ?",
+    )
+    .bind(device_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if let Some(last) = last_transfer {
+        let available_at = last + Duration::days(TRANSFER_COOLDOWN_DAYS);
+        if available_at > Utc::now() {
+            return Err(AppError::Forbidden(format!(
+                "Device was transferred recently, next transfer available at {}",
+                available_at.to_rfc3339()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// 記錄轉移 (含解除綁定後由其他帳號綁定)
+async fn record_transfer(
+    tx: &mut Transaction<'_, MySql>,
+    device_id: &str,
+    device: &LockedDevice,
+    from_user_id: Option<&str>,
+    to_user_id: &str,
+) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO device_transfers (id, device_id, from_user_id, to_user_id, had_used_free_trial, transferred_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(device_id)
+    .bind(from_user_id)
+    .bind(to_user_id)
+    .bind(device.has_used_free_trial)
+    .bind(Utc::now())
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// 鎖定設備列，供綁定 / 轉移在同一交易中判斷
+async fn lock_device(tx: &mut Transaction<'_, MySql>, device_id: &str) -> AppResult<LockedDevice> {
+    let device: Option<LockedDevice> = sqlx::query_as(
+        r#"
+        SELECT user_id, unbound_user_id, has_used_free_trial, has_purchased_evaluation, is_banned, ban_reason
+        FROM devices
+        WHERE device_id = ?
+        FOR UPDATE
+        "#,
+    )
+    .bind(device_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let device = device.ok_or_else(|| AppError::NotFound("Device not registered".to_string()))?;
+    if device.is_banned {
+        return Err(AppError::DeviceBanned(
+            device
+                .ban_reason
+                .clone()
+                .unwrap_or_else(|| "This device has been banned".to_string()),
+        ));
+    }
+    Ok(device)
+}
+
+/// 檢查方案設備上限後綁定，並讓設備與帳號的試用紀錄互相同步
+///
+/// 帳號在沒有綁定這台設備時購買的評估版也會記到設備上，其他帳號之後綁定這台設備就不能再購買。
+async fn attach(
+    tx: &mut Transaction<'_, MySql>,
+    device_id: &str,
+    device: &LockedDevice,
+    user_id: &str,
+    tier: &str,
+) -> AppResult<()> {
+    // 鎖住用戶列，讓同一帳號的並行綁定依序計算數量
+    let free_trial_used: Option<bool> =
+        sqlx::query_scalar("SELECT free_trial_used FROM users WHERE id = ? FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+    let free_trial_used =
+        free_trial_used.ok_or_else(|| AppError::Unauthorized("Account no longer exists".to_string()))?;
+    let evaluation_subscriptions: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM subscriptions WHERE user_id = ? AND tier = 'evaluation'")
+            .bind(user_id)
+            .fetch_one(&mut **tx)
+            .await?;
+    let account = TrialHistory {
+        free_trial_used,
+        evaluation_purchased: evaluation_subscriptions > 0,
+    };
+    let merged = TrialHistory {
+        free_trial_used: device.has_used_free_trial,
+        evaluation_purchased: device.has_purchased_evaluation,
+    }
+    .merge(account);
+
+    let bound: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM devices WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    let limit = device_limit(tier);
+    if bound >= limit {
+        return Err(AppError::DeviceLimitReached(format!(
+            "The {} plan allows {} bound device(s)",
+            tier, limit
+        )));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE devices
+        SET user_id = ?, bound_at = ?, has_used_free_trial = ?, has_purchased_evaluation = ?
+        WHERE device_id = ?
+        "#,
+    )
+    .bind(user_id)
+    .bind(Utc::now())
+    .bind(merged.free_trial_used)
+    .bind(merged.evaluation_purchased)
+    .bind(device_id)
+    .execute(&mut **tx)
+    .await?;
+
+    if merged.free_trial_used && !account.free_trial_used {
+        sqlx::query("UPDATE users SET free_trial_used = 1 WHERE id = ?")
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn fetch_bound(state: &AppState, device_id: &str) -> AppResult<BoundDevice> {
+    let device = sqlx::query_as(
+        "SELECT device_id, platform, has_used_free_trial, bound_at, last_used_at FROM devices WHERE device_id = ?",
+    )
+    .bind(device_id)
+    .fetch_one(&state.pool)
+    .await?;
+    Ok(device)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_limit_by_tier() {
+        assert_eq!(device_limit("free"), 1);
+        assert_eq!(device_limit("evaluation"), 1);
+        assert_eq!(device_limit("basic"), 2);
+        assert_eq!(device_limit("premium"), 3);
+        assert_eq!(device_limit("unlimited"), 5);
+        assert_eq!(device_limit("unknown"), 1);
+    }
+
+    #[test]
+    fn test_evaluation_bought_before_binding_is_recorded_on_device() {
+        let device = TrialHistory::default();
+        let account = TrialHistory {
+            free_trial_used: false,
+            evaluation_purchased: true,
+        };
+        assert_eq!(device.merge(account), account);
+
+        // 設備的紀錄不會因綁定到沒有紀錄的帳號而清除
+        let used = TrialHistory {
+            free_trial_used: true,
+            evaluation_purchased: true,
+        };
+        assert_eq!(used.merge(TrialHistory::default()), used);
+    }
+
+    #[test]
+    fn test_transfer_requires_owner_code() {
+        let grant = TransferGrant {
+            owner_id: "owner".to_string(),
+            code: "12345678".to_string(),
+        };
+        assert!(check_transfer_grant(Some(&grant), "owner", Some(" 12345678 ")).is_ok());
+
+        // 沒有轉移碼、轉移碼錯誤，或轉移碼不是目前擁有者核發的
+        for (grant, owner, code) in [
+            (None, "owner", Some("12345678")),
+            (Some(&grant), "owner", None),
+            (Some(&grant), "owner", Some("")),
+            (Some(&grant), "owner", Some("87654321")),
+            (Some(&grant), "new-owner", Some("12345678")),
+        ] {
+            assert!(matches!(
+                check_transfer_grant(grant, owner, code),
+                Err(AppError::Forbidden(_))
+            ));
+        }
+    }
+}
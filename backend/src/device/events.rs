@@ -0,0 +1,338 @@
+// src/device/events.rs
+
+use super::validate_device_id;
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use axum::{extract::State, Json};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySql, MySqlPool, QueryBuilder};
+use std::collections::HashSet;
+use tokio::sync::mpsc;
+
+/// 可接受的事件類型
+pub const EVENT_TYPES: &[&str] = &[
+    "APP_OPENED",
+    "APP_CLOSED",
+    "TRIAL_STARTED",
+    "TRIAL_ENDED",
+    "PRACTICE_STARTED",
+    "PRACTICE_COMPLETED",
+    "PRACTICE_ABANDONED",
+    "SUBSCRIPTION_VIEWED",
+    "PURCHASE_STARTED",
+    "PURCHASE_COMPLETED",
+    "ERROR",
+];
+
+/// 單次請求最多事件數
+const MAX_BATCH_SIZE: usize = 100;
+/// metadata 序列化後的最大長度
+const MAX_METADATA_BYTES: usize = 4096;
+/// 寫入佇列容量，滿了就請客戶端稍後重送
+const QUEUE_CAPACITY: usize = 10_000;
+/// 累積到這個數量就立即寫入
+const FLUSH_BATCH_SIZE: usize = 500;
+/// 最長寫入間隔
+const FLUSH_INTERVAL_MS: u64 = 1_000;
+
+/// 批次事件請求
+#[derive(Deserialize)]
+pub struct UsageEventBatch {
+    device_id: String,
+    app_version: Option<String>,
+    platform: Option<String>,
+    events: Vec<ClientEvent>,
+}
+
+#[derive(Deserialize)]
+pub struct ClientEvent {
+    event_id: String,
+    event_type: String,
+    occurred_at: Option<DateTime<Utc>>,
+    metadata: Option<serde_json::Value>,
+}
+
+/// 批次事件回應
+#[derive(Serialize)]
+pub struct UsageEventBatchResponse {
+    accepted: usize,
+    duplicates: usize,
+    rejected: Vec<RejectedEvent>,
+}
+
+#[derive(Serialize)]
+pub struct RejectedEvent {
+    event_id: String,
+    reason: String,
+}
+
+/// 待寫入 `device_usage_logs` 的事件
+#[derive(Debug)]
+pub struct UsageEvent {
+    pub device_id: String,
+    pub client_event_id: String,
+    pub event_type: String,
+    pub app_version: Option<String>,
+    pub platform: Option<String>,
+    pub metadata: Option<String>,
+    pub occurred_at: Option<DateTime<Utc>>,
+    pub received_at: DateTime<Utc>,
+}
+
+/// 背景批次寫入器
+///
+/// handler 只把事件放進佇列，由單一背景 task 累積後以多列 `INSERT IGNORE` 寫入，
+/// 同一設備重複的 `client_event_id` 由唯一鍵去除。
+#[derive(Clone)]
+pub struct UsageEventWriter {
+    sender: mpsc::Sender<UsageEvent>,
+}
+
+impl UsageEventWriter {
+    pub fn spawn(pool: MySqlPool) -> Self {
+        let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        tokio::spawn(run_writer(pool, receiver));
+        Self { sender }
+    }
+
+    /// 放入佇列；佇列已滿時回傳 false
+    pub fn try_enqueue(&self, event: UsageEvent) -> bool {
+        self.sender.try_send(event).is_ok()
+    }
+
+    /// 剩餘佇列空間
+    pub fn capacity(&self) -> usize {
+        self.sender.capacity()
+    }
+}
+
+/// 事件會作為試用風險訊號，只接受設備擁有者登入後送出的事件
+fn ensure_event_owner(owner: Option<&str>, user_id: &str) -> AppResult<()> {
+    if owner != Some(user_id) {
+        return Err(AppError::Forbidden(
+            "Device is not bound to this account".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 接收客戶端批次事件 (設備需綁定目前帳號)
+pub async fn ingest_events(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<UsageEventBatch>,
+) -> AppResult<Json<UsageEventBatchResponse>> {
+    let device_id = validate_device_id(&payload.device_id)?.to_string();
+    if payload.events.is_empty() || payload.events.len() > MAX_BATCH_SIZE {
+        return Err(AppError::Validation(format!(
+            "events must contain 1-{} items",
+            MAX_BATCH_SIZE
+        )));
+    }
+    let owner: Option<Option<String>> = sqlx::query_scalar("SELECT user_id FROM devices WHERE device_id = ?")
+        .bind(&device_id)
+        .fetch_optional(&state.pool)
+        .await?;
+    ensure_event_owner(owner.flatten().as_deref(), &user.user_id)?;
+    if state.usage_events.capacity() < payload.events.len() {
+        return Err(AppError::RateLimited(
+            "Event queue is full, retry later".to_string(),
+        ));
+    }
+
+    let app_version = trimmed(payload.app_version, 20);
+    let platform = trimmed(payload.platform, 20).map(|p| p.to_lowercase());
+    let received_at = Utc::now();
+
+    let mut seen = HashSet::new();
+    let mut accepted = 0;
+    let mut duplicates = 0;
+    let mut rejected = Vec::new();
+
+    for event in payload.events {
+        let event_id = event.event_id.trim().to_string();
+        if !seen.insert(event_id.clone()) {
+            duplicates += 1;
+            continue;
+        }
+
+        let metadata = match validate_event(&event_id, &event, received_at) {
+            Ok(metadata) => metadata,
+            Err(reason) => {
+                rejected.push(RejectedEvent { event_id, reason });
+                continue;
+            }
+        };
+
+        let queued = state.usage_events.try_enqueue(UsageEvent {
+            device_id: device_id.clone(),
+            client_event_id: event_id.clone(),
+            event_type: event.event_type,
+            app_version: app_version.clone(),
+            platform: platform.clone(),
+            metadata,
+            occurred_at: event.occurred_at,
+            received_at,
+        });
+        if queued {
+            accepted += 1;
+        } else {
+            rejected.push(RejectedEvent {
+                event_id,
+                reason: "queue full, retry later".to_string(),
+            });
+        }
+    }
+
+    Ok(Json(UsageEventBatchResponse {
+        accepted,
+        duplicates,
+        rejected,
+    }))
+}
+
+/// 檢查單一事件，成功時回傳序列化後的 metadata
+fn validate_event(
+    event_id: &str,
+    event: &ClientEvent,
+    now: DateTime<Utc>,
+) -> Result<Option<String>, String> {
+    if event_id.is_empty() || event_id.len() > 64 {
+        return Err("event_id must be 1-64 characters".to_string());
+    }
+    if !EVENT_TYPES.contains(&event.event_type.as_str()) {
+        return Err(format!("unknown event_type {}", event.event_type));
+    }
+    if let Some(occurred_at) = event.occurred_at {
+        if occurred_at > now + Duration::minutes(5) {
+            return Err("occurred_at is in the future".to_string());
+        }
+    }
+
+    match &event.metadata {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value @ serde_json::Value::Object(_)) => {
+            let metadata = value.to_string();
+            if metadata.len() > MAX_METADATA_BYTES {
+                return Err(format!("metadata exceeds {} bytes", MAX_METADATA_BYTES));
+            }
+            Ok(Some(metadata))
+        }
+        Some(_) => Err("metadata must be a JSON object".to_string()),
+    }
+}
+
+fn trimmed(value: Option<String>, max_len: usize) -> Option<String> {
+    value
+        .map(|v| v.trim().chars().take(max_len).collect::<String>())
+        .filter(|v| !v.is_empty())
+}
+
+async fn run_writer(pool: MySqlPool, mut receiver: mpsc::Receiver<UsageEvent>) {
+    let mut buffer = Vec::with_capacity(FLUSH_BATCH_SIZE);
+    let mut ticker = tokio::time::interval(std::time::Duration::from_millis(FLUSH_INTERVAL_MS));
+
+    loop {
+        tokio::select! {
+            received = receiver.recv() => match received {
+                Some(event) => {
+                    buffer.push(event);
+                    if buffer.len() >= FLUSH_BATCH_SIZE {
+                        flush(&pool, &mut buffer).await;
+                    }
+                }
+                None => {
+                    flush(&pool, &mut buffer).await;
+                    break;
+                }
+            },
+            _ = ticker.tick() => flush(&pool, &mut buffer).await,
+        }
+    }
+}
+
+async fn flush(pool: &MySqlPool, buffer: &mut Vec<UsageEvent>) {
+    if buffer.is_empty() {
+        return;
+    }
+
+    let mut builder: QueryBuilder<MySql> = QueryBuilder::new(
+        "INSERT IGNORE INTO device_usage_logs \
+         (id, device_id, client_event_id, event_type, app_version, platform, metadata, occurred_at, created_at) ",
+    );
+    builder.push_values(buffer.iter(), |mut row, event| {
+        row.push_bind(uuid::Uuid::new_v4().to_string())
+            .push_bind(&event.device_id)
+            .push_bind(&event.client_event_id)
+            .push_bind(&event.event_type)
+            .push_bind(&event.app_version)
+            .push_bind(&event.platform)
+            .push_bind(&event.metadata)
+            .push_bind(event.occurred_at)
+            .push_bind(event.received_at);
+    });
+
+    match builder.build().execute(pool).await {
+        Ok(result) => log::debug!(
+            "flushed {} usage events ({} new)",
+            buffer.len(),
+            result.rows_affected()
+        ),
+        Err(err) => log::error!("failed to write {} usage events: {}", buffer.len(), err),
+    }
+    buffer.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event_type: &str, metadata: Option<serde_json::Value>) -> ClientEvent {
+        ClientEvent {
+            event_id: "evt-1".to_string(),
+            event_type: event_type.to_string(),
+            occurred_at: None,
+            metadata,
+        }
+    }
+
+    #[test]
+    fn test_events_only_from_device_owner() {
+        assert!(ensure_event_owner(Some("user-1"), "user-1").is_ok());
+        for owner in [None, Some("user-2")] {
+            assert!(matches!(
+                ensure_event_owner(owner, "user-1"),
+                Err(AppError::Forbidden(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_validate_event_type_registry() {
+        let now = Utc::now();
+        assert!(validate_event("evt-1", &event("APP_OPENED", None), now).is_ok());
+        assert!(validate_event("evt-1", &event("APP_HACKED", None), now).is_err());
+    }
+
+    #[test]
+    fn test_validate_event_metadata() {
+        let now = Utc::now();
+        let ok = event("ERROR", Some(serde_json::json!({ "code": 500 })));
+        assert_eq!(
+            validate_event("evt-1", &ok, now).unwrap().as_deref(),
+            Some(r#"{"code":500}"#)
+        );
+        let not_object = event("ERROR", Some(serde_json::json!([1, 2])));
+        assert!(validate_event("evt-1", &not_object, now).is_err());
+    }
+
+    #[test]
+    fn test_validate_event_rejects_future_timestamp() {
+        let now = Utc::now();
+        let mut future = event("APP_OPENED", None);
+        future.occurred_at = Some(now + Duration::hours(1));
+        assert!(validate_event("evt-1", &future, now).is_err());
+    }
+}
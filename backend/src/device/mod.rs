@@ -1,17 +1,22 @@
 // src/device/mod.rs
 
-use axum::{routing::{post, get}, Router, Json, extract::Path};
+mod binding;
+mod events;
+
+pub use binding::{
+    bind_device, device_limit, issue_transfer_code, list_devices, transfer_device, unbind_device,
+};
+pub use events::{ingest_events, UsageEventWriter, EVENT_TYPES};
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult};
+use axum::{extract::State, Json};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{MySqlPool, Row};
-use std::sync::Arc;
-
-pub fn router(pool: Arc<MySqlPool>) -> Router {
-    Router::new()
-        .route("/api/v1/devices/status", post(check_status))
-        .route("/api/v1/devices/register", post(register_device))
-        .route("/api/v1/devices/mark-trial-used", post(mark_trial_used))
-        .with_state(pool)
-}
+use sqlx::MySqlPool;
+
+/// 支援的平台
+const PLATFORMS: &[&str] = &["android", "ios", "web"];
 
 /// 設備註冊請求
 #[derive(Deserialize)]
@@ -21,50 +26,262 @@ pub struct RegisterDeviceRequest {
     fcm_token: Option<String>,
 }
 
+/// 只帶設備 ID 的請求 (查詢狀態、標記試用)
+#[derive(Deserialize)]
+pub struct DeviceIdRequest {
+    device_id: String,
+}
+
+/// 綁定 / 轉移請求；設備屬於其他帳號時需附上該帳號核發的轉移碼
+#[derive(Deserialize)]
+pub struct DeviceBindRequest {
+    device_id: String,
+    #[serde(default)]
+    transfer_code: Option<String>,
+}
+
 /// 設備狀態回應
 #[derive(Serialize)]
 pub struct DeviceStatusResponse {
     device_id: String,
     has_used_free_trial: bool,
     is_banned: bool,
-    registered_at: Option<String>,
-    last_used_at: Option<String>,
+    can_use_free_trial: bool,
+    registered_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
 }
 
-/// 檢查設備狀態
-async fn check_status(
-    Json(payload): Json<RegisterDeviceRequest>,
-) -> Json<DeviceStatusResponse> {
-    // TODO: 從資料庫查詢設備狀態
-    Json(DeviceStatusResponse {
-        device_id: payload.device_id,
-        has_used_free_trial: false,
-        is_banned: false,
-        registered_at: None,
-        last_used_at: None,
-    })
+#[derive(sqlx::FromRow)]
+pub(crate) struct DeviceRow {
+    pub device_id: String,
+    pub user_id: Option<String>,
+    pub has_used_free_trial: bool,
+    pub is_banned: bool,
+    pub ban_reason: Option<String>,
+    pub registered_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl DeviceRow {
+    /// 同 `sp_check_device_free_trial` 的 can_use_free_trial
+    pub fn can_use_free_trial(&self) -> bool {
+        !self.is_banned && !self.has_used_free_trial
+    }
+
+    fn into_status(self) -> DeviceStatusResponse {
+        DeviceStatusResponse {
+            can_use_free_trial: self.can_use_free_trial(),
+            device_id: self.device_id,
+            has_used_free_trial: self.has_used_free_trial,
+            is_banned: self.is_banned,
+            registered_at: Some(self.registered_at),
+            last_used_at: self.last_used_at,
+        }
+    }
+
+    /// 已綁定帳號的設備只有登入的擁有者能修改 FCM token 與試用紀錄
+    fn ensure_modifiable_by(&self, user_id: Option<&str>) -> AppResult<()> {
+        match self.user_id.as_deref() {
+            Some(owner) if Some(owner) != user_id => Err(AppError::Forbidden(
+                "Device is bound to an account, sign in as its owner to update it".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn ensure_not_banned(&self) -> AppResult<()> {
+        if self.is_banned {
+            return Err(AppError::DeviceBanned(
+                self.ban_reason
+                    .clone()
+                    .unwrap_or_else(|| "This device has been banned".to_string()),
+            ));
+        }
+        Ok(())
+    }
 }
 
-/// 註冊設備
-async fn register_device(
+/// 檢查設備狀態 (未註冊的設備視為可試用)
+pub async fn check_status(
+    State(pool): State<MySqlPool>,
+    Json(payload): Json<DeviceIdRequest>,
+) -> AppResult<Json<DeviceStatusResponse>> {
+    let device_id = validate_device_id(&payload.device_id)?;
+
+    let status = match find_device(&pool, device_id).await? {
+        Some(device) => device.into_status(),
+        None => DeviceStatusResponse {
+            device_id: device_id.to_string(),
+            has_used_free_trial: false,
+            is_banned: false,
+            can_use_free_trial: true,
+            registered_at: None,
+            last_used_at: None,
+        },
+    };
+
+    Ok(Json(status))
+}
+
+/// 註冊設備 (已註冊則更新平台、FCM token 與最後使用時間)
+///
+/// 未登入時只能建立新設備或更新未綁定的設備；已綁定的設備需由擁有者登入後更新。
+pub async fn register_device(
+    State(pool): State<MySqlPool>,
+    user: Option<AuthUser>,
     Json(payload): Json<RegisterDeviceRequest>,
-) -> Json<serde_json::Value> {
-    // TODO: 寫入資料庫
-    Json(serde_json::json!({
-        "success": true,
-        "device_id": payload.device_id,
-        "message": "Device registered successfully"
-    }))
+) -> AppResult<Json<DeviceStatusResponse>> {
+    let device_id = validate_device_id(&payload.device_id)?;
+    let platform = payload.platform.trim().to_lowercase();
+    if !PLATFORMS.contains(&platform.as_str()) {
+        return Err(AppError::Validation(format!(
+            "platform must be one of: {}",
+            PLATFORMS.join(", ")
+        )));
+    }
+    let fcm_token = payload
+        .fcm_token
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty());
+
+    let now = Utc::now();
+    match find_device(&pool, device_id).await? {
+        Some(device) => {
+            device.ensure_not_banned()?;
+            device.ensure_modifiable_by(user.as_ref().map(|user| user.user_id.as_str()))?;
+            // 以檢查時的擁有者為條件，期間被綁定或轉移時不更新
+            sqlx::query(
+                r#"
+                UPDATE devices
+                SET last_used_at = ?, platform = ?, fcm_token = COALESCE(?, fcm_token)
+                WHERE device_id = ? AND user_id <=> ?
+                "#,
+            )
+            .bind(now)
+            .bind(&platform)
+            .bind(&fcm_token)
+            .bind(device_id)
+            .bind(&device.user_id)
+            .execute(&pool)
+            .await?;
+        }
+        None => {
+            sqlx::query(
+                r#"
+                INSERT IGNORE INTO devices (id, device_id, platform, fcm_token, registered_at, last_used_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(device_id)
+            .bind(&platform)
+            .bind(&fcm_token)
+            .bind(now)
+            .bind(now)
+            .execute(&pool)
+            .await?;
+        }
+    }
+
+    let device = find_device(&pool, device_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("device {} missing after upsert", device_id))?;
+    Ok(Json(device.into_status()))
 }
 
-/// 標記設備已使用免費試用
-async fn mark_trial_used(
-    Path(device_id): Path<String>,
-) -> Json<serde_json::Value> {
-    // TODO: 更新資料庫
-    Json(serde_json::json!({
-        "success": true,
-        "device_id": device_id,
-        "message": "Device marked as trial used"
-    }))
+/// 標記設備已使用免費試用 (已綁定帳號時需由擁有者登入，並一併標記帳號)
+pub async fn mark_trial_used(
+    State(pool): State<MySqlPool>,
+    user: Option<AuthUser>,
+    Json(payload): Json<DeviceIdRequest>,
+) -> AppResult<Json<DeviceStatusResponse>> {
+    let device_id = validate_device_id(&payload.device_id)?;
+
+    let device = find_device(&pool, device_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Device not registered".to_string()))?;
+    device.ensure_not_banned()?;
+    device.ensure_modifiable_by(user.as_ref().map(|user| user.user_id.as_str()))?;
+
+    let mut tx = pool.begin().await?;
+    let marked = sqlx::query(
+        "UPDATE devices SET has_used_free_trial = 1, last_used_at = ? WHERE device_id = ? AND user_id <=> ?",
+    )
+    .bind(Utc::now())
+    .bind(device_id)
+    .bind(&device.user_id)
+    .execute(&mut *tx)
+    .await?;
+    if marked.rows_affected() == 0 {
+        return Err(AppError::Conflict(
+            "Device binding changed, try again".to_string(),
+        ));
+    }
+    if let Some(user_id) = &device.user_id {
+        sqlx::query("UPDATE users SET free_trial_used = 1 WHERE id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    let device = find_device(&pool, device_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Device not registered".to_string()))?;
+    Ok(Json(device.into_status()))
+}
+
+pub(crate) async fn find_device(pool: &MySqlPool, device_id: &str) -> AppResult<Option<DeviceRow>> {
+    let device = sqlx::query_as(
+        r#"
+        SELECT device_id, user_id, has_used_free_trial, is_banned, ban_reason, registered_at, last_used_at
+        FROM devices
+        WHERE device_id = ?
+        "#,
+    )
+    .bind(device_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(device)
+}
+
+pub(crate) fn validate_device_id(device_id: &str) -> AppResult<&str> {
+    let device_id = device_id.trim();
+    if device_id.is_empty() || device_id.len() > 64 {
+        return Err(AppError::Validation(
+            "device_id must be 1-64 characters".to_string(),
+        ));
+    }
+    Ok(device_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(user_id: Option<&str>) -> DeviceRow {
+        DeviceRow {
+            device_id: "device-1".to_string(),
+            user_id: user_id.map(str::to_string),
+            has_used_free_trial: false,
+            is_banned: false,
+            ban_reason: None,
+            registered_at: Utc::now(),
+            last_used_at: None,
+        }
+    }
+
+    #[test]
+    fn test_bound_device_is_modifiable_only_by_owner() {
+        assert!(device(None).ensure_modifiable_by(None).is_ok());
+        assert!(device(None).ensure_modifiable_by(Some("user-2")).is_ok());
+        let bound = device(Some("user-1"));
+        assert!(bound.ensure_modifiable_by(Some("user-1")).is_ok());
+        for user_id in [None, Some("user-2")] {
+            assert!(matches!(
+                bound.ensure_modifiable_by(user_id),
+                Err(AppError::Forbidden(_))
+            ));
+        }
+    }
 }
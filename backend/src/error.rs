@@ -0,0 +1,139 @@
+// src/error.rs
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// API 錯誤 (格式見 Document/API.md「錯誤格式」)
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("{0}")]
+    Unauthorized(String),
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("{0}")]
+    DeviceBanned(String),
+
+    #[error("{0}")]
+    DeviceLimitReached(String),
+
+    #[error("{0}")]
+    RateLimited(String),
+
+    #[error("{0}")]
+    PracticeExpired(String),
+
+    /// 需要升級方案；`required_tier` 為可解鎖的最便宜方案
+    #[error("{message}")]
+    SubscriptionRequired {
+        message: String,
+        required_tier: Option<String>,
+    },
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    fn status(&self) -> StatusCode {
+        match self {
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::DeviceBanned(_) => StatusCode::FORBIDDEN,
+            AppError::DeviceLimitReached(_) => StatusCode::FORBIDDEN,
+            AppError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::PracticeExpired(_) => StatusCode::GONE,
+            AppError::SubscriptionRequired { .. } => StatusCode::FORBIDDEN,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Unauthorized(_) => "UNAUTHORIZED",
+            AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::Validation(_) => "VALIDATION_ERROR",
+            AppError::DeviceBanned(_) => "DEVICE_BANNED",
+            AppError::DeviceLimitReached(_) => "DEVICE_LIMIT_REACHED",
+            AppError::RateLimited(_) => "RATE_LIMIT_EXCEEDED",
+            AppError::PracticeExpired(_) => "PRACTICE_EXPIRED",
+            AppError::SubscriptionRequired { .. } => "SUBSCRIPTION_REQUIRED",
+            AppError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// 錯誤回應的 `details`
+    pub fn details(&self) -> serde_json::Value {
+        match self {
+            AppError::SubscriptionRequired { required_tier, .. } => {
+                serde_json::json!({ "required_tier": required_tier })
+            }
+            _ => serde_json::json!({}),
+        }
+    }
+
+    /// 回傳給客戶端的訊息；內部錯誤只記錄 log，不外流細節
+    pub fn public_message(&self) -> String {
+        match self {
+            AppError::Internal(err) => {
+                log::error!("internal error: {:#}", err);
+                "Internal server error".to_string()
+            }
+            other => other.to_string(),
+        }
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        AppError::Internal(err.into())
+    }
+}
+
+impl From<mongodb::error::Error> for AppError {
+    fn from(err: mongodb::error::Error) -> Self {
+        AppError::Internal(err.into())
+    }
+}
+
+impl From<validator::ValidationErrors> for AppError {
+    fn from(err: validator::ValidationErrors) -> Self {
+        AppError::Validation(err.to_string())
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let message = self.public_message();
+
+        let body = serde_json::json!({
+            "error": {
+                "code": self.code(),
+                "message": message,
+                "details": self.details()
+            }
+        });
+
+        (self.status(), Json(body)).into_response()
+    }
+}
@@ -0,0 +1,189 @@
+// src/evaluation/mod.rs
+
+mod openai;
+mod rules;
+
+pub use openai::OpenAiEvaluator;
+pub use rules::RuleEvaluator;
+
+use crate::config::ExternalConfig;
+use crate::speech::WordTiming;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 評估項目 (錯誤類型同名)
+pub const ERROR_TYPES: &[&str] = &["pronunciation", "grammar", "vocabulary", "fluency"];
+
+/// 一輪回答的評估資料
+#[derive(Debug, Clone, Default)]
+pub struct EvaluationInput {
+    /// AI 角色說的那句話
+    pub prompt: String,
+    pub speaker_role: String,
+    /// 用戶回答的逐字稿
+    pub transcript: String,
+    /// 語音辨識信心值 (0.0 - 1.0)，沒有時為 0
+    pub confidence: f32,
+    pub words: Vec<WordTiming>,
+}
+
+/// 各項目的原始評分 (0-100)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RawScores {
+    pub pronunciation: u32,
+    pub grammar: u32,
+    pub vocabulary: u32,
+    pub fluency: u32,
+}
+
+/// 需要改進的片段
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorItem {
+    #[serde(rename = "type")]
+    pub error_type: String,
+    pub original: String,
+    pub suggested: String,
+}
+
+/// 評估結果 (配分見 Document/LEVEL_SYSTEM.md：發音 30 / 語法 30 / 用詞 20 / 流暢度 20)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Evaluation {
+    pub pronunciation: u32,
+    pub grammar: u32,
+    pub vocabulary: u32,
+    pub fluency: u32,
+    pub total: u32,
+    pub feedback: String,
+    pub suggestions: Vec<String>,
+    pub errors: Vec<ErrorItem>,
+}
+
+impl Evaluation {
+    /// 依 LEVEL_SYSTEM.md 的權重 (0.3 / 0.3 / 0.2 / 0.2) 將原始評分換算為配分
+    pub fn from_raw(
+        raw: RawScores,
+        feedback: String,
+        suggestions: Vec<String>,
+        errors: Vec<ErrorItem>,
+    ) -> Self {
+        let weigh = |score: u32, weight: u32| (score.min(100) * weight + 5) / 10;
+        let pronunciation = weigh(raw.pronunciation, 3);
+        let grammar = weigh(raw.grammar, 3);
+        let vocabulary = weigh(raw.vocabulary, 2);
+        let fluency = weigh(raw.fluency, 2);
+
+        Self {
+            pronunciation,
+            grammar,
+            vocabulary,
+            fluency,
+            total: pronunciation + grammar + vocabulary + fluency,
+            feedback,
+            suggestions,
+            errors,
+        }
+    }
+
+    /// 沒有辨識到回答
+    pub fn silent() -> Self {
+        Self::from_raw(
+            RawScores::default(),
+            "未能辨識到回答，請再試一次。".to_string(),
+            vec!["請靠近麥克風並完整說出回答".to_string()],
+            Vec::new(),
+        )
+    }
+}
+
+/// AI 評估服務
+#[async_trait]
+pub trait Evaluator: Send + Sync {
+    async fn evaluate(&self, input: &EvaluationInput) -> anyhow::Result<Evaluation>;
+}
+
+/// 依 `ExternalConfig.ai_provider` 建立評估服務
+pub fn build_evaluator(config: &ExternalConfig) -> anyhow::Result<Arc<dyn Evaluator>> {
+    let evaluator: Arc<dyn Evaluator> = match config.ai_provider.as_str() {
+        "openai" => Arc::new(OpenAiEvaluator::new(
+            &config.ai_base_url,
+            &config.ai_api_key,
+            &config.ai_model,
+            config.ai_max_retries,
+        )?),
+        "local" => Arc::new(RuleEvaluator),
+        other => anyhow::bail!("unknown AI_PROVIDER {}", other),
+    };
+    Ok(evaluator)
+}
+
+/// 評估一輪回答：逐字稿為空直接給 0 分，評估服務失敗時改用規則評分，練習不會因此中斷
+pub async fn evaluate_turn(evaluator: &dyn Evaluator, input: &EvaluationInput) -> Evaluation {
+    if input.transcript.trim().is_empty() {
+        return Evaluation::silent();
+    }
+
+    match evaluator.evaluate(input).await {
+        Ok(evaluation) => evaluation,
+        Err(err) => {
+            log::warn!("evaluation failed, falling back to rules: {:#}", err);
+            rules::evaluate(input)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weighted_scores() {
+        let raw = RawScores {
+            pronunciation: 93,
+            grammar: 97,
+            vocabulary: 90,
+            fluency: 85,
+        };
+        let evaluation = Evaluation::from_raw(raw, String::new(), Vec::new(), Vec::new());
+        assert_eq!(evaluation.pronunciation, 28);
+        assert_eq!(evaluation.grammar, 29);
+        assert_eq!(evaluation.vocabulary, 18);
+        assert_eq!(evaluation.fluency, 17);
+        assert_eq!(evaluation.total, 92);
+    }
+
+    #[test]
+    fn test_weighted_scores_are_capped() {
+        let raw = RawScores {
+            pronunciation: 250,
+            grammar: 100,
+            vocabulary: 100,
+            fluency: 100,
+        };
+        let evaluation = Evaluation::from_raw(raw, String::new(), Vec::new(), Vec::new());
+        assert_eq!(evaluation.total, 100);
+    }
+
+    struct FailingEvaluator;
+
+    #[async_trait]
+    impl Evaluator for FailingEvaluator {
+        async fn evaluate(&self, _input: &EvaluationInput) -> anyhow::Result<Evaluation> {
+            anyhow::bail!("provider unavailable")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_turn_falls_back_to_rules() {
+        let input = EvaluationInput {
+            prompt: "Can you review my pull request?".to_string(),
+            transcript: "Sure, I will review the pull request this afternoon.".to_string(),
+            ..Default::default()
+        };
+        let evaluation = evaluate_turn(&FailingEvaluator, &input).await;
+        assert!(evaluation.total > 0);
+
+        let silent = evaluate_turn(&FailingEvaluator, &EvaluationInput::default()).await;
+        assert_eq!(silent.total, 0);
+    }
+}
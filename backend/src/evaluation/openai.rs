@@ -0,0 +1,247 @@
+// src/evaluation/openai.rs
+
+use super::{ErrorItem, Evaluation, EvaluationInput, Evaluator, RawScores, ERROR_TYPES};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::future::Future;
+use std::time::Duration;
+
+/// 第一次重試前的等待時間，之後每次加倍
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+const SYSTEM_PROMPT: &str = r#"You are an English speaking examiner for software engineers practising workplace conversations.
+Rate the learner's spoken reply to the prompt on four criteria, each an integer from 0 to 100:
+pronunciation (clarity and accuracy, use the speech recognition confidence as a hint),
+grammar (sentence structure and tense), vocabulary (word choice and technical terms),
+fluency (pace and natural pauses).
+Reply with a single JSON object and nothing else:
+{"pronunciation":0,"grammar":0,"vocabulary":0,"fluency":0,"feedback":"...","suggestions":["..."],"errors":[{"type":"grammar","original":"...","suggested":"..."}]}
+"type" must be one of pronunciation, grammar, vocabulary, fluency.
+Write feedback and suggestions in Traditional Chinese (zh-TW); keep original and suggested in English."#;
+
+/// 模型輸出格式，不接受多餘或缺少的欄位
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ModelOutput {
+    pronunciation: u32,
+    grammar: u32,
+    vocabulary: u32,
+    fluency: u32,
+    feedback: String,
+    suggestions: Vec<String>,
+    errors: Vec<ErrorItem>,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+/// OpenAI 相容的 Chat Completions API 評估
+pub struct OpenAiEvaluator {
+    endpoint: String,
+    api_key: String,
+    model: String,
+    max_retries: u32,
+    client: reqwest::Client,
+}
+
+impl OpenAiEvaluator {
+    pub fn new(base_url: &str, api_key: &str, model: &str, max_retries: u32) -> anyhow::Result<Self> {
+        if api_key.is_empty() {
+            anyhow::bail!("AI_API_KEY is required for the openai AI provider");
+        }
+        Ok(Self {
+            endpoint: format!("{}/chat/completions", base_url.trim_end_matches('/')),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            max_retries,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()?,
+        })
+    }
+
+    async fn request(&self, input: &EvaluationInput) -> anyhow::Result<Evaluation> {
+        let user = serde_json::json!({
+            "prompt": input.prompt,
+            "speaker_role": input.speaker_role,
+            "reply": input.transcript,
+            "recognition_confidence": input.confidence,
+            "words": input.words,
+        });
+        let body = serde_json::json!({
+            "model": self.model,
+            "temperature": 0,
+            "response_format": { "type": "json_object" },
+            "messages": [
+                { "role": "system", "content": SYSTEM_PROMPT },
+                { "role": "user", "content": user.to_string() },
+            ],
+        });
+
+        let response: ChatResponse = self
+            .client
+            .post(&self.endpoint)
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let content = response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .ok_or_else(|| anyhow::anyhow!("AI response has no choices"))?;
+
+        parse_model_output(&content)
+    }
+}
+
+#[async_trait]
+impl Evaluator for OpenAiEvaluator {
+    async fn evaluate(&self, input: &EvaluationInput) -> anyhow::Result<Evaluation> {
+        // 連線錯誤與格式錯誤都重試 (模型偶爾會輸出不合格的 JSON)
+        with_retries(self.max_retries, RETRY_BASE_DELAY, || self.request(input)).await
+    }
+}
+
+/// 解析模型輸出並轉換為配分；分數超出範圍或錯誤類型不明時視為無效
+fn parse_model_output(content: &str) -> anyhow::Result<Evaluation> {
+    let json = strip_code_fence(content);
+    let output: ModelOutput = serde_json::from_str(json)
+        .map_err(|err| anyhow::anyhow!("invalid evaluation JSON: {}", err))?;
+
+    for (name, score) in [
+        ("pronunciation", output.pronunciation),
+        ("grammar", output.grammar),
+        ("vocabulary", output.vocabulary),
+        ("fluency", output.fluency),
+    ] {
+        if score > 100 {
+            anyhow::bail!("{} score {} is out of range", name, score);
+        }
+    }
+    if output.feedback.trim().is_empty() {
+        anyhow::bail!("evaluation feedback is empty");
+    }
+    if let Some(item) = output
+        .errors
+        .iter()
+        .find(|item| !ERROR_TYPES.contains(&item.error_type.as_str()))
+    {
+        anyhow::bail!("unknown error type {}", item.error_type);
+    }
+
+    let raw = RawScores {
+        pronunciation: output.pronunciation,
+        grammar: output.grammar,
+        vocabulary: output.vocabulary,
+        fluency: output.fluency,
+    };
+    Ok(Evaluation::from_raw(
+        raw,
+        output.feedback.trim().to_string(),
+        output.suggestions,
+        output.errors,
+    ))
+}
+
+/// 模型有時會用 ```json 包住輸出
+fn strip_code_fence(content: &str) -> &str {
+    let trimmed = content.trim();
+    trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map(str::trim)
+        .unwrap_or(trimmed)
+}
+
+/// 失敗時以指數退避重試，最多 `max_retries` 次
+async fn with_retries<T, F, Fut>(
+    max_retries: u32,
+    base_delay: Duration,
+    mut operation: F,
+) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < max_retries => {
+                log::warn!("AI evaluation attempt {} failed: {:#}", attempt + 1, err);
+                tokio::time::sleep(base_delay * 2u32.pow(attempt)).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const VALID: &str = r#"{"pronunciation":93,"grammar":97,"vocabulary":90,"fluency":85,
+        "feedback":"發音清楚","suggestions":["句子間稍作停頓"],
+        "errors":[{"type":"grammar","original":"I has","suggested":"I have"}]}"#;
+
+    #[test]
+    fn test_parse_valid_output() {
+        let evaluation = parse_model_output(VALID).unwrap();
+        assert_eq!(evaluation.total, 92);
+        assert_eq!(evaluation.errors[0].suggested, "I have");
+
+        let fenced = format!("```json\n{}\n```", VALID);
+        assert_eq!(parse_model_output(&fenced).unwrap(), evaluation);
+    }
+
+    #[test]
+    fn test_reject_invalid_output() {
+        assert!(parse_model_output("Great job!").is_err());
+        assert!(parse_model_output(&VALID.replace("93", "930")).is_err());
+        assert!(parse_model_output(&VALID.replace(r#""type":"grammar""#, r#""type":"style""#)).is_err());
+        assert!(parse_model_output(&VALID.replace(r#""fluency":85,"#, "")).is_err());
+        assert!(parse_model_output(&VALID.replace(r#""fluency":85,"#, r#""fluency":85,"extra":1,"#)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_with_retries_is_bounded() {
+        let calls = AtomicU32::new(0);
+        let result: anyhow::Result<()> = with_retries(2, Duration::ZERO, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            anyhow::bail!("unavailable")
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let calls = AtomicU32::new(0);
+        let result = with_retries(2, Duration::ZERO, || async {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                anyhow::bail!("invalid evaluation JSON")
+            }
+            Ok(7)
+        })
+        .await;
+        assert_eq!(result.unwrap(), 7);
+    }
+}
@@ -0,0 +1,209 @@
+// src/evaluation/rules.rs
+
+use super::{ErrorItem, Evaluation, EvaluationInput, Evaluator, RawScores};
+use async_trait::async_trait;
+use std::collections::HashSet;
+
+/// 回答達到這個字數即視為完整
+const FULL_ANSWER_WORDS: usize = 12;
+/// 字與字間隔超過這個毫秒數視為不自然的停頓
+const LONG_PAUSE_MS: u64 = 1_200;
+/// 自然語速範圍 (每分鐘字數)
+const NATURAL_WPM: (f32, f32) = (100.0, 170.0);
+
+/// 本機規則評估，不需外部服務；AI 評估失敗時也以此備援
+pub struct RuleEvaluator;
+
+#[async_trait]
+impl Evaluator for RuleEvaluator {
+    async fn evaluate(&self, input: &EvaluationInput) -> anyhow::Result<Evaluation> {
+        Ok(evaluate(input))
+    }
+}
+
+/// 規則評分
+///
+/// - 發音：語音辨識信心值 × 回答完整度
+/// - 語法：過短回答、小寫的 "i" 扣分
+/// - 用詞：不重複字數，呼應題目關鍵字加分
+/// - 流暢度：有時間軸時依語速與長停頓，否則依回答完整度；重複字扣分
+pub fn evaluate(input: &EvaluationInput) -> Evaluation {
+    let words = tokenize(&input.transcript);
+    if words.is_empty() {
+        return Evaluation::silent();
+    }
+
+    let completeness = words.len().min(FULL_ANSWER_WORDS) as f32 / FULL_ANSWER_WORDS as f32;
+    let mut errors = Vec::new();
+    let mut suggestions = Vec::new();
+
+    let confidence = if input.confidence > 0.0 { input.confidence.min(1.0) } else { 1.0 };
+    let pronunciation = 100.0 * confidence * (0.4 + 0.6 * completeness);
+    if input.confidence > 0.0 && input.confidence < 0.7 {
+        suggestions.push("放慢速度，把每個字的字尾發清楚".to_string());
+    }
+
+    let mut repeats = 0;
+    for pair in words.windows(2).filter(|pair| pair[0] == pair[1]) {
+        repeats += 1;
+        errors.push(ErrorItem {
+            error_type: "fluency".to_string(),
+            original: format!("{} {}", pair[0], pair[1]),
+            suggested: pair[0].clone(),
+        });
+    }
+    let mut grammar = 100i32;
+    if words.len() < 3 {
+        grammar -= 35;
+        suggestions.push("試著用完整的句子回應".to_string());
+    }
+    if input.transcript.split_whitespace().any(|word| word == "i" || word.starts_with("i'")) {
+        grammar -= 10;
+        errors.push(ErrorItem {
+            error_type: "grammar".to_string(),
+            original: "i".to_string(),
+            suggested: "I".to_string(),
+        });
+    }
+
+    let distinct: HashSet<&str> = words.iter().map(String::as_str).collect();
+    let prompt_words: HashSet<String> = tokenize(&input.prompt)
+        .into_iter()
+        .filter(|word| word.len() >= 4)
+        .collect();
+    let on_topic = words.iter().any(|word| prompt_words.contains(word));
+    let vocabulary = (distinct.len() * 8 + if on_topic { 20 } else { 0 }).min(100);
+    if !on_topic {
+        suggestions.push("回答時可以呼應對方提到的重點".to_string());
+    }
+
+    let fluency = (fluency_score(input, completeness) - repeats as f32 * 15.0).max(0.0);
+    if fluency < 70.0 {
+        suggestions.push("減少句中的長停頓，維持穩定語速".to_string());
+    }
+
+    let raw = RawScores {
+        pronunciation: pronunciation.round() as u32,
+        grammar: grammar.max(0) as u32,
+        vocabulary: vocabulary as u32,
+        fluency: fluency.round() as u32,
+    };
+
+    let feedback = if words.len() < 3 {
+        "回答太短，試著用完整的句子回應。"
+    } else if !on_topic {
+        "回答完整，可以多呼應對方提到的重點。"
+    } else if !errors.is_empty() {
+        "內容切題，注意避免重複字詞與小錯誤。"
+    } else {
+        "回答切題且完整，繼續保持！"
+    };
+
+    Evaluation::from_raw(raw, feedback.to_string(), suggestions, errors)
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn fluency_score(input: &EvaluationInput, completeness: f32) -> f32 {
+    let (Some(first), Some(last)) = (input.words.first(), input.words.last()) else {
+        return 100.0 * completeness;
+    };
+    let duration_ms = last.end_ms.saturating_sub(first.start_ms);
+    if input.words.len() < 2 || duration_ms == 0 {
+        return 100.0 * completeness;
+    }
+
+    let wpm = input.words.len() as f32 / (duration_ms as f32 / 60_000.0);
+    let (low, high) = NATURAL_WPM;
+    let pace = if wpm < low {
+        100.0 * wpm / low
+    } else if wpm > high {
+        (100.0 - (wpm - high) * 0.5).max(40.0)
+    } else {
+        100.0
+    };
+
+    let long_pauses = input
+        .words
+        .windows(2)
+        .filter(|pair| pair[1].start_ms.saturating_sub(pair[0].end_ms) > LONG_PAUSE_MS)
+        .count() as f32;
+
+    ((pace - long_pauses * 10.0) * (0.5 + 0.5 * completeness)).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speech::WordTiming;
+
+    fn input(transcript: &str) -> EvaluationInput {
+        EvaluationInput {
+            prompt: "Can you review my pull request today?".to_string(),
+            speaker_role: "Tech Lead".to_string(),
+            transcript: transcript.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn timed(words: &[(&str, u64, u64)]) -> Vec<WordTiming> {
+        words
+            .iter()
+            .map(|(word, start_ms, end_ms)| WordTiming {
+                word: word.to_string(),
+                start_ms: *start_ms,
+                end_ms: *end_ms,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_full_answer_scores_high() {
+        let evaluation = evaluate(&input(
+            "Sure, I will review the pull request after lunch and leave comments on the tests.",
+        ));
+        assert_eq!(evaluation.grammar, 30);
+        assert_eq!(evaluation.fluency, 20);
+        assert!(evaluation.errors.is_empty());
+        assert!(evaluation.total >= 90);
+    }
+
+    #[test]
+    fn test_short_repeated_answer_penalized() {
+        let evaluation = evaluate(&input("Yes yes"));
+        assert_eq!(evaluation.grammar, 20);
+        assert!(evaluation.fluency < evaluate(&input("Yes sure")).fluency);
+        assert_eq!(evaluation.errors[0].error_type, "fluency");
+        assert_eq!(evaluation.errors[0].suggested, "yes");
+        assert!(evaluation.total < 50);
+    }
+
+    #[test]
+    fn test_long_pauses_reduce_fluency() {
+        let mut smooth = input("Sure I can review it");
+        smooth.words = timed(&[
+            ("Sure", 0, 300),
+            ("I", 350, 450),
+            ("can", 500, 800),
+            ("review", 850, 1300),
+            ("it", 1350, 1600),
+        ]);
+        let mut halting = smooth.clone();
+        halting.words = timed(&[
+            ("Sure", 0, 300),
+            ("I", 2000, 2100),
+            ("can", 4000, 4300),
+            ("review", 4350, 4800),
+            ("it", 4850, 5100),
+        ]);
+        assert!(evaluate(&halting).fluency < evaluate(&smooth).fluency);
+    }
+}
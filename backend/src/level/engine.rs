@@ -0,0 +1,261 @@
+// src/level/engine.rs
+
+use serde::{Deserialize, Serialize};
+
+/// 最高等級
+pub const MAX_LEVEL: i32 = 10;
+/// 連續達標幾次升級
+pub const CONSECUTIVE_REQUIRED: i32 = 3;
+/// 累計達標幾次升級
+pub const CUMULATIVE_REQUIRED: i32 = 6;
+/// 解鎖付費版 80% 折扣的等級
+pub const DISCOUNT_LEVEL_5: i32 = 5;
+/// 解鎖付費版 60% 折扣的等級
+pub const DISCOUNT_LEVEL_10: i32 = 10;
+
+/// `user_levels` 的等級欄位
+///
+/// `consecutive_wins` 與 `cumulative_practices` 只計算目前等級的達標次數，升級後歸零。
+#[derive(Debug, Clone, Default, PartialEq, Eq, sqlx::FromRow)]
+pub struct LevelState {
+    pub current_level: i32,
+    pub total_score: i32,
+    pub consecutive_wins: i32,
+    pub cumulative_practices: i32,
+    pub level_5_unlocked: bool,
+    pub level_10_unlocked: bool,
+}
+
+/// 練習完成回應中的 `level_up` (見 Document/API.md)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelUp {
+    pub leveled_up: bool,
+    pub new_level: i32,
+    pub message: String,
+}
+
+/// 套用一次練習後的結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelOutcome {
+    pub state: LevelState,
+    /// 這次練習是否達到目前等級的門檻
+    pub qualified: bool,
+    pub level_up: LevelUp,
+}
+
+/// 從 `level` 升到下一級需要的練習分數
+///
+/// 等級 L 的門檻為 (L + 1) × 10 分；依 LEVEL_SYSTEM.md，升到 10 級的門檻與 9 級同為 90 分。
+pub fn qualifying_score(level: i32) -> u32 {
+    ((level.clamp(0, MAX_LEVEL - 1) + 1) * 10).min(90) as u32
+}
+
+/// 已解鎖的訂閱折扣 (百分比)：5 級 8 折、10 級 6 折
+pub fn discount_percentage(state: &LevelState) -> u32 {
+    if state.level_10_unlocked {
+        40
+    } else if state.level_5_unlocked {
+        20
+    } else {
+        0
+    }
+}
+
+/// 升到下一級的進度 (百分比)：連續或累計達標次數中較接近門檻者；最高等級為 100
+pub fn progress_percent(state: &LevelState) -> u32 {
+    if state.current_level >= MAX_LEVEL {
+        return 100;
+    }
+    let consecutive = state.consecutive_wins.clamp(0, CONSECUTIVE_REQUIRED) * 100 / CONSECUTIVE_REQUIRED;
+    let cumulative = state.cumulative_practices.clamp(0, CUMULATIVE_REQUIRED) * 100 / CUMULATIVE_REQUIRED;
+    consecutive.max(cumulative) as u32
+}
+
+/// 依一次練習的總分更新等級狀態
+///
+/// - 達到門檻：連續與累計次數各加一；連續 3 次或累計 6 次即升一級，兩個計數歸零
+/// - 未達門檻：連續次數歸零，累計次數保留 (等級只升不降)
+/// - 到達 5 級與 10 級時解鎖對應折扣，解鎖後不會收回
+pub fn apply_practice(state: &LevelState, score: u32) -> LevelOutcome {
+    let mut next = state.clone();
+    next.total_score = state.total_score.saturating_add(score.min(100) as i32);
+
+    if state.current_level >= MAX_LEVEL {
+        return LevelOutcome {
+            qualified: score >= qualifying_score(MAX_LEVEL),
+            level_up: LevelUp {
+                leveled_up: false,
+                new_level: MAX_LEVEL,
+                message: "You've reached the highest level!".to_string(),
+            },
+            state: next,
+        };
+    }
+
+    let qualified = score >= qualifying_score(state.current_level);
+    if qualified {
+        next.consecutive_wins += 1;
+        next.cumulative_practices += 1;
+    } else {
+        next.consecutive_wins = 0;
+    }
+
+    let leveled_up = next.consecutive_wins >= CONSECUTIVE_REQUIRED
+        || next.cumulative_practices >= CUMULATIVE_REQUIRED;
+    if leveled_up {
+        next.current_level = state.current_level + 1;
+        next.consecutive_wins = 0;
+        next.cumulative_practices = 0;
+    }
+    next.level_5_unlocked |= next.current_level >= DISCOUNT_LEVEL_5;
+    next.level_10_unlocked |= next.current_level >= DISCOUNT_LEVEL_10;
+
+    let message = if leveled_up {
+        format!("Congratulations! You've reached Level {}!", next.current_level)
+    } else {
+        let remaining = (CONSECUTIVE_REQUIRED - next.consecutive_wins)
+            .min(CUMULATIVE_REQUIRED - next.cumulative_practices);
+        format!(
+            "Score {}+ {} more time{} to reach Level {}.",
+            qualifying_score(next.current_level),
+            remaining,
+            if remaining == 1 { "" } else { "s" },
+            next.current_level + 1
+        )
+    };
+
+    LevelOutcome {
+        level_up: LevelUp {
+            leveled_up,
+            new_level: next.current_level,
+            message,
+        },
+        qualified,
+        state: next,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(state: LevelState, scores: &[u32]) -> (LevelState, Vec<bool>) {
+        let mut state = state;
+        let mut level_ups = Vec::new();
+        for &score in scores {
+            let outcome = apply_practice(&state, score);
+            level_ups.push(outcome.level_up.leveled_up);
+            state = outcome.state;
+        }
+        (state, level_ups)
+    }
+
+    #[test]
+    fn test_qualifying_scores() {
+        assert_eq!(qualifying_score(0), 10);
+        assert_eq!(qualifying_score(4), 50);
+        assert_eq!(qualifying_score(8), 90);
+        assert_eq!(qualifying_score(9), 90);
+    }
+
+    #[test]
+    fn test_three_consecutive_levels_up() {
+        let (state, level_ups) = run(LevelState::default(), &[12, 15, 11]);
+        assert_eq!(level_ups, vec![false, false, true]);
+        assert_eq!(state.current_level, 1);
+        assert_eq!(state.consecutive_wins, 0);
+        assert_eq!(state.cumulative_practices, 0);
+        assert_eq!(state.total_score, 38);
+    }
+
+    #[test]
+    fn test_miss_resets_consecutive_only() {
+        let (state, level_ups) = run(LevelState::default(), &[12, 15, 5]);
+        assert!(level_ups.iter().all(|up| !up));
+        assert_eq!(state.consecutive_wins, 0);
+        assert_eq!(state.cumulative_practices, 2);
+    }
+
+    #[test]
+    fn test_six_cumulative_levels_up() {
+        // 交錯未達標，連續次數始終不到 3，第 6 次達標時升級
+        let (state, level_ups) = run(LevelState::default(), &[12, 12, 5, 12, 12, 5, 12, 12]);
+        assert_eq!(level_ups.iter().filter(|up| **up).count(), 1);
+        assert!(level_ups[7]);
+        assert_eq!(state.current_level, 1);
+    }
+
+    #[test]
+    fn test_never_demotes() {
+        let start = LevelState {
+            current_level: 6,
+            ..Default::default()
+        };
+        let (state, _) = run(start, &[0, 0, 0, 0]);
+        assert_eq!(state.current_level, 6);
+    }
+
+    #[test]
+    fn test_unlocks_discounts() {
+        let start = LevelState {
+            current_level: 4,
+            consecutive_wins: 2,
+            cumulative_practices: 2,
+            ..Default::default()
+        };
+        let outcome = apply_practice(&start, 55);
+        assert!(outcome.level_up.leveled_up);
+        assert_eq!(outcome.level_up.new_level, 5);
+        assert_eq!(outcome.level_up.message, "Congratulations! You've reached Level 5!");
+        assert!(outcome.state.level_5_unlocked);
+        assert!(!outcome.state.level_10_unlocked);
+
+        let nine = LevelState {
+            current_level: 9,
+            consecutive_wins: 2,
+            level_5_unlocked: true,
+            ..Default::default()
+        };
+        let outcome = apply_practice(&nine, 92);
+        assert_eq!(outcome.state.current_level, MAX_LEVEL);
+        assert!(outcome.state.level_5_unlocked && outcome.state.level_10_unlocked);
+        assert_eq!(discount_percentage(&outcome.state), 40);
+    }
+
+    #[test]
+    fn test_max_level_stays() {
+        let top = LevelState {
+            current_level: MAX_LEVEL,
+            level_5_unlocked: true,
+            level_10_unlocked: true,
+            ..Default::default()
+        };
+        let outcome = apply_practice(&top, 100);
+        assert!(!outcome.level_up.leveled_up);
+        assert_eq!(outcome.state.current_level, MAX_LEVEL);
+        assert_eq!(outcome.state.total_score, 100);
+    }
+
+    #[test]
+    fn test_progress_message() {
+        let outcome = apply_practice(&LevelState::default(), 12);
+        assert_eq!(outcome.level_up.message, "Score 10+ 2 more times to reach Level 1.");
+    }
+
+    #[test]
+    fn test_progress_percent() {
+        let state = LevelState {
+            current_level: 4,
+            consecutive_wins: 1,
+            cumulative_practices: 5,
+            ..Default::default()
+        };
+        assert_eq!(progress_percent(&state), 83);
+        assert_eq!(progress_percent(&LevelState::default()), 0);
+        let top = LevelState {
+            current_level: MAX_LEVEL,
+            ..Default::default()
+        };
+        assert_eq!(progress_percent(&top), 100);
+    }
+}
@@ -0,0 +1,77 @@
+// src/level/mod.rs
+
+mod engine;
+
+pub use engine::{
+    apply_practice, discount_percentage, progress_percent, qualifying_score, LevelOutcome, LevelState, LevelUp,
+    CONSECUTIVE_REQUIRED, CUMULATIVE_REQUIRED, MAX_LEVEL,
+};
+
+use crate::error::AppResult;
+use chrono::Utc;
+use sqlx::{MySql, MySqlPool, Transaction};
+
+/// 讀取用戶等級；還沒有練習記錄時為預設值
+pub async fn load_level(pool: &MySqlPool, user_id: &str) -> AppResult<LevelState> {
+    let state: Option<LevelState> = sqlx::query_as(
+        r#"
+        SELECT current_level, total_score, consecutive_wins, cumulative_practices,
+               level_5_unlocked, level_10_unlocked
+        FROM user_levels
+        WHERE user_id = ?
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(state.unwrap_or_default())
+}
+
+/// 在練習完成的交易中更新用戶等級 (鎖定該用戶的 `user_levels` 列，避免同時完成兩次練習時計數遺失)
+pub async fn record_practice(
+    tx: &mut Transaction<'_, MySql>,
+    user_id: &str,
+    score: u32,
+) -> AppResult<LevelOutcome> {
+    sqlx::query("INSERT IGNORE INTO user_levels (id, user_id) VALUES (?, ?)")
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await?;
+
+    let state: LevelState = sqlx::query_as(
+        r#"
+        SELECT current_level, total_score, consecutive_wins, cumulative_practices,
+               level_5_unlocked, level_10_unlocked
+        FROM user_levels
+        WHERE user_id = ?
+        FOR UPDATE
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let outcome = apply_practice(&state, score);
+    let next = &outcome.state;
+    sqlx::query(
+        r#"
+        UPDATE user_levels
+        SET current_level = ?, total_score = ?, consecutive_wins = ?, cumulative_practices = ?,
+            level_5_unlocked = ?, level_10_unlocked = ?, last_practice_at = ?
+        WHERE user_id = ?
+        "#,
+    )
+    .bind(next.current_level)
+    .bind(next.total_score)
+    .bind(next.consecutive_wins)
+    .bind(next.cumulative_practices)
+    .bind(next.level_5_unlocked)
+    .bind(next.level_10_unlocked)
+    .bind(Utc::now())
+    .bind(user_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(outcome)
+}
@@ -1,6 +1,20 @@
 pub mod config;
+pub mod error;
+pub mod state;
 pub mod auth;
 pub mod conversation;
+pub mod practice;
 pub mod user;
 pub mod device;
+pub mod trial;
+pub mod notification;
+pub mod speech;
+pub mod evaluation;
+pub mod level;
+pub mod notebook;
+pub mod subscription;
+pub mod payment;
 pub mod database;
+pub mod vocabulary;
+pub mod scenario;
+pub mod statistics;
@@ -1,47 +1,77 @@
-use axum::{routing::post, Router};
-use dotenv::dotenv;
 use std::net::SocketAddr;
 use tower_http::cors::{Any, CorsLayer};
 
-mod config;
-mod auth;
-mod conversation;
-mod user;
-mod database;
-mod device;
-
-use config::Config;
-use axum::routing::post;
+use nice_speak_backend::config::Config;
+use nice_speak_backend::state::AppState;
+use nice_speak_backend::{
+    auth, conversation, database, device, notebook, payment, practice, scenario, speech, statistics, subscription,
+    trial, user, vocabulary,
+};
+use axum::extract::DefaultBodyLimit;
+use axum::routing::{get, post, put};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    dotenv().ok();
     env_logger::init();
     let config = Config::from_env()?;
-    let pool = database::create_pool(&config.database_url).await?;
+    let pool = database::create_pool(&config.database).await?;
+    database::run_migrations(&pool).await?;
+    let redis = database::create_redis(&config).await?;
+    let mongo = database::create_mongo(&config).await?;
 
-    let cors = tower_http::cors::CorsLayer::new()
-        .allow_origin(tower_http::cors::Any)
-        .allow_methods(tower_http::cors::Any)
-        .allow_headers(tower_http::cors::Any);
+    let cors = CorsLayer::new()
+        .allow_origin(Any)
+        .allow_methods(Any)
+        .allow_headers(Any);
 
     let app = axum::Router::new()
         .route("/health", post(|| async { "OK" }))
         .route("/api/v1/auth/register", post(auth::register))
         .route("/api/v1/auth/login", post(auth::login))
-        .route("/api/v1/user/profile", post(user::get_profile))
+        .route("/api/v1/auth/refresh", post(auth::refresh))
+        .route("/api/v1/auth/logout", post(auth::logout))
+        .route("/api/v1/user/profile", get(user::get_profile))
+        .route("/api/v1/user/errors", get(notebook::list_errors))
         .route("/api/v1/devices/status", post(device::check_status))
         .route("/api/v1/devices/register", post(device::register_device))
         .route("/api/v1/devices/mark-trial-used", post(device::mark_trial_used))
+        .route("/api/v1/devices", get(device::list_devices))
+        .route("/api/v1/devices/bind", post(device::bind_device))
+        .route("/api/v1/devices/unbind", post(device::unbind_device))
+        .route("/api/v1/devices/transfer-code", post(device::issue_transfer_code))
+        .route("/api/v1/devices/transfer", post(device::transfer_device))
+        .route("/api/v1/devices/events", post(device::ingest_events))
+        .route("/api/v1/trial/request", post(trial::request_trial))
+        .route("/api/v1/scenarios", get(scenario::list))
+        .route("/api/v1/scenarios/categories", get(scenario::categories))
+        .route("/api/v1/scenarios/:id", get(scenario::detail))
+        .route("/api/v1/practice/start", post(practice::start))
+        .route(
+            "/api/v1/practice/:id/submit",
+            post(practice::submit).layer(DefaultBodyLimit::max(practice::MAX_SUBMIT_BODY_BYTES)),
+        )
+        .route("/api/v1/practice/:id/complete", post(practice::complete))
+        .route("/api/v1/practice/:id/abandon", post(practice::abandon))
+        .route("/api/v1/subscription/plans", get(subscription::plans))
+        .route("/api/v1/subscription/quote", post(subscription::quote))
+        .route("/api/v1/subscription/purchase", post(subscription::purchase))
+        .route("/api/v1/subscription/status", get(subscription::status))
+        .route("/api/v1/subscription/roles", put(subscription::update_roles))
+        .route("/api/v1/subscription/cancel", post(subscription::cancel))
+        .route(payment::NOTIFY_PATH, post(subscription::payment_notify))
+        .route(payment::PERIOD_NOTIFY_PATH, post(subscription::period_notify))
+        .route("/api/v1/statistics/overview", get(statistics::overview))
+        .route("/api/v1/vocabulary/review", get(vocabulary::review_queue))
+        .route("/api/v1/vocabulary/:id/review", post(vocabulary::submit_review))
+        .route("/ws/practice", get(conversation::practice_ws))
+        .route("/media/tts/:file", get(speech::serve_tts_audio))
         .layer(cors)
-        .with_state(pool.clone());
+        .with_state(AppState::new(pool.clone(), redis, mongo, config.clone())?);
 
-    let addr = std::net::SocketAddr::from(([0, 0, 0, 0], config.port));
-    log::info!("🚀 Server running on http://{}", addr);
+    let listener = tokio::net::TcpListener::bind(config.listen_addr()).await?;
+    log::info!("🚀 Server running on http://{}", listener.local_addr()?);
 
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
-        .await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
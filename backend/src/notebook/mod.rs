@@ -0,0 +1,98 @@
+// src/notebook/mod.rs
+
+mod review;
+mod store;
+
+pub use review::{normalize, MASTERY_STREAK};
+pub use store::{ErrorFilter, ErrorLog, ErrorNotebook};
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::evaluation::ERROR_TYPES;
+use crate::state::AppState;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 每頁預設筆數
+const DEFAULT_LIMIT: u64 = 20;
+const MAX_LIMIT: u64 = 100;
+
+#[derive(Deserialize)]
+pub struct ErrorQuery {
+    /// pronunciation / grammar / vocabulary / fluency
+    #[serde(rename = "type")]
+    error_type: Option<String>,
+    mastered: Option<bool>,
+    page: Option<u64>,
+    limit: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct ErrorEntry {
+    id: String,
+    #[serde(rename = "type")]
+    error_type: String,
+    original: String,
+    corrected: String,
+    suggested: String,
+    frequency: i64,
+    mastered: bool,
+    last_seen_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct ErrorListResponse {
+    errors: Vec<ErrorEntry>,
+    total: u64,
+    page: u64,
+    limit: u64,
+}
+
+impl From<ErrorLog> for ErrorEntry {
+    fn from(log: ErrorLog) -> Self {
+        Self {
+            id: log.id.to_hex(),
+            last_seen_at: log.updated_at(),
+            error_type: log.error_type,
+            original: log.original_text,
+            corrected: log.corrected_text,
+            suggested: log.suggested_text,
+            frequency: log.frequency,
+            mastered: log.mastered,
+        }
+    }
+}
+
+/// `GET /api/v1/user/errors` 錯誤本，可依類型與是否已掌握篩選
+pub async fn list_errors(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<ErrorQuery>,
+) -> AppResult<Json<ErrorListResponse>> {
+    let error_type = match query.error_type.as_deref().map(str::trim).filter(|value| !value.is_empty()) {
+        Some(value) if ERROR_TYPES.contains(&value) => Some(value.to_string()),
+        Some(value) => return Err(AppError::Validation(format!("Unknown error type {}", value))),
+        None => None,
+    };
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let filter = ErrorFilter {
+        error_type,
+        mastered: query.mastered,
+        skip: (page - 1).saturating_mul(limit),
+        limit: limit as i64,
+    };
+    let (logs, total) = state.error_notebook.list(&user.user_id, &filter).await?;
+
+    Ok(Json(ErrorListResponse {
+        errors: logs.into_iter().map(ErrorEntry::from).collect(),
+        total,
+        page,
+        limit,
+    }))
+}
@@ -0,0 +1,130 @@
+// src/notebook/review.rs
+
+use std::collections::HashSet;
+
+/// 在之後的練習中答對幾次後標記為已掌握 (每次練習最多計一次)
+pub const MASTERY_STREAK: i64 = 2;
+
+/// 合併同一個錯誤用的正規化文字：小寫、去除標點 (保留縮寫的撇號)、合併空白
+pub fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .replace(['’', '‘'], "'")
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .map(|word| word.trim_matches('\''))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 正規化後的文字是否包含整段片語 (以單字為界)
+fn contains_phrase(text: &str, phrase: &str) -> bool {
+    !phrase.is_empty() && format!(" {} ", text).contains(&format!(" {} ", phrase))
+}
+
+/// 錯誤記錄的唯一鍵 (類型 + 正規化後的原文)
+pub type ErrorKey = (String, String);
+
+/// 錯誤本中尚未掌握的一筆記錄 (複習判斷需要的欄位)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenError {
+    pub key: ErrorKey,
+    pub suggested: String,
+    /// 最後一次出錯的練習
+    pub practice_id: String,
+    /// 最後一次答對的練習
+    pub last_correct_practice_id: Option<String>,
+    pub correct_count: i64,
+}
+
+/// 這一輪回答是否修正了先前的錯誤，是的話回傳新的答對次數
+///
+/// 只計算出錯之後的其他練習，且這一輪沒有再出現同樣的錯誤：需說出建議用法，
+/// 原文與建議不同時不能再說出原文。沒有建議用法的錯誤無法判斷是否修正，不會自動掌握。
+pub fn corrected(
+    error: &OpenError,
+    practice_id: &str,
+    transcript: &str,
+    flagged: &HashSet<ErrorKey>,
+) -> Option<i64> {
+    if error.practice_id == practice_id
+        || error.last_correct_practice_id.as_deref() == Some(practice_id)
+        || flagged.contains(&error.key)
+    {
+        return None;
+    }
+
+    let transcript = normalize(transcript);
+    let suggested = normalize(&error.suggested);
+    if transcript.is_empty() || suggested.is_empty() {
+        return None;
+    }
+    let original = &error.key.1;
+    let said_suggestion = contains_phrase(&transcript, &suggested);
+    let repeated_original = *original != suggested && contains_phrase(&transcript, original);
+
+    (said_suggestion && !repeated_original).then_some(error.correct_count + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(error_type: &str, original: &str, suggested: &str) -> OpenError {
+        OpenError {
+            key: (error_type.to_string(), normalize(original)),
+            suggested: suggested.to_string(),
+            practice_id: "p1".to_string(),
+            last_correct_practice_id: None,
+            correct_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("  I  want to, "), "i want to");
+        assert_eq!(normalize("I’d like to!"), "i'd like to");
+        assert_eq!(normalize("'Deploy' the API."), "deploy the api");
+        assert_eq!(normalize("..."), "");
+    }
+
+    #[test]
+    fn test_corrected_in_later_practice() {
+        let error = open("vocabulary", "I want to", "I'd like to");
+        let none = HashSet::new();
+        assert_eq!(corrected(&error, "p2", "I’d like to review the PR.", &none), Some(1));
+        // 出錯的同一次練習不算
+        assert_eq!(corrected(&error, "p1", "I'd like to review the PR.", &none), None);
+        // 同時說了原文也不算
+        assert_eq!(corrected(&error, "p2", "I want to, I'd like to review", &none), None);
+        // 以單字為界比對
+        assert_eq!(corrected(&error, "p2", "I'd liked to review", &none), None);
+    }
+
+    #[test]
+    fn test_corrected_once_per_practice() {
+        let mut error = open("grammar", "he go", "he goes");
+        error.last_correct_practice_id = Some("p2".to_string());
+        error.correct_count = 1;
+        let none = HashSet::new();
+        assert_eq!(corrected(&error, "p2", "he goes to the office", &none), None);
+        assert_eq!(corrected(&error, "p3", "he goes to the office", &none), Some(MASTERY_STREAK));
+    }
+
+    #[test]
+    fn test_pronunciation_needs_clean_attempt() {
+        let error = open("pronunciation", "deploy", "deploy");
+        let mut flagged = HashSet::new();
+        assert_eq!(corrected(&error, "p2", "We deploy on Friday", &flagged), Some(1));
+        flagged.insert(error.key.clone());
+        assert_eq!(corrected(&error, "p2", "We deploy on Friday", &flagged), None);
+        assert_eq!(corrected(&error, "p2", "", &HashSet::new()), None);
+    }
+
+    #[test]
+    fn test_error_without_suggestion_is_never_corrected() {
+        let error = open("grammar", "he go", "");
+        let none = HashSet::new();
+        assert_eq!(corrected(&error, "p2", "Let's review the PR", &none), None);
+        assert_eq!(corrected(&error, "p2", "he go to the office", &none), None);
+    }
+}
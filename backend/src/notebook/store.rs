@@ -0,0 +1,231 @@
+// src/notebook/store.rs
+
+use super::review::{corrected, normalize, ErrorKey, OpenError, MASTERY_STREAK};
+use crate::error::AppResult;
+use crate::evaluation::{ErrorItem, ERROR_TYPES};
+use chrono::{DateTime, Utc};
+use mongodb::bson::{self, doc, oid::ObjectId, Document};
+use mongodb::options::{FindOptions, IndexOptions, UpdateOptions};
+use mongodb::{Collection, Database, IndexModel};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Document/DATABASE.md `error_logs`
+const COLLECTION: &str = "error_logs";
+/// 每一輪最多檢查的未掌握錯誤數 (最近出錯的優先)
+const REVIEW_SCAN_LIMIT: i64 = 100;
+
+/// `error_logs` 的一筆錯誤；同一用戶同類型、正規化後相同的原文合併為一筆
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorLog {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub user_id: String,
+    /// 最後一次出錯的練習
+    pub practice_record_id: String,
+    pub error_type: String,
+    pub original_text: String,
+    pub corrected_text: String,
+    pub suggested_text: String,
+    pub normalized_text: String,
+    pub frequency: i64,
+    pub mastered: bool,
+    /// 出錯後在其他練習答對的次數 (再次出錯時歸零)
+    #[serde(default)]
+    pub correct_count: i64,
+    #[serde(default)]
+    pub last_correct_practice_id: Option<String>,
+    pub created_at: bson::DateTime,
+    pub updated_at: bson::DateTime,
+}
+
+impl ErrorLog {
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.updated_at.timestamp_millis()).unwrap_or_default()
+    }
+
+    fn open_error(&self) -> OpenError {
+        OpenError {
+            key: (self.error_type.clone(), self.normalized_text.clone()),
+            suggested: self.suggested_text.clone(),
+            practice_id: self.practice_record_id.clone(),
+            last_correct_practice_id: self.last_correct_practice_id.clone(),
+            correct_count: self.correct_count,
+        }
+    }
+}
+
+/// 錯誤本查詢條件
+#[derive(Debug, Clone, Default)]
+pub struct ErrorFilter {
+    pub error_type: Option<String>,
+    pub mastered: Option<bool>,
+    pub skip: u64,
+    pub limit: i64,
+}
+
+impl ErrorFilter {
+    fn to_document(&self, user_id: &str) -> Document {
+        let mut filter = doc! { "user_id": user_id };
+        if let Some(error_type) = &self.error_type {
+            filter.insert("error_type", error_type);
+        }
+        if let Some(mastered) = self.mastered {
+            filter.insert("mastered", mastered);
+        }
+        filter
+    }
+}
+
+/// 錯誤本 (MongoDB `error_logs`)
+#[derive(Clone)]
+pub struct ErrorNotebook {
+    collection: Collection<ErrorLog>,
+}
+
+impl ErrorNotebook {
+    pub fn new(db: &Database) -> Self {
+        Self {
+            collection: db.collection(COLLECTION),
+        }
+    }
+
+    /// 建立合併錯誤用的唯一索引、列表排序與複習掃描用的索引
+    pub async fn ensure_indexes(&self) -> AppResult<()> {
+        let unique = IndexModel::builder()
+            .keys(doc! { "user_id": 1, "error_type": 1, "normalized_text": 1 })
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+        let listing = IndexModel::builder()
+            .keys(doc! { "user_id": 1, "mastered": 1, "frequency": -1 })
+            .build();
+        let review = IndexModel::builder()
+            .keys(doc! { "user_id": 1, "mastered": 1, "updated_at": -1 })
+            .build();
+        self.collection.create_indexes([unique, listing, review], None).await?;
+        Ok(())
+    }
+
+    /// 記錄一輪評估：新的錯誤累加次數 (已掌握的錯誤重新開啟)，並檢查先前的錯誤是否已經答對
+    pub async fn record_turn(
+        &self,
+        user_id: &str,
+        practice_id: &str,
+        transcript: &str,
+        errors: &[ErrorItem],
+    ) -> AppResult<()> {
+        let now = bson::DateTime::now();
+        let mut flagged: HashSet<ErrorKey> = HashSet::new();
+        for item in errors {
+            let normalized = normalize(&item.original);
+            if !ERROR_TYPES.contains(&item.error_type.as_str()) || normalized.is_empty() {
+                continue;
+            }
+            if !flagged.insert((item.error_type.clone(), normalized.clone())) {
+                continue;
+            }
+
+            self.collection
+                .update_one(
+                    doc! { "user_id": user_id, "error_type": &item.error_type, "normalized_text": &normalized },
+                    doc! {
+                        "$inc": { "frequency": 1_i64 },
+                        "$set": {
+                            "practice_record_id": practice_id,
+                            "original_text": &item.original,
+                            "corrected_text": &item.suggested,
+                            "suggested_text": &item.suggested,
+                            "mastered": false,
+                            "correct_count": 0_i64,
+                            "last_correct_practice_id": bson::Bson::Null,
+                            "updated_at": now,
+                        },
+                        "$setOnInsert": { "created_at": now },
+                    },
+                    UpdateOptions::builder().upsert(true).build(),
+                )
+                .await?;
+        }
+
+        // 沒有建議用法的錯誤不會自動掌握，不需檢查
+        let options = FindOptions::builder()
+            .sort(doc! { "updated_at": -1 })
+            .limit(REVIEW_SCAN_LIMIT)
+            .build();
+        let mut cursor = self
+            .collection
+            .find(
+                doc! {
+                    "user_id": user_id,
+                    "mastered": false,
+                    "practice_record_id": { "$ne": practice_id },
+                    "suggested_text": { "$ne": "" },
+                },
+                options,
+            )
+            .await?;
+        let mut reviewed = Vec::new();
+        while cursor.advance().await? {
+            let log = cursor.deserialize_current()?;
+            if let Some(count) = corrected(&log.open_error(), practice_id, transcript, &flagged) {
+                reviewed.push((log.id, log.correct_count, count));
+            }
+        }
+
+        for (id, previous, count) in reviewed {
+            // 以原本的次數為條件，同時送出的兩輪不會重複計算
+            self.collection
+                .update_one(
+                    doc! { "_id": id, "correct_count": previous, "mastered": false },
+                    doc! {
+                        "$set": {
+                            "correct_count": count,
+                            "mastered": count >= MASTERY_STREAK,
+                            "last_correct_practice_id": practice_id,
+                            "updated_at": now,
+                        },
+                    },
+                    None,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// 用戶的錯誤本：未掌握的在前，依出錯次數與最後出錯時間排序
+    pub async fn list(&self, user_id: &str, filter: &ErrorFilter) -> AppResult<(Vec<ErrorLog>, u64)> {
+        let query = filter.to_document(user_id);
+        let total = self.collection.count_documents(query.clone(), None).await?;
+
+        let options = FindOptions::builder()
+            .sort(doc! { "mastered": 1, "frequency": -1, "updated_at": -1 })
+            .skip(filter.skip)
+            .limit(filter.limit)
+            .build();
+        let mut cursor = self.collection.find(query, options).await?;
+        let mut logs = Vec::new();
+        while cursor.advance().await? {
+            logs.push(cursor.deserialize_current()?);
+        }
+        Ok((logs, total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_document() {
+        let filter = ErrorFilter {
+            error_type: Some("grammar".to_string()),
+            mastered: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter.to_document("u1"),
+            doc! { "user_id": "u1", "error_type": "grammar", "mastered": false }
+        );
+        assert_eq!(ErrorFilter::default().to_document("u1"), doc! { "user_id": "u1" });
+    }
+}
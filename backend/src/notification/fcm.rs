@@ -0,0 +1,188 @@
+// src/notification/fcm.rs
+
+use super::{PushMessage, PushTransport, SendOutcome};
+use anyhow::Context;
+use async_trait::async_trait;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+const FCM_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+const FCM_ENDPOINT: &str = "https://fcm.googleapis.com/v1/projects";
+/// OAuth token 到期前提早更新的秒數
+const TOKEN_REFRESH_MARGIN_SECS: u64 = 60;
+
+/// Firebase 服務帳戶金鑰 (從 Firebase Console 下載的 JSON)
+#[derive(Deserialize)]
+struct ServiceAccount {
+    project_id: String,
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+#[derive(Serialize)]
+struct OAuthClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Deserialize)]
+struct OAuthToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// FCM HTTP v1 推播
+pub struct FcmTransport {
+    account: ServiceAccount,
+    client: reqwest::Client,
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl FcmTransport {
+    pub fn from_credentials_file(path: &str) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read FCM credentials file {}", path))?;
+        let account: ServiceAccount =
+            serde_json::from_str(&content).context("invalid FCM service account JSON")?;
+
+        Ok(Self {
+            account,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()?,
+            token: Mutex::new(None),
+        })
+    }
+
+    /// 以服務帳戶簽署 JWT 換取 OAuth access token，並快取到到期前
+    async fn access_token(&self) -> anyhow::Result<String> {
+        let mut cached = self.token.lock().await;
+        if let Some((token, expires_at)) = cached.as_ref() {
+            if *expires_at > Instant::now() {
+                return Ok(token.clone());
+            }
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let claims = OAuthClaims {
+            iss: &self.account.client_email,
+            scope: FCM_SCOPE,
+            aud: &self.account.token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let assertion = encode(
+            &Header::new(Algorithm::RS256),
+            &claims,
+            &EncodingKey::from_rsa_pem(self.account.private_key.as_bytes())?,
+        )?;
+
+        let token: OAuthToken = self
+            .client
+            .post(&self.account.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let ttl = token.expires_in.saturating_sub(TOKEN_REFRESH_MARGIN_SECS);
+        *cached = Some((
+            token.access_token.clone(),
+            Instant::now() + Duration::from_secs(ttl),
+        ));
+        Ok(token.access_token)
+    }
+}
+
+#[async_trait]
+impl PushTransport for FcmTransport {
+    async fn send(&self, message: &PushMessage) -> anyhow::Result<SendOutcome> {
+        let access_token = self.access_token().await?;
+        let url = format!("{}/{}/messages:send", FCM_ENDPOINT, self.account.project_id);
+        let body = serde_json::json!({
+            "message": {
+                "token": message.token,
+                "notification": {
+                    "title": message.title,
+                    "body": message.body,
+                },
+                "data": message.data,
+            }
+        });
+
+        let response = self
+            .client
+            .post(url)
+            .bearer_auth(access_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = response.status().as_u16();
+        if response.status().is_success() {
+            return Ok(SendOutcome::Delivered);
+        }
+        let text = response.text().await.unwrap_or_default();
+        Ok(classify_error(status, &text))
+    }
+}
+
+/// 依 FCM 錯誤回應判斷 token 是否已失效
+fn classify_error(status: u16, body: &str) -> SendOutcome {
+    let json: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+    let error = &json["error"];
+
+    let unregistered = error["details"]
+        .as_array()
+        .map(|details| {
+            details
+                .iter()
+                .any(|detail| detail["errorCode"] == "UNREGISTERED")
+        })
+        .unwrap_or(false);
+    let invalid_token = error["status"] == "INVALID_ARGUMENT"
+        && error["message"]
+            .as_str()
+            .map(|message| message.contains("registration token"))
+            .unwrap_or(false);
+
+    if status == 404 || unregistered || invalid_token {
+        SendOutcome::Unregistered
+    } else {
+        SendOutcome::Failed(format!("FCM responded {}: {}", status, body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_unregistered() {
+        let body = r#"{"error":{"code":404,"status":"NOT_FOUND","details":[{"errorCode":"UNREGISTERED"}]}}"#;
+        assert_eq!(classify_error(404, body), SendOutcome::Unregistered);
+    }
+
+    #[test]
+    fn test_classify_invalid_token() {
+        let body = r#"{"error":{"code":400,"status":"INVALID_ARGUMENT","message":"The registration token is not a valid FCM registration token"}}"#;
+        assert_eq!(classify_error(400, body), SendOutcome::Unregistered);
+    }
+
+    #[test]
+    fn test_classify_server_error() {
+        let body = r#"{"error":{"code":503,"status":"UNAVAILABLE"}}"#;
+        assert!(matches!(classify_error(503, body), SendOutcome::Failed(_)));
+    }
+}
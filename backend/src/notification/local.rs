@@ -0,0 +1,118 @@
+// src/notification/local.rs
+
+use super::{PushMessage, PushTransport, SendOutcome};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tokio::io::AsyncWriteExt;
+
+/// 記憶體推播，供測試檢查送出的訊息
+#[derive(Default)]
+pub struct MemoryTransport {
+    sent: Mutex<Vec<PushMessage>>,
+    unregistered: Mutex<HashSet<String>>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 讓指定 token 之後回報為已失效
+    pub fn mark_unregistered(&self, token: &str) {
+        self.unregistered.lock().unwrap().insert(token.to_string());
+    }
+
+    /// 已送出的訊息
+    pub fn sent(&self) -> Vec<PushMessage> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl PushTransport for MemoryTransport {
+    async fn send(&self, message: &PushMessage) -> anyhow::Result<SendOutcome> {
+        if self.unregistered.lock().unwrap().contains(&message.token) {
+            return Ok(SendOutcome::Unregistered);
+        }
+        self.sent.lock().unwrap().push(message.clone());
+        Ok(SendOutcome::Delivered)
+    }
+}
+
+/// 將推播以 JSON Lines 附加到檔案，供本機開發檢視
+pub struct FileTransport {
+    path: PathBuf,
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl FileTransport {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl PushTransport for FileTransport {
+    async fn send(&self, message: &PushMessage) -> anyhow::Result<SendOutcome> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+
+        let _guard = self.lock.lock().await;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        // tokio::fs::File 的寫入在背景執行緒進行，需 flush 才保證已寫入
+        file.flush().await?;
+        Ok(SendOutcome::Delivered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn message(token: &str) -> PushMessage {
+        PushMessage {
+            token: token.to_string(),
+            title: "title".to_string(),
+            body: "body".to_string(),
+            data: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_transport_reports_unregistered() {
+        let transport = MemoryTransport::new();
+        transport.mark_unregistered("stale");
+
+        assert_eq!(transport.send(&message("ok")).await.unwrap(), SendOutcome::Delivered);
+        assert_eq!(
+            transport.send(&message("stale")).await.unwrap(),
+            SendOutcome::Unregistered
+        );
+        assert_eq!(transport.sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_file_transport_appends_json_lines() {
+        let path = std::env::temp_dir().join(format!("push-{}.jsonl", uuid::Uuid::new_v4()));
+        let transport = FileTransport::new(&path);
+        transport.send(&message("a")).await.unwrap();
+        transport.send(&message("b")).await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains(r#""token":"b""#));
+    }
+}
@@ -0,0 +1,133 @@
+// src/notification/mod.rs
+
+mod fcm;
+mod local;
+mod templates;
+
+pub use fcm::FcmTransport;
+pub use local::{FileTransport, MemoryTransport};
+pub use templates::Template;
+
+use crate::config::ExternalConfig;
+use async_trait::async_trait;
+use serde::Serialize;
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 單一裝置的推播訊息
+#[derive(Debug, Clone, Serialize)]
+pub struct PushMessage {
+    pub token: String,
+    pub title: String,
+    pub body: String,
+    pub data: HashMap<String, String>,
+}
+
+/// 推播結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendOutcome {
+    Delivered,
+    /// token 已失效 (App 解除安裝、重新安裝等)，應從資料庫移除
+    Unregistered,
+    Failed(String),
+}
+
+/// 推播傳送方式
+#[async_trait]
+pub trait PushTransport: Send + Sync {
+    async fn send(&self, message: &PushMessage) -> anyhow::Result<SendOutcome>;
+}
+
+/// 依 `ExternalConfig.push_provider` 建立傳送方式
+pub fn build_transport(config: &ExternalConfig) -> anyhow::Result<Arc<dyn PushTransport>> {
+    let transport: Arc<dyn PushTransport> = match config.push_provider.as_str() {
+        "fcm" => Arc::new(FcmTransport::from_credentials_file(&config.fcm_credentials_file)?),
+        "file" => Arc::new(FileTransport::new(&config.push_outbox_file)),
+        "memory" | "none" => Arc::new(MemoryTransport::new()),
+        other => anyhow::bail!("unknown PUSH_PROVIDER {}", other),
+    };
+    Ok(transport)
+}
+
+/// 一次通知的發送統計
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct FanOutReport {
+    pub delivered: usize,
+    pub pruned: usize,
+    pub failed: usize,
+}
+
+/// 推播服務：找出用戶所有綁定設備的 FCM token 逐一發送
+#[derive(Clone)]
+pub struct Notifier {
+    pool: MySqlPool,
+    transport: Arc<dyn PushTransport>,
+}
+
+impl Notifier {
+    pub fn new(pool: MySqlPool, transport: Arc<dyn PushTransport>) -> Self {
+        Self { pool, transport }
+    }
+
+    /// 以範本通知用戶的所有設備，並移除傳送方式回報已失效的 token
+    pub async fn notify_user(
+        &self,
+        user_id: &str,
+        template: Template,
+        params: &HashMap<&str, String>,
+    ) -> anyhow::Result<FanOutReport> {
+        let tokens: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT fcm_token FROM devices
+            WHERE user_id = ? AND fcm_token IS NOT NULL AND is_banned = 0
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let (title, body) = template.render(params);
+        let mut data: HashMap<String, String> = params
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect();
+        data.insert("template".to_string(), template.code().to_string());
+
+        let mut report = FanOutReport::default();
+        for token in tokens {
+            let message = PushMessage {
+                token,
+                title: title.clone(),
+                body: body.clone(),
+                data: data.clone(),
+            };
+
+            match self.transport.send(&message).await {
+                Ok(SendOutcome::Delivered) => report.delivered += 1,
+                Ok(SendOutcome::Unregistered) => {
+                    self.prune_token(&message.token).await?;
+                    report.pruned += 1;
+                }
+                Ok(SendOutcome::Failed(reason)) => {
+                    log::warn!("push to user {} failed: {}", user_id, reason);
+                    report.failed += 1;
+                }
+                Err(err) => {
+                    log::warn!("push to user {} failed: {:#}", user_id, err);
+                    report.failed += 1;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn prune_token(&self, token: &str) -> anyhow::Result<()> {
+        sqlx::query("UPDATE devices SET fcm_token = NULL WHERE fcm_token = ?")
+            .bind(token)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
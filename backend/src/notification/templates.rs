@@ -0,0 +1,90 @@
+// src/notification/templates.rs
+
+use std::collections::HashMap;
+
+/// 推播範本
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    TrialExpiring,
+    SubscriptionExpiring,
+    SubscriptionRenewed,
+    SubscriptionExpired,
+    LevelUp,
+    PracticeReminder,
+}
+
+impl Template {
+    /// 範本代碼，會放在推播 data 的 `template` 欄位讓 App 判斷導頁
+    pub fn code(&self) -> &'static str {
+        match self {
+            Template::TrialExpiring => "trial_expiring",
+            Template::SubscriptionExpiring => "subscription_expiring",
+            Template::SubscriptionRenewed => "subscription_renewed",
+            Template::SubscriptionExpired => "subscription_expired",
+            Template::LevelUp => "level_up",
+            Template::PracticeReminder => "practice_reminder",
+        }
+    }
+
+    /// (標題, 內文)，`{name}` 會以參數取代
+    fn text(&self) -> (&'static str, &'static str) {
+        match self {
+            Template::TrialExpiring => (
+                "試用即將結束",
+                "您的{plan}將於 {days} 天後到期，升級即可繼續練習。",
+            ),
+            Template::SubscriptionExpiring => (
+                "訂閱即將到期",
+                "您的{plan}將於 {date} 到期。",
+            ),
+            Template::SubscriptionRenewed => (
+                "訂閱已續約",
+                "您的{plan}已續約至 {date}，感謝支持！",
+            ),
+            Template::SubscriptionExpired => (
+                "訂閱已到期",
+                "您的{plan}已到期，重新訂閱即可解鎖所有情境。",
+            ),
+            Template::LevelUp => (
+                "恭喜升級！",
+                "您已達到等級 {level}，繼續保持！",
+            ),
+            Template::PracticeReminder => (
+                "今天練習了嗎？",
+                "已連續練習 {streak} 天，花 5 分鐘維持紀錄吧！",
+            ),
+        }
+    }
+
+    /// 套用參數產生 (標題, 內文)；缺少的參數保留原本的 `{name}`
+    pub fn render(&self, params: &HashMap<&str, String>) -> (String, String) {
+        let (title, body) = self.text();
+        (fill(title, params), fill(body, params))
+    }
+}
+
+fn fill(text: &str, params: &HashMap<&str, String>) -> String {
+    params.iter().fold(text.to_string(), |acc, (key, value)| {
+        acc.replace(&format!("{{{}}}", key), value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let params = HashMap::from([("level", "6".to_string())]);
+        let (title, body) = Template::LevelUp.render(&params);
+        assert_eq!(title, "恭喜升級！");
+        assert_eq!(body, "您已達到等級 6，繼續保持！");
+    }
+
+    #[test]
+    fn test_render_keeps_missing_placeholder() {
+        let params = HashMap::from([("plan", "入門版".to_string())]);
+        let (_, body) = Template::SubscriptionExpiring.render(&params);
+        assert_eq!(body, "您的入門版將於 {date} 到期。");
+    }
+}
@@ -0,0 +1,392 @@
+// src/payment/ecpay.rs
+
+use super::{CheckoutForm, CheckoutOrder, PaymentNotice, Period, PeriodNotice};
+use anyhow::Context;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+
+/// 全方位金流 (AIO) 結帳路徑
+pub const CHECKOUT_PATH: &str = "/Cashier/AioCheckOut/V5";
+
+/// 信用卡定期定額作業 (停止扣款) 路徑
+pub const PERIOD_ACTION_PATH: &str = "/Cashier/CreditCardPeriodAction";
+
+/// 付款結果通知處理完成時需回應的內容，其他內容綠界會重送
+pub const NOTIFY_ACK: &str = "1|OK";
+
+/// 綠界的日期格式 (台灣時間)
+const DATE_FORMAT: &str = "%Y/%m/%d %H:%M:%S";
+
+/// 綠界全方位金流
+#[derive(Debug, Clone)]
+pub struct EcPay {
+    merchant_id: String,
+    hash_key: String,
+    hash_iv: String,
+    checkout_url: String,
+    period_action_url: String,
+    return_url: String,
+    period_return_url: String,
+    client: reqwest::Client,
+}
+
+impl EcPay {
+    /// `base_url` 為金流主機 (測試環境 https://payment-stage.ecpay.com.tw)；
+    /// `return_url` / `period_return_url` 為付款結果與定期定額每期扣款的通知網址
+    pub fn new(
+        merchant_id: &str,
+        hash_key: &str,
+        hash_iv: &str,
+        base_url: &str,
+        return_url: &str,
+        period_return_url: &str,
+    ) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        Self {
+            merchant_id: merchant_id.to_string(),
+            hash_key: hash_key.to_string(),
+            hash_iv: hash_iv.to_string(),
+            checkout_url: format!("{}{}", base_url, CHECKOUT_PATH),
+            period_action_url: format!("{}{}", base_url, PERIOD_ACTION_PATH),
+            return_url: return_url.to_string(),
+            period_return_url: period_return_url.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// 產生送往綠界的結帳表單 (含 CheckMacValue)
+    pub fn checkout_form(&self, order: &CheckoutOrder) -> CheckoutForm {
+        let mut fields = BTreeMap::new();
+        fields.insert("MerchantID".to_string(), self.merchant_id.clone());
+        fields.insert("MerchantTradeNo".to_string(), order.merchant_trade_no.clone());
+        fields.insert("MerchantTradeDate".to_string(), format_date(order.trade_date));
+        fields.insert("PaymentType".to_string(), "aio".to_string());
+        fields.insert("TotalAmount".to_string(), order.amount.to_string());
+        fields.insert("TradeDesc".to_string(), order.description.clone());
+        fields.insert("ItemName".to_string(), order.item_name.clone());
+        fields.insert("ReturnURL".to_string(), self.return_url.clone());
+        fields.insert("ChoosePayment".to_string(), choose_payment(&order.payment_method).to_string());
+        fields.insert("EncryptType".to_string(), "1".to_string());
+        if let Some(period) = order.period {
+            // 定期定額只支援信用卡，每期金額與第一次相同
+            let (period_type, exec_times) = match period {
+                Period::Monthly => ("M", "99"),
+                Period::Yearly => ("Y", "9"),
+            };
+            fields.insert("ChoosePayment".to_string(), "Credit".to_string());
+            fields.insert("PeriodAmount".to_string(), order.amount.to_string());
+            fields.insert("PeriodType".to_string(), period_type.to_string());
+            fields.insert("Frequency".to_string(), "1".to_string());
+            fields.insert("ExecTimes".to_string(), exec_times.to_string());
+            fields.insert("PeriodReturnURL".to_string(), self.period_return_url.clone());
+        }
+        let mac = check_mac_value(&self.hash_key, &self.hash_iv, &fields);
+        fields.insert("CheckMacValue".to_string(), mac);
+
+        CheckoutForm {
+            action: self.checkout_url.clone(),
+            method: "POST",
+            fields,
+        }
+    }
+
+    /// 驗證付款結果通知的 CheckMacValue 並解析內容
+    pub fn verify_notification(&self, params: &HashMap<String, String>) -> anyhow::Result<PaymentNotice> {
+        let field = self.verified_fields(params)?;
+        let rtn_code: i64 = field("RtnCode")?.parse().context("invalid RtnCode")?;
+        Ok(PaymentNotice {
+            merchant_trade_no: field("MerchantTradeNo")?.to_string(),
+            trade_no: field("TradeNo")?.to_string(),
+            amount: field("TradeAmt")?.parse().context("invalid TradeAmt")?,
+            paid: rtn_code == 1,
+            message: params.get("RtnMsg").cloned().unwrap_or_default(),
+            paid_at: params.get("PaymentDate").and_then(|value| parse_date(value)),
+            simulated: params.get("SimulatePaid").is_some_and(|value| value == "1"),
+        })
+    }
+
+    /// 驗證定期定額每期扣款通知的 CheckMacValue 並解析內容
+    pub fn verify_period_notification(&self, params: &HashMap<String, String>) -> anyhow::Result<PeriodNotice> {
+        let field = self.verified_fields(params)?;
+        let rtn_code: i64 = field("RtnCode")?.parse().context("invalid RtnCode")?;
+        Ok(PeriodNotice {
+            merchant_trade_no: field("MerchantTradeNo")?.to_string(),
+            gwsr: field("Gwsr")?.to_string(),
+            amount: field("Amount")?.parse().context("invalid Amount")?,
+            paid: rtn_code == 1,
+            message: params.get("RtnMsg").cloned().unwrap_or_default(),
+            total_success_times: field("TotalSuccessTimes")?.parse().context("invalid TotalSuccessTimes")?,
+            processed_at: params.get("ProcessDate").and_then(|value| parse_date(value)),
+        })
+    }
+
+    /// 停止定期定額扣款 (已停止時綠界同樣回傳成功)
+    pub async fn cancel_period(&self, merchant_trade_no: &str) -> anyhow::Result<()> {
+        let mut fields = BTreeMap::new();
+        fields.insert("MerchantID".to_string(), self.merchant_id.clone());
+        fields.insert("MerchantTradeNo".to_string(), merchant_trade_no.to_string());
+        fields.insert("Action".to_string(), "Cancel".to_string());
+        fields.insert("TimeStamp".to_string(), Utc::now().timestamp().to_string());
+        let mac = check_mac_value(&self.hash_key, &self.hash_iv, &fields);
+        fields.insert("CheckMacValue".to_string(), mac);
+
+        let response = self
+            .client
+            .post(&self.period_action_url)
+            .form(&fields)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let result = parse_query(&response);
+        match result.get("RtnCode").map(String::as_str) {
+            Some("1") => Ok(()),
+            _ => anyhow::bail!(
+                "ECPay refused to cancel {}: {}",
+                merchant_trade_no,
+                result.get("RtnMsg").map(String::as_str).unwrap_or(response.as_str())
+            ),
+        }
+    }
+
+    /// 驗證 CheckMacValue 與特店編號，回傳讀取必填欄位的函式
+    fn verified_fields<'a>(
+        &self,
+        params: &'a HashMap<String, String>,
+    ) -> anyhow::Result<impl Fn(&str) -> anyhow::Result<&'a str>> {
+        if !verify(&self.hash_key, &self.hash_iv, params) {
+            anyhow::bail!("CheckMacValue mismatch");
+        }
+        let field = |name: &str| {
+            params
+                .get(name)
+                .map(String::as_str)
+                .with_context(|| format!("missing {}", name))
+        };
+        if field("MerchantID")? != self.merchant_id {
+            anyhow::bail!("notification is for another merchant");
+        }
+        Ok(field)
+    }
+}
+
+/// 計算 CheckMacValue：參數依名稱排序 (不分大小寫) 後前後加上 HashKey / HashIV，
+/// 以 .NET UrlEncode 規則編碼、轉小寫，再取 SHA-256 (大寫 hex)
+pub fn check_mac_value<'a, I, K, V>(hash_key: &str, hash_iv: &str, params: I) -> String
+where
+    I: IntoIterator<Item = (&'a K, &'a V)>,
+    K: AsRef<str> + 'a + ?Sized,
+    V: AsRef<str> + 'a + ?Sized,
+{
+    let mut pairs: Vec<(&str, &str)> = params
+        .into_iter()
+        .map(|(key, value)| (key.as_ref(), value.as_ref()))
+        .filter(|(key, _)| *key != "CheckMacValue")
+        .collect();
+    pairs.sort_by_key(|(key, _)| key.to_lowercase());
+
+    let mut raw = format!("HashKey={}", hash_key);
+    for (key, value) in pairs {
+        raw.push_str(&format!("&{}={}", key, value));
+    }
+    raw.push_str(&format!("&HashIV={}", hash_iv));
+
+    Sha256::digest(url_encode(&raw).to_lowercase().as_bytes())
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect()
+}
+
+/// 驗證參數中的 CheckMacValue
+pub fn verify(hash_key: &str, hash_iv: &str, params: &HashMap<String, String>) -> bool {
+    params.get("CheckMacValue").is_some_and(|mac| {
+        mac.eq_ignore_ascii_case(&check_mac_value(hash_key, hash_iv, params))
+    })
+}
+
+/// 綠界日期格式 (台灣時間)
+pub fn format_date(at: DateTime<Utc>) -> String {
+    at.with_timezone(&taipei()).format(DATE_FORMAT).to_string()
+}
+
+pub fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    let naive = NaiveDateTime::parse_from_str(value.trim(), DATE_FORMAT).ok()?;
+    taipei()
+        .from_local_datetime(&naive)
+        .single()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+fn taipei() -> FixedOffset {
+    FixedOffset::east_opt(8 * 3600).expect("valid offset")
+}
+
+/// 付款方式對應的 ChoosePayment (Google Pay 走信用卡頁面)
+fn choose_payment(method: &str) -> &'static str {
+    match method {
+        "credit_card" | "google_pay" => "Credit",
+        "apple_pay" => "ApplePay",
+        "atm" => "ATM",
+        "cvs" => "CVS",
+        _ => "ALL",
+    }
+}
+
+/// 解析綠界以 `key=value&...` 回傳的結果
+pub fn parse_query(body: &str) -> HashMap<String, String> {
+    body.trim()
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (url_decode(key), url_decode(value)))
+        .collect()
+}
+
+fn url_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'+', _) => decoded.push(b' '),
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 2;
+            }
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// .NET `HttpUtility.UrlEncode`：英數與 `-_.!*()` 不編碼，空白為 `+`
+fn url_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len() * 3);
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'!' | b'*' | b'(' | b')' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH_KEY: &str = "pwFHCqoQZGmho4w6";
+    const HASH_IV: &str = "EkRm7iFt8PBc7l9v";
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_url_encode_matches_dotnet() {
+        assert_eq!(url_encode("a b&c=d"), "a+b%26c%3Dd");
+        assert_eq!(url_encode("-_.!*()"), "-_.!*()");
+        assert_eq!(url_encode("https://x.tw/?q=1"), "https%3A%2F%2Fx.tw%2F%3Fq%3D1");
+        assert_eq!(url_encode("方案"), "%E6%96%B9%E6%A1%88");
+    }
+
+    #[test]
+    fn test_check_mac_value() {
+        let params = params(&[
+            ("ChoosePayment", "ALL"),
+            ("EncryptType", "1"),
+            ("ItemName", "Apple iphone 15"),
+            ("MerchantID", "3002607"),
+            ("MerchantTradeDate", "2023/03/12 15:30:23"),
+            ("MerchantTradeNo", "ecpay20230312153023"),
+            ("PaymentType", "aio"),
+            ("ReturnURL", "https://www.ecpay.com.tw/receive.php"),
+            ("TotalAmount", "30000"),
+            ("TradeDesc", "促銷方案"),
+        ]);
+        assert_eq!(
+            check_mac_value(HASH_KEY, HASH_IV, &params),
+            "A086985C9DC855679BC2F3090EEA5D9FBE7DDC93CCFBCAFBCE996D4025B3D367"
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        let mut params = params(&[("MerchantID", "3002607"), ("TradeAmt", "100")]);
+        let mac = check_mac_value(HASH_KEY, HASH_IV, &params);
+        params.insert("CheckMacValue".to_string(), mac.to_lowercase());
+        assert!(verify(HASH_KEY, HASH_IV, &params));
+
+        params.insert("TradeAmt".to_string(), "1".to_string());
+        assert!(!verify(HASH_KEY, HASH_IV, &params));
+        params.remove("CheckMacValue");
+        assert!(!verify(HASH_KEY, HASH_IV, &params));
+    }
+
+    #[test]
+    fn test_checkout_form_round_trips() {
+        let gateway = EcPay::new(
+            "3002607",
+            HASH_KEY,
+            HASH_IV,
+            "https://payment-stage.ecpay.com.tw/",
+            "https://api.test/notify",
+            "https://api.test/period-notify",
+        );
+        let order = CheckoutOrder {
+            merchant_trade_no: "NS0123456789ABCDEF01".to_string(),
+            trade_date: Utc.with_ymd_and_hms(2024, 1, 1, 16, 30, 0).unwrap(),
+            amount: 691,
+            description: "Nice Speak 訂閱".to_string(),
+            item_name: "入門版 × 12 個月".to_string(),
+            payment_method: "apple_pay".to_string(),
+            period: None,
+        };
+        let form = gateway.checkout_form(&order);
+        assert_eq!(form.action, "https://payment-stage.ecpay.com.tw/Cashier/AioCheckOut/V5");
+        assert_eq!(form.fields["MerchantTradeDate"], "2024/01/02 00:30:00");
+        assert_eq!(form.fields["ChoosePayment"], "ApplePay");
+        assert!(!form.fields.contains_key("PeriodType"));
+
+        let fields: HashMap<String, String> = form.fields.into_iter().collect();
+        assert!(verify(HASH_KEY, HASH_IV, &fields));
+
+        let recurring = gateway.checkout_form(&CheckoutOrder {
+            period: Some(Period::Yearly),
+            ..order
+        });
+        assert_eq!(recurring.fields["ChoosePayment"], "Credit");
+        assert_eq!(recurring.fields["PeriodAmount"], "691");
+        assert_eq!(recurring.fields["PeriodType"], "Y");
+        assert_eq!(recurring.fields["PeriodReturnURL"], "https://api.test/period-notify");
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let result = parse_query("MerchantID=3002607&RtnCode=1&RtnMsg=%E6%88%90%E5%8A%9F+OK\r\n");
+        assert_eq!(result["RtnCode"], "1");
+        assert_eq!(result["RtnMsg"], "成功 OK");
+        assert_eq!(url_decode("100%"), "100%");
+    }
+
+    #[test]
+    fn test_parse_date_is_taipei_time() {
+        assert_eq!(
+            parse_date("2024/01/02 00:30:00"),
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 16, 30, 0).unwrap())
+        );
+        assert_eq!(parse_date("yesterday"), None);
+    }
+}
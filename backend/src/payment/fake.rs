@@ -0,0 +1,392 @@
+// src/payment/fake.rs
+
+use super::ecpay::{self, CHECKOUT_PATH, PERIOD_ACTION_PATH};
+use axum::{extract::State, http::StatusCode, routing::post, Form, Router};
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 假綠界送出的付款結果通知
+#[derive(Debug, Clone)]
+pub struct FakeNotification {
+    pub params: HashMap<String, String>,
+    /// 特店回應的內容 (應為 `1|OK`)
+    pub response: String,
+}
+
+/// 定期定額的訂單
+struct FakePeriod {
+    return_url: String,
+    amount: String,
+    success_times: u32,
+}
+
+struct FakeState {
+    merchant_id: String,
+    hash_key: String,
+    hash_iv: String,
+    rtn_code: AtomicI64,
+    simulate_paid: AtomicBool,
+    notifications: Mutex<Vec<FakeNotification>>,
+    periods: Mutex<HashMap<String, FakePeriod>>,
+    cancelled: Mutex<HashSet<String>>,
+    client: reqwest::Client,
+}
+
+impl FakeState {
+    /// 簽章後以 server-to-server 通知特店，記錄並回傳特店的回應
+    async fn notify(&self, url: &str, mut params: HashMap<String, String>) -> String {
+        let mac = ecpay::check_mac_value(&self.hash_key, &self.hash_iv, &params);
+        params.insert("CheckMacValue".to_string(), mac);
+
+        let response = match self.client.post(url).form(&params).send().await {
+            Ok(response) => response.text().await.unwrap_or_default(),
+            Err(err) => format!("notify failed: {}", err),
+        };
+        self.notifications.lock().unwrap().push(FakeNotification {
+            params,
+            response: response.clone(),
+        });
+        response
+    }
+
+    fn rtn(&self) -> (String, String) {
+        let code = self.rtn_code.load(Ordering::SeqCst);
+        let message = if code == 1 { "交易成功" } else { "交易失敗" };
+        (code.to_string(), message.to_string())
+    }
+}
+
+/// 本機假綠界：收到結帳表單後驗證 CheckMacValue，並立即以 server-to-server 通知回呼 ReturnURL
+///
+/// 供測試與本機開發使用，將 `PAYMENT_BASE_URL` 指向 `base_url()` 即可。
+/// 定期定額的後續扣款以 `charge_period` 觸發。
+pub struct FakeEcPay {
+    addr: SocketAddr,
+    state: Arc<FakeState>,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl FakeEcPay {
+    /// 在 127.0.0.1 的隨機 port 啟動
+    pub async fn spawn(merchant_id: &str, hash_key: &str, hash_iv: &str) -> anyhow::Result<Self> {
+        let state = Arc::new(FakeState {
+            merchant_id: merchant_id.to_string(),
+            hash_key: hash_key.to_string(),
+            hash_iv: hash_iv.to_string(),
+            rtn_code: AtomicI64::new(1),
+            simulate_paid: AtomicBool::new(false),
+            notifications: Mutex::new(Vec::new()),
+            periods: Mutex::new(HashMap::new()),
+            cancelled: Mutex::new(HashSet::new()),
+            client: reqwest::Client::new(),
+        });
+        let app = Router::new()
+            .route(CHECKOUT_PATH, post(checkout))
+            .route(PERIOD_ACTION_PATH, post(period_action))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let handle = tokio::spawn(async move {
+            if let Err(err) = axum::serve(listener, app).await {
+                log::error!("fake ECPay server stopped: {}", err);
+            }
+        });
+
+        Ok(Self { addr, state, handle })
+    }
+
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// 之後的交易回傳的 RtnCode (1 為付款成功)
+    pub fn set_rtn_code(&self, code: i64) {
+        self.state.rtn_code.store(code, Ordering::SeqCst);
+    }
+
+    /// 之後的交易是否為綠界後台的模擬付款 (`SimulatePaid=1`)
+    pub fn set_simulate_paid(&self, simulated: bool) {
+        self.state.simulate_paid.store(simulated, Ordering::SeqCst);
+    }
+
+    /// 已送出的付款結果通知
+    pub fn notifications(&self) -> Vec<FakeNotification> {
+        self.state.notifications.lock().unwrap().clone()
+    }
+
+    /// 已停止扣款的定期定額訂單
+    pub fn is_cancelled(&self, merchant_trade_no: &str) -> bool {
+        self.state.cancelled.lock().unwrap().contains(merchant_trade_no)
+    }
+
+    /// 執行定期定額的下一期扣款並通知 PeriodReturnURL，回傳特店的回應
+    pub async fn charge_period(&self, merchant_trade_no: &str) -> anyhow::Result<String> {
+        if self.is_cancelled(merchant_trade_no) {
+            anyhow::bail!("period {} has been cancelled", merchant_trade_no);
+        }
+        let (rtn_code, rtn_msg) = self.state.rtn();
+        let (url, amount, times) = {
+            let mut periods = self.state.periods.lock().unwrap();
+            let period = periods
+                .get_mut(merchant_trade_no)
+                .ok_or_else(|| anyhow::anyhow!("unknown period {}", merchant_trade_no))?;
+            if rtn_code == "1" {
+                period.success_times += 1;
+            }
+            (period.return_url.clone(), period.amount.clone(), period.success_times)
+        };
+
+        let params = fields(&[
+            ("MerchantID", self.state.merchant_id.clone()),
+            ("MerchantTradeNo", merchant_trade_no.to_string()),
+            ("RtnCode", rtn_code),
+            ("RtnMsg", rtn_msg),
+            ("Amount", amount),
+            ("Gwsr", Utc::now().timestamp_micros().to_string()),
+            ("ProcessDate", ecpay::format_date(Utc::now())),
+            ("AuthCode", "777777".to_string()),
+            ("TotalSuccessTimes", times.to_string()),
+            ("SimulatePaid", "0".to_string()),
+        ]);
+        Ok(self.state.notify(&url, params).await)
+    }
+}
+
+impl Drop for FakeEcPay {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+fn fields(pairs: &[(&str, String)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect()
+}
+
+/// 驗證 CheckMacValue 與特店編號
+fn check_request(state: &FakeState, form: &HashMap<String, String>) -> Result<(), (StatusCode, String)> {
+    if !ecpay::verify(&state.hash_key, &state.hash_iv, form) {
+        return Err((StatusCode::BAD_REQUEST, "CheckMacValue Error".to_string()));
+    }
+    if form.get("MerchantID") != Some(&state.merchant_id) {
+        return Err((StatusCode::BAD_REQUEST, "MerchantID Error".to_string()));
+    }
+    Ok(())
+}
+
+async fn checkout(
+    State(state): State<Arc<FakeState>>,
+    Form(form): Form<HashMap<String, String>>,
+) -> (StatusCode, String) {
+    if let Err(rejection) = check_request(&state, &form) {
+        return rejection;
+    }
+    let Some(return_url) = form.get("ReturnURL") else {
+        return (StatusCode::BAD_REQUEST, "ReturnURL Error".to_string());
+    };
+
+    let (rtn_code, rtn_msg) = state.rtn();
+    let field = |name: &str| form.get(name).cloned().unwrap_or_default();
+    if let Some(period_url) = form.get("PeriodReturnURL") {
+        state.periods.lock().unwrap().insert(
+            field("MerchantTradeNo"),
+            FakePeriod {
+                return_url: period_url.clone(),
+                amount: field("PeriodAmount"),
+                success_times: u32::from(rtn_code == "1"),
+            },
+        );
+    }
+
+    let now = ecpay::format_date(Utc::now());
+    let params = fields(&[
+        ("MerchantID", state.merchant_id.clone()),
+        ("MerchantTradeNo", field("MerchantTradeNo")),
+        ("StoreID", String::new()),
+        ("RtnCode", rtn_code),
+        ("RtnMsg", rtn_msg),
+        ("TradeNo", Utc::now().timestamp_micros().to_string()),
+        ("TradeAmt", field("TotalAmount")),
+        ("PaymentDate", now.clone()),
+        ("PaymentType", "Credit_CreditCard".to_string()),
+        ("PaymentTypeChargeFee", "0".to_string()),
+        ("TradeDate", now),
+        (
+            "SimulatePaid",
+            u8::from(state.simulate_paid.load(Ordering::SeqCst)).to_string(),
+        ),
+    ]);
+    (StatusCode::OK, state.notify(return_url, params).await)
+}
+
+async fn period_action(
+    State(state): State<Arc<FakeState>>,
+    Form(form): Form<HashMap<String, String>>,
+) -> (StatusCode, String) {
+    if let Err(rejection) = check_request(&state, &form) {
+        return rejection;
+    }
+    let trade_no = form.get("MerchantTradeNo").cloned().unwrap_or_default();
+    let (rtn_code, rtn_msg) = match form.get("Action").map(String::as_str) {
+        _ if !state.periods.lock().unwrap().contains_key(&trade_no) => ("0", "Order not found"),
+        Some("Cancel") => {
+            state.cancelled.lock().unwrap().insert(trade_no.clone());
+            ("1", "OK")
+        }
+        _ => ("0", "Unsupported action"),
+    };
+    (
+        StatusCode::OK,
+        format!(
+            "MerchantID={}&MerchantTradeNo={}&RtnCode={}&RtnMsg={}",
+            state.merchant_id, trade_no, rtn_code, rtn_msg
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payment::{CheckoutForm, CheckoutOrder, EcPay, PaymentNotice, Period, PeriodNotice};
+
+    const MERCHANT_ID: &str = "3002607";
+    const HASH_KEY: &str = "pwFHCqoQZGmho4w6";
+    const HASH_IV: &str = "EkRm7iFt8PBc7l9v";
+
+    #[derive(Default)]
+    struct Received {
+        payments: Vec<PaymentNotice>,
+        periods: Vec<PeriodNotice>,
+    }
+
+    /// 模擬特店的通知 endpoint，記錄驗證後的結果並回傳對應的 gateway
+    async fn spawn_merchant(fake: &FakeEcPay) -> (EcPay, Arc<Mutex<Received>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let merchant = listener.local_addr().unwrap();
+        let gateway = EcPay::new(
+            MERCHANT_ID,
+            HASH_KEY,
+            HASH_IV,
+            &fake.base_url(),
+            &format!("http://{}/notify", merchant),
+            &format!("http://{}/period-notify", merchant),
+        );
+        let received = Arc::new(Mutex::new(Received::default()));
+
+        let (payments, periods) = (gateway.clone(), gateway.clone());
+        let (payments_received, periods_received) = (received.clone(), received.clone());
+        let app = Router::new()
+            .route(
+                "/notify",
+                post(move |Form(params): Form<HashMap<String, String>>| async move {
+                    match payments.verify_notification(&params) {
+                        Ok(notice) => {
+                            payments_received.lock().unwrap().payments.push(notice);
+                            ecpay::NOTIFY_ACK.to_string()
+                        }
+                        Err(err) => format!("0|{}", err),
+                    }
+                }),
+            )
+            .route(
+                "/period-notify",
+                post(move |Form(params): Form<HashMap<String, String>>| async move {
+                    match periods.verify_period_notification(&params) {
+                        Ok(notice) => {
+                            periods_received.lock().unwrap().periods.push(notice);
+                            ecpay::NOTIFY_ACK.to_string()
+                        }
+                        Err(err) => format!("0|{}", err),
+                    }
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (gateway, received)
+    }
+
+    fn order(amount: u32, period: Option<Period>) -> CheckoutOrder {
+        CheckoutOrder {
+            merchant_trade_no: "NS00000000000000TEST".to_string(),
+            trade_date: Utc::now(),
+            amount,
+            description: "Nice Speak 訂閱".to_string(),
+            item_name: "入門版 × 1 個月".to_string(),
+            payment_method: "credit_card".to_string(),
+            period,
+        }
+    }
+
+    async fn submit(form: &CheckoutForm) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(&form.action)
+            .form(&form.fields)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_checkout_triggers_signed_notification() {
+        let fake = FakeEcPay::spawn(MERCHANT_ID, HASH_KEY, HASH_IV).await.unwrap();
+        let (gateway, received) = spawn_merchant(&fake).await;
+
+        let response = submit(&gateway.checkout_form(&order(691, None))).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), ecpay::NOTIFY_ACK);
+
+        let notices = received.lock().unwrap().payments.clone();
+        assert_eq!(notices.len(), 1);
+        assert!(notices[0].paid);
+        assert_eq!(notices[0].amount, 691);
+        assert_eq!(notices[0].merchant_trade_no, "NS00000000000000TEST");
+        assert!(notices[0].paid_at.is_some());
+
+        fake.set_rtn_code(10100248);
+        submit(&gateway.checkout_form(&order(100, None))).await;
+        assert!(!received.lock().unwrap().payments[1].paid);
+        assert_eq!(fake.notifications().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_checkout_rejects_bad_signature() {
+        let fake = FakeEcPay::spawn(MERCHANT_ID, HASH_KEY, HASH_IV).await.unwrap();
+        let gateway = EcPay::new(
+            MERCHANT_ID,
+            "wrong-key",
+            HASH_IV,
+            &fake.base_url(),
+            "http://127.0.0.1:9/notify",
+            "http://127.0.0.1:9/period-notify",
+        );
+
+        let response = submit(&gateway.checkout_form(&order(100, None))).await;
+        assert_eq!(response.status(), 400);
+        assert!(fake.notifications().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_period_charges_until_cancelled() {
+        let fake = FakeEcPay::spawn(MERCHANT_ID, HASH_KEY, HASH_IV).await.unwrap();
+        let (gateway, received) = spawn_merchant(&fake).await;
+        submit(&gateway.checkout_form(&order(100, Some(Period::Monthly)))).await;
+
+        let response = fake.charge_period("NS00000000000000TEST").await.unwrap();
+        assert_eq!(response, ecpay::NOTIFY_ACK);
+        let periods = received.lock().unwrap().periods.clone();
+        assert_eq!(periods.len(), 1);
+        assert!(periods[0].paid);
+        assert_eq!(periods[0].amount, 100);
+        assert_eq!(periods[0].total_success_times, 2);
+
+        gateway.cancel_period("NS00000000000000TEST").await.unwrap();
+        assert!(fake.is_cancelled("NS00000000000000TEST"));
+        assert!(fake.charge_period("NS00000000000000TEST").await.is_err());
+        assert!(gateway.cancel_period("NS0000000000000OTHER").await.is_err());
+    }
+}
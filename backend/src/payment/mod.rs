@@ -0,0 +1,113 @@
+// src/payment/mod.rs
+
+pub mod ecpay;
+mod fake;
+
+pub use ecpay::EcPay;
+pub use fake::{FakeEcPay, FakeNotification};
+
+use crate::config::Config;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// 付款結果通知的路徑 (不需登入，以 CheckMacValue 驗證)
+pub const NOTIFY_PATH: &str = "/api/v1/payments/ecpay/notify";
+
+/// 定期定額每期扣款結果通知的路徑
+pub const PERIOD_NOTIFY_PATH: &str = "/api/v1/payments/ecpay/period-notify";
+
+/// 信用卡定期定額的扣款週期
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Monthly,
+    Yearly,
+}
+
+/// 送往金流的訂單
+#[derive(Debug, Clone)]
+pub struct CheckoutOrder {
+    /// 特店交易編號 (英數 20 字內，不可重複)
+    pub merchant_trade_no: String,
+    pub trade_date: DateTime<Utc>,
+    /// 應付金額 (NT$)
+    pub amount: u32,
+    pub description: String,
+    pub item_name: String,
+    /// credit_card / apple_pay / google_pay / atm / cvs
+    pub payment_method: String,
+    /// 設定時以信用卡定期定額自動續訂，之後每期由綠界扣款並通知
+    pub period: Option<Period>,
+}
+
+/// 用戶端以 POST 送出的結帳表單
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckoutForm {
+    pub action: String,
+    pub method: &'static str,
+    pub fields: BTreeMap<String, String>,
+}
+
+/// 驗證後的付款結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentNotice {
+    pub merchant_trade_no: String,
+    /// 金流交易編號
+    pub trade_no: String,
+    pub amount: u32,
+    pub paid: bool,
+    pub message: String,
+    pub paid_at: Option<DateTime<Utc>>,
+    /// 綠界後台模擬付款
+    pub simulated: bool,
+}
+
+/// 定期定額的一期扣款結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeriodNotice {
+    /// 第一次付款的特店交易編號
+    pub merchant_trade_no: String,
+    /// 這一期的授權交易單號
+    pub gwsr: String,
+    pub amount: u32,
+    pub paid: bool,
+    pub message: String,
+    /// 累計成功扣款次數
+    pub total_success_times: u32,
+    pub processed_at: Option<DateTime<Utc>>,
+}
+
+/// 依 `ExternalConfig.payment_provider` 建立金流 (通知網址為 `APP_URL` + `NOTIFY_PATH` / `PERIOD_NOTIFY_PATH`)
+pub fn build_gateway(config: &Config) -> anyhow::Result<EcPay> {
+    let external = &config.external;
+    match external.payment_provider.as_str() {
+        "ecpay" => Ok(EcPay::new(
+            &external.payment_merchant_id,
+            &external.payment_hash_key,
+            &external.payment_hash_iv,
+            &external.payment_base_url,
+            &format!("{}{}", config.app_url.trim_end_matches('/'), NOTIFY_PATH),
+            &format!("{}{}", config.app_url.trim_end_matches('/'), PERIOD_NOTIFY_PATH),
+        )),
+        other => anyhow::bail!("unknown PAYMENT_PROVIDER {}", other),
+    }
+}
+
+/// 產生特店交易編號 (`NS` + 18 碼)
+pub fn new_merchant_trade_no() -> String {
+    let id = uuid::Uuid::new_v4().simple().to_string().to_uppercase();
+    format!("NS{}", &id[..18])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merchant_trade_no_fits_ecpay_limit() {
+        let no = new_merchant_trade_no();
+        assert_eq!(no.len(), 20);
+        assert!(no.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(no, new_merchant_trade_no());
+    }
+}
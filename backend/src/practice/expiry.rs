@@ -0,0 +1,43 @@
+// src/practice/expiry.rs
+
+use super::status::PracticeStatus;
+use crate::error::AppResult;
+use chrono::{Duration, Utc};
+use sqlx::MySqlPool;
+
+/// 掃描逾時練習的間隔
+const SWEEP_INTERVAL_SECS: u64 = 60;
+
+/// 將閒置超過 `timeout_minutes` 的進行中練習標記為 expired，回傳筆數
+pub async fn expire_stale(pool: &MySqlPool, timeout_minutes: i64) -> AppResult<u64> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        r#"
+        UPDATE practice_records
+        SET status = ?, ended_at = ?
+        WHERE status = ? AND last_activity_at < ?
+        "#,
+    )
+    .bind(PracticeStatus::Expired.as_str())
+    .bind(now)
+    .bind(PracticeStatus::InProgress.as_str())
+    .bind(now - Duration::minutes(timeout_minutes))
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// 背景定期結束逾時的練習 (多個實例同時執行也只會更新一次)
+pub fn spawn_expiry_sweeper(pool: MySqlPool, timeout_minutes: i64) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(SWEEP_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match expire_stale(&pool, timeout_minutes).await {
+                Ok(0) => {}
+                Ok(expired) => log::info!("expired {} idle practice sessions", expired),
+                Err(err) => log::warn!("failed to expire idle practice sessions: {}", err),
+            }
+        }
+    });
+}
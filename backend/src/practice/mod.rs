@@ -0,0 +1,193 @@
+// src/practice/mod.rs
+
+mod expiry;
+mod session;
+mod status;
+
+pub use expiry::spawn_expiry_sweeper;
+pub use session::{
+    abandon_practice, complete_practice, current_dialogue, decode_audio, load_session,
+    parse_audio_format, start_practice, submit_turn, Dialogue, PracticeSession, PracticeSummary,
+    TurnResult, MAX_AUDIO_BYTES,
+};
+pub use status::PracticeStatus;
+
+use crate::auth::AuthUser;
+use crate::error::AppResult;
+use crate::evaluation::{ErrorItem, Evaluation};
+use crate::level::LevelUp;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 提交作答的請求上限 (base64 約為原始大小的 4/3，再預留 JSON 欄位的空間)
+pub const MAX_SUBMIT_BODY_BYTES: usize = MAX_AUDIO_BYTES * 2;
+
+#[derive(Deserialize)]
+pub struct StartRequest {
+    scenario_id: String,
+}
+
+#[derive(Serialize)]
+pub struct PracticeInfo {
+    id: String,
+    scenario_id: String,
+    status: PracticeStatus,
+    started_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct StartResponse {
+    practice: PracticeInfo,
+    dialogue: Option<Dialogue>,
+}
+
+#[derive(Deserialize)]
+pub struct SubmitRequest {
+    /// base64 語音
+    audio: String,
+    /// 客戶端辨識結果，伺服器端辨識不到內容時採用
+    transcript: Option<String>,
+    /// 回應的對話；省略時為目前這一輪
+    dialogue_id: Option<String>,
+    /// wav (預設) / ogg_opus / webm_opus
+    format: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SubmitResponse {
+    dialogue_id: String,
+    evaluation: Evaluation,
+    /// 全部作答完畢時為 null，接著呼叫 complete
+    next_dialogue: Option<Dialogue>,
+}
+
+#[derive(Serialize)]
+pub struct ScoreBreakdown {
+    pronunciation: u32,
+    grammar: u32,
+    vocabulary: u32,
+    fluency: u32,
+}
+
+#[derive(Serialize)]
+pub struct CompletedPractice {
+    id: String,
+    status: PracticeStatus,
+    completed_at: DateTime<Utc>,
+    total_score: u32,
+    evaluation: ScoreBreakdown,
+    level_up: Option<LevelUp>,
+}
+
+#[derive(Serialize)]
+pub struct CompleteResponse {
+    practice: CompletedPractice,
+    errors: Vec<ErrorItem>,
+}
+
+#[derive(Serialize)]
+pub struct PracticeState {
+    id: String,
+    status: PracticeStatus,
+}
+
+#[derive(Serialize)]
+pub struct AbandonResponse {
+    practice: PracticeState,
+}
+
+/// `POST /api/v1/practice/start` 開始練習並回傳第一句對話
+pub async fn start(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<StartRequest>,
+) -> AppResult<Json<StartResponse>> {
+    let mut session = start_practice(&state, &user.user_id, payload.scenario_id.trim()).await?;
+    let dialogue = current_dialogue(&state, &mut session).await;
+
+    Ok(Json(StartResponse {
+        practice: PracticeInfo {
+            id: session.practice_id,
+            scenario_id: session.scenario_id,
+            status: PracticeStatus::InProgress,
+            started_at: session.started_at,
+        },
+        dialogue,
+    }))
+}
+
+/// `POST /api/v1/practice/:id/submit` 提交目前這一輪的語音並取得評估
+pub async fn submit(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(practice_id): Path<String>,
+    Json(payload): Json<SubmitRequest>,
+) -> AppResult<Json<SubmitResponse>> {
+    let format = parse_audio_format(payload.format.as_deref())?;
+    let audio = decode_audio(&payload.audio)?;
+
+    let mut session = load_session(&state, &user.user_id, &practice_id).await?;
+    let turn = submit_turn(
+        &state,
+        &mut session,
+        payload.dialogue_id.as_deref(),
+        &audio,
+        format,
+        payload.transcript,
+    )
+    .await?;
+    let next_dialogue = current_dialogue(&state, &mut session).await;
+
+    Ok(Json(SubmitResponse {
+        dialogue_id: turn.dialogue_id,
+        evaluation: turn.evaluation,
+        next_dialogue,
+    }))
+}
+
+/// `POST /api/v1/practice/:id/complete` 結算練習 (重複呼叫回傳同一份結果)
+pub async fn complete(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(practice_id): Path<String>,
+) -> AppResult<Json<CompleteResponse>> {
+    let summary = complete_practice(&state, &user.user_id, &practice_id).await?;
+
+    Ok(Json(CompleteResponse {
+        practice: CompletedPractice {
+            id: summary.practice_id,
+            status: summary.status,
+            completed_at: summary.completed_at,
+            total_score: summary.total,
+            evaluation: ScoreBreakdown {
+                pronunciation: summary.pronunciation,
+                grammar: summary.grammar,
+                vocabulary: summary.vocabulary,
+                fluency: summary.fluency,
+            },
+            level_up: summary.level_up,
+        },
+        errors: summary.errors,
+    }))
+}
+
+/// `POST /api/v1/practice/:id/abandon` 放棄練習
+pub async fn abandon(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(practice_id): Path<String>,
+) -> AppResult<Json<AbandonResponse>> {
+    abandon_practice(&state, &user.user_id, &practice_id).await?;
+
+    Ok(Json(AbandonResponse {
+        practice: PracticeState {
+            id: practice_id,
+            status: PracticeStatus::Abandoned,
+        },
+    }))
+}
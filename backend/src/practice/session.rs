@@ -0,0 +1,691 @@
+// src/practice/session.rs
+
+use super::status::PracticeStatus;
+use crate::error::{AppError, AppResult};
+use crate::evaluation::{evaluate_turn, ErrorItem, Evaluation, EvaluationInput};
+use crate::level::{self, LevelUp};
+use crate::notification::Template;
+use crate::speech::AudioFormat;
+use crate::state::AppState;
+use crate::statistics;
+use crate::subscription;
+use crate::vocabulary;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{MySqlConnection, MySqlPool};
+use std::collections::HashMap;
+
+/// 單輪語音上限
+pub const MAX_AUDIO_BYTES: usize = 5 * 1024 * 1024;
+
+/// 情境中的一句對話
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Dialogue {
+    pub id: String,
+    #[serde(rename = "sequence")]
+    pub sequence_number: i32,
+    #[serde(rename = "speaker")]
+    pub speaker_role: String,
+    pub content: String,
+    pub audio_url: Option<String>,
+}
+
+/// 進行中的練習
+#[derive(Debug, Clone)]
+pub struct PracticeSession {
+    pub practice_id: String,
+    pub user_id: String,
+    pub scenario_id: String,
+    pub started_at: DateTime<Utc>,
+    pub dialogues: Vec<Dialogue>,
+    /// 已作答的輪數，`dialogues[answered]` 即為目前這一輪
+    pub answered: usize,
+}
+
+impl PracticeSession {
+    /// 目前等待回答的對話；全部作答完畢時為 None
+    pub fn current(&self) -> Option<&Dialogue> {
+        self.dialogues.get(self.answered)
+    }
+}
+
+/// 一輪作答的評估結果
+#[derive(Debug, Clone)]
+pub struct TurnResult {
+    pub dialogue_id: String,
+    pub evaluation: Evaluation,
+}
+
+/// 練習完成後的總結
+#[derive(Debug, Clone, Serialize)]
+pub struct PracticeSummary {
+    pub practice_id: String,
+    pub status: PracticeStatus,
+    pub completed_at: DateTime<Utc>,
+    pub pronunciation: u32,
+    pub grammar: u32,
+    pub vocabulary: u32,
+    pub fluency: u32,
+    pub total: u32,
+    /// 完成當下的等級變化；重複完成時回傳同一份結果
+    pub level_up: Option<LevelUp>,
+    pub errors: Vec<ErrorItem>,
+}
+
+/// 各項目平均分數
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct PracticeScores {
+    pronunciation: u32,
+    grammar: u32,
+    vocabulary: u32,
+    fluency: u32,
+    total: u32,
+}
+
+#[derive(sqlx::FromRow)]
+struct PracticeRow {
+    scenario_id: String,
+    status: String,
+    started_at: DateTime<Utc>,
+    last_activity_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+    total_score: Option<i32>,
+    pronunciation_score: Option<i32>,
+    grammar_score: Option<i32>,
+    vocabulary_score: Option<i32>,
+    fluency_score: Option<i32>,
+    level_up: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TurnRow {
+    dialogue_id: String,
+    transcript: Option<String>,
+    pronunciation_score: i32,
+    grammar_score: i32,
+    vocabulary_score: i32,
+    fluency_score: i32,
+    errors: Option<String>,
+}
+
+const PRACTICE_QUERY: &str = r#"
+    SELECT scenario_id, status, started_at, last_activity_at, completed_at, total_score,
+           pronunciation_score, grammar_score, vocabulary_score, fluency_score,
+           CAST(level_up AS CHAR) AS level_up
+    FROM practice_records
+    WHERE id = ? AND user_id = ?
+"#;
+
+/// 最後作答時間距今超過 `timeout_minutes` 即視為逾時
+pub fn is_stale(last_activity_at: DateTime<Utc>, now: DateTime<Utc>, timeout_minutes: i64) -> bool {
+    now - last_activity_at > Duration::minutes(timeout_minutes)
+}
+
+/// 解析語音格式，未指定時為 WAV
+pub fn parse_audio_format(format: Option<&str>) -> AppResult<AudioFormat> {
+    match format {
+        Some(format) => AudioFormat::parse(format)
+            .ok_or_else(|| AppError::Validation(format!("Unsupported audio format {}", format))),
+        None => Ok(AudioFormat::default()),
+    }
+}
+
+/// 解碼 base64 語音 (不接受音檔網址)
+pub fn decode_audio(audio: &str) -> AppResult<Vec<u8>> {
+    let audio = audio.trim();
+    if audio.starts_with("http://") || audio.starts_with("https://") {
+        return Err(AppError::Validation("Audio URLs are not supported, send base64 audio".to_string()));
+    }
+    STANDARD
+        .decode(audio)
+        .map_err(|_| AppError::Validation("Audio must be base64 encoded".to_string()))
+}
+
+/// 情境的對話內容 (依順序)
+pub async fn load_dialogues(pool: &MySqlPool, scenario_id: &str) -> AppResult<Vec<Dialogue>> {
+    let dialogues: Vec<Dialogue> = sqlx::query_as(
+        r#"
+        SELECT id, sequence_number, speaker_role, content, audio_url
+        FROM dialogues
+        WHERE scenario_id = ?
+        ORDER BY sequence_number
+        "#,
+    )
+    .bind(scenario_id)
+    .fetch_all(pool)
+    .await?;
+
+    if dialogues.is_empty() {
+        return Err(AppError::NotFound("Scenario has no dialogues".to_string()));
+    }
+    Ok(dialogues)
+}
+
+/// 對話沒有音檔時以 TTS 合成並寫回 `dialogues.audio_url`；合成失敗時不帶音檔繼續練習
+pub async fn ensure_dialogue_audio(state: &AppState, dialogue: &mut Dialogue) {
+    if dialogue.audio_url.is_some() {
+        return;
+    }
+
+    let url = match state.tts.audio_url(&dialogue.content, &dialogue.speaker_role).await {
+        Ok(url) => url,
+        Err(err) => {
+            log::warn!("failed to synthesize dialogue {}: {:#}", dialogue.id, err);
+            return;
+        }
+    };
+
+    let updated = sqlx::query("UPDATE dialogues SET audio_url = ? WHERE id = ? AND audio_url IS NULL")
+        .bind(&url)
+        .bind(&dialogue.id)
+        .execute(&state.pool)
+        .await;
+    if let Err(err) = updated {
+        log::warn!("failed to save audio url for dialogue {}: {}", dialogue.id, err);
+    }
+    dialogue.audio_url = Some(url);
+}
+
+/// 目前這一輪的對話 (補上音檔)；全部作答完畢時為 None
+pub async fn current_dialogue(state: &AppState, session: &mut PracticeSession) -> Option<Dialogue> {
+    let answered = session.answered;
+    let dialogue = session.dialogues.get_mut(answered)?;
+    ensure_dialogue_audio(state, dialogue).await;
+    Some(dialogue.clone())
+}
+
+/// 建立新的練習記錄 (需已訂閱可解鎖該情境的方案)
+pub async fn start_practice(
+    state: &AppState,
+    user_id: &str,
+    scenario_id: &str,
+) -> AppResult<PracticeSession> {
+    let pool = &state.pool;
+    subscription::ensure_scenario_access(pool, user_id, scenario_id).await?;
+
+    let dialogues = load_dialogues(pool, scenario_id).await?;
+    let practice_id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    sqlx::query(
+        r#"
+        INSERT INTO practice_records (id, user_id, scenario_id, started_at, last_activity_at, status)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&practice_id)
+    .bind(user_id)
+    .bind(scenario_id)
+    .bind(now)
+    .bind(now)
+    .bind(PracticeStatus::InProgress.as_str())
+    .execute(pool)
+    .await?;
+
+    Ok(PracticeSession {
+        practice_id,
+        user_id: user_id.to_string(),
+        scenario_id: scenario_id.to_string(),
+        started_at: now,
+        dialogues,
+        answered: 0,
+    })
+}
+
+/// 載入用戶自己進行中的練習，已作答的輪數由 `practice_turns` 計算；閒置逾時的練習在此標記為 expired
+pub async fn load_session(
+    state: &AppState,
+    user_id: &str,
+    practice_id: &str,
+) -> AppResult<PracticeSession> {
+    let pool = &state.pool;
+    let mut conn = pool.acquire().await?;
+    let record = fetch_practice(&mut conn, user_id, practice_id, false).await?;
+    drop(conn);
+
+    let status = PracticeStatus::parse(&record.status)?;
+    if status == PracticeStatus::InProgress
+        && is_stale(record.last_activity_at, Utc::now(), state.config.practice.timeout_minutes)
+    {
+        let mut conn = pool.acquire().await?;
+        end_practice(&mut conn, practice_id, PracticeStatus::Expired).await?;
+        return Err(AppError::PracticeExpired("Practice has expired".to_string()));
+    }
+    status.ensure_in_progress()?;
+
+    let dialogues = load_dialogues(pool, &record.scenario_id).await?;
+    let answered_ids: Vec<String> =
+        sqlx::query_scalar("SELECT dialogue_id FROM practice_turns WHERE practice_id = ?")
+            .bind(practice_id)
+            .fetch_all(pool)
+            .await?;
+    let answered = dialogues
+        .iter()
+        .take_while(|dialogue| answered_ids.contains(&dialogue.id))
+        .count();
+
+    Ok(PracticeSession {
+        practice_id: practice_id.to_string(),
+        user_id: user_id.to_string(),
+        scenario_id: record.scenario_id,
+        started_at: record.started_at,
+        dialogues,
+        answered,
+    })
+}
+
+/// 辨識並評分目前這一輪，寫入 `practice_turns`
+///
+/// `dialogue_id` 為 None 時視為目前這一輪；伺服器端辨識不到內容時才採用客戶端提供的逐字稿。
+pub async fn submit_turn(
+    state: &AppState,
+    session: &mut PracticeSession,
+    dialogue_id: Option<&str>,
+    audio: &[u8],
+    format: AudioFormat,
+    client_transcript: Option<String>,
+) -> AppResult<TurnResult> {
+    let dialogue = session
+        .current()
+        .cloned()
+        .ok_or_else(|| AppError::Conflict("All dialogues have been answered".to_string()))?;
+
+    if let Some(dialogue_id) = dialogue_id {
+        if dialogue_id != dialogue.id {
+            return Err(AppError::Validation(format!(
+                "Expected audio for dialogue {}",
+                dialogue.id
+            )));
+        }
+    }
+    if audio.is_empty() {
+        return Err(AppError::Validation("Audio is empty".to_string()));
+    }
+    if audio.len() > MAX_AUDIO_BYTES {
+        return Err(AppError::Validation(format!(
+            "Audio exceeds {} bytes",
+            MAX_AUDIO_BYTES
+        )));
+    }
+
+    let transcription = state.stt.transcribe(audio, format).await?;
+    let mut input = EvaluationInput {
+        prompt: dialogue.content.clone(),
+        speaker_role: dialogue.speaker_role.clone(),
+        ..Default::default()
+    };
+    if !transcription.transcript.trim().is_empty() {
+        input.transcript = transcription.transcript;
+        input.confidence = transcription.confidence;
+        input.words = transcription.words;
+    } else if let Some(text) = client_transcript.filter(|text| !text.trim().is_empty()) {
+        input.transcript = text;
+    }
+
+    let evaluation = evaluate_turn(state.evaluator.as_ref(), &input).await;
+    let transcript = Some(input.transcript.as_str()).filter(|text| !text.is_empty());
+    let inserted = record_turn(&state.pool, session, &dialogue, transcript, audio.len(), &evaluation).await?;
+    if !inserted {
+        return Err(AppError::Conflict("Dialogue has already been answered".to_string()));
+    }
+    session.answered += 1;
+
+    // 錯誤本失敗不影響這一輪的結果
+    if let Err(err) = state
+        .error_notebook
+        .record_turn(&session.user_id, &session.practice_id, &input.transcript, &evaluation.errors)
+        .await
+    {
+        log::warn!("failed to update error notebook for practice {}: {}", session.practice_id, err);
+    }
+
+    Ok(TurnResult {
+        dialogue_id: dialogue.id,
+        evaluation,
+    })
+}
+
+/// 記錄一輪作答；同一輪重送時回傳 false (不覆蓋先前的結果)
+async fn record_turn(
+    pool: &MySqlPool,
+    session: &PracticeSession,
+    dialogue: &Dialogue,
+    transcript: Option<&str>,
+    audio_bytes: usize,
+    evaluation: &Evaluation,
+) -> AppResult<bool> {
+    let now = Utc::now();
+    let suggestions = serde_json::to_string(&evaluation.suggestions).map_err(anyhow::Error::from)?;
+    let errors = serde_json::to_string(&evaluation.errors).map_err(anyhow::Error::from)?;
+    let result = sqlx::query(
+        r#"
+        INSERT IGNORE INTO practice_turns
+            (id, practice_id, dialogue_id, sequence_number, transcript, audio_bytes,
+             pronunciation_score, grammar_score, vocabulary_score, fluency_score, total_score,
+             feedback, suggestions, errors, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&session.practice_id)
+    .bind(&dialogue.id)
+    .bind(dialogue.sequence_number)
+    .bind(transcript)
+    .bind(audio_bytes as i64)
+    .bind(evaluation.pronunciation)
+    .bind(evaluation.grammar)
+    .bind(evaluation.vocabulary)
+    .bind(evaluation.fluency)
+    .bind(evaluation.total)
+    .bind(&evaluation.feedback)
+    .bind(suggestions)
+    .bind(errors)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    sqlx::query("UPDATE practice_records SET last_activity_at = ? WHERE id = ?")
+        .bind(now)
+        .bind(&session.practice_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 結算練習：彙整各輪分數、標記為完成、更新等級並收錄情境單字 (同一交易)
+///
+/// 已完成的練習直接回傳當時的結果，不會重複計算等級；閒置逾時的練習改標記為 expired。
+pub async fn complete_practice(
+    state: &AppState,
+    user_id: &str,
+    practice_id: &str,
+) -> AppResult<PracticeSummary> {
+    let mut tx = state.pool.begin().await?;
+    let record = fetch_practice(&mut tx, user_id, practice_id, true).await?;
+    let status = PracticeStatus::parse(&record.status)?;
+
+    if status == PracticeStatus::Completed {
+        let turns = load_turns(&mut tx, practice_id).await?;
+        tx.commit().await?;
+        return stored_summary(practice_id, record, &turns);
+    }
+    if status == PracticeStatus::InProgress
+        && is_stale(record.last_activity_at, Utc::now(), state.config.practice.timeout_minutes)
+    {
+        end_practice(&mut tx, practice_id, PracticeStatus::Expired).await?;
+        tx.commit().await?;
+        return Err(AppError::PracticeExpired("Practice has expired".to_string()));
+    }
+    status.transition(PracticeStatus::Completed)?;
+
+    let turns = load_turns(&mut tx, practice_id).await?;
+    if turns.is_empty() {
+        return Err(AppError::Validation(
+            "Submit at least one answer before completing".to_string(),
+        ));
+    }
+    let dialogues = load_dialogues(&state.pool, &record.scenario_id).await?;
+    let scores = aggregate_scores(&turns, dialogues.len());
+
+    let transcript = turns
+        .iter()
+        .map(|turn| {
+            let prompt = dialogues
+                .iter()
+                .find(|dialogue| dialogue.id == turn.dialogue_id)
+                .map(|dialogue| format!("{}: {}", dialogue.speaker_role, dialogue.content))
+                .unwrap_or_default();
+            format!("{}\nYou: {}", prompt, turn.transcript.as_deref().unwrap_or(""))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let level_up = level::record_practice(&mut tx, user_id, scores.total).await?.level_up;
+    vocabulary::enrol_scenario(&mut tx, user_id, &record.scenario_id).await?;
+    let completed_at = Utc::now();
+    sqlx::query(
+        r#"
+        UPDATE practice_records
+        SET status = ?, completed_at = ?, total_score = ?, pronunciation_score = ?,
+            grammar_score = ?, vocabulary_score = ?, fluency_score = ?, transcript = ?, level_up = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(PracticeStatus::Completed.as_str())
+    .bind(completed_at)
+    .bind(scores.total)
+    .bind(scores.pronunciation)
+    .bind(scores.grammar)
+    .bind(scores.vocabulary)
+    .bind(scores.fluency)
+    .bind(transcript)
+    .bind(serde_json::to_string(&level_up).map_err(anyhow::Error::from)?)
+    .bind(practice_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    statistics::invalidate(&state.redis, &state.config.redis.key_prefix, user_id).await;
+    if level_up.leveled_up {
+        notify_level_up(state, user_id, level_up.new_level);
+    }
+
+    Ok(PracticeSummary {
+        practice_id: practice_id.to_string(),
+        status: PracticeStatus::Completed,
+        completed_at,
+        pronunciation: scores.pronunciation,
+        grammar: scores.grammar,
+        vocabulary: scores.vocabulary,
+        fluency: scores.fluency,
+        total: scores.total,
+        level_up: Some(level_up),
+        errors: collect_errors(&turns),
+    })
+}
+
+/// 放棄練習；已放棄時視為成功
+pub async fn abandon_practice(state: &AppState, user_id: &str, practice_id: &str) -> AppResult<()> {
+    let mut tx = state.pool.begin().await?;
+    let record = fetch_practice(&mut tx, user_id, practice_id, true).await?;
+    let status = PracticeStatus::parse(&record.status)?;
+    if status == PracticeStatus::Abandoned {
+        return Ok(());
+    }
+
+    let next = status.transition(PracticeStatus::Abandoned)?;
+    end_practice(&mut tx, practice_id, next).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn fetch_practice(
+    conn: &mut MySqlConnection,
+    user_id: &str,
+    practice_id: &str,
+    for_update: bool,
+) -> AppResult<PracticeRow> {
+    let sql = if for_update {
+        format!("{} FOR UPDATE", PRACTICE_QUERY.trim_end())
+    } else {
+        PRACTICE_QUERY.to_string()
+    };
+    let record: Option<PracticeRow> = sqlx::query_as(&sql)
+        .bind(practice_id)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    record.ok_or_else(|| AppError::NotFound("Practice not found".to_string()))
+}
+
+/// 將進行中的練習標記為 abandoned 或 expired
+async fn end_practice(
+    conn: &mut MySqlConnection,
+    practice_id: &str,
+    status: PracticeStatus,
+) -> AppResult<()> {
+    sqlx::query("UPDATE practice_records SET status = ?, ended_at = ? WHERE id = ? AND status = ?")
+        .bind(status.as_str())
+        .bind(Utc::now())
+        .bind(practice_id)
+        .bind(PracticeStatus::InProgress.as_str())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+async fn load_turns(conn: &mut MySqlConnection, practice_id: &str) -> AppResult<Vec<TurnRow>> {
+    let turns = sqlx::query_as(
+        r#"
+        SELECT dialogue_id, transcript, pronunciation_score, grammar_score, vocabulary_score,
+               fluency_score, CAST(errors AS CHAR) AS errors
+        FROM practice_turns
+        WHERE practice_id = ?
+        ORDER BY sequence_number
+        "#,
+    )
+    .bind(practice_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    Ok(turns)
+}
+
+/// 重複完成時依資料庫中的結果重建總結
+fn stored_summary(practice_id: &str, record: PracticeRow, turns: &[TurnRow]) -> AppResult<PracticeSummary> {
+    let score = |value: Option<i32>| value.unwrap_or(0).max(0) as u32;
+    let level_up = match record.level_up.as_deref() {
+        Some(json) => Some(serde_json::from_str(json).map_err(anyhow::Error::from)?),
+        None => None,
+    };
+
+    Ok(PracticeSummary {
+        practice_id: practice_id.to_string(),
+        status: PracticeStatus::Completed,
+        completed_at: record.completed_at.unwrap_or(record.last_activity_at),
+        pronunciation: score(record.pronunciation_score),
+        grammar: score(record.grammar_score),
+        vocabulary: score(record.vocabulary_score),
+        fluency: score(record.fluency_score),
+        total: score(record.total_score),
+        level_up,
+        errors: collect_errors(turns),
+    })
+}
+
+/// 各項目以情境的對話輪數平均，未作答的輪次以 0 分計
+fn aggregate_scores(turns: &[TurnRow], dialogue_count: usize) -> PracticeScores {
+    let rounds = dialogue_count.max(turns.len());
+    if rounds == 0 {
+        return PracticeScores::default();
+    }
+
+    let average = |pick: fn(&TurnRow) -> i32| -> u32 {
+        let sum: i64 = turns.iter().map(|turn| pick(turn).max(0) as i64).sum();
+        (sum as f64 / rounds as f64).round() as u32
+    };
+    let pronunciation = average(|turn| turn.pronunciation_score);
+    let grammar = average(|turn| turn.grammar_score);
+    let vocabulary = average(|turn| turn.vocabulary_score);
+    let fluency = average(|turn| turn.fluency_score);
+
+    PracticeScores {
+        pronunciation,
+        grammar,
+        vocabulary,
+        fluency,
+        total: pronunciation + grammar + vocabulary + fluency,
+    }
+}
+
+/// 彙整各輪的錯誤項目 (去除重複)
+fn collect_errors(turns: &[TurnRow]) -> Vec<ErrorItem> {
+    let mut errors: Vec<ErrorItem> = Vec::new();
+    for turn in turns {
+        let Some(json) = turn.errors.as_deref() else {
+            continue;
+        };
+        match serde_json::from_str::<Vec<ErrorItem>>(json) {
+            Ok(items) => {
+                for item in items {
+                    if !errors.contains(&item) {
+                        errors.push(item);
+                    }
+                }
+            }
+            Err(err) => log::warn!("invalid errors JSON for dialogue {}: {}", turn.dialogue_id, err),
+        }
+    }
+    errors
+}
+
+/// 背景推播升級通知，不延遲練習結果
+fn notify_level_up(state: &AppState, user_id: &str, level: i32) {
+    let notifier = state.notifier.clone();
+    let user_id = user_id.to_string();
+    tokio::spawn(async move {
+        let params = HashMap::from([("level", level.to_string())]);
+        if let Err(err) = notifier.notify_user(&user_id, Template::LevelUp, &params).await {
+            log::warn!("failed to send level up notification to {}: {:#}", user_id, err);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(dialogue_id: &str, scores: [i32; 4], errors: Option<&str>) -> TurnRow {
+        TurnRow {
+            dialogue_id: dialogue_id.to_string(),
+            transcript: None,
+            pronunciation_score: scores[0],
+            grammar_score: scores[1],
+            vocabulary_score: scores[2],
+            fluency_score: scores[3],
+            errors: errors.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_counts_unanswered_as_zero() {
+        let turns = vec![turn("d1", [28, 29, 18, 17], None), turn("d2", [26, 27, 16, 15], None)];
+        let all = aggregate_scores(&turns, 2);
+        assert_eq!(all.pronunciation, 27);
+        assert_eq!(all.total, 27 + 28 + 17 + 16);
+
+        let partial = aggregate_scores(&turns, 4);
+        assert_eq!(partial.pronunciation, 14);
+        assert_eq!(partial.fluency, 8);
+        assert_eq!(aggregate_scores(&[], 0), PracticeScores::default());
+    }
+
+    #[test]
+    fn test_collect_errors_dedupes() {
+        let item = r#"[{"type":"grammar","original":"I has","suggested":"I have"}]"#;
+        let turns = vec![
+            turn("d1", [0; 4], Some(item)),
+            turn("d2", [0; 4], Some(item)),
+            turn("d3", [0; 4], Some("not json")),
+            turn("d4", [0; 4], None),
+        ];
+        let errors = collect_errors(&turns);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].suggested, "I have");
+    }
+
+    #[test]
+    fn test_stale_after_timeout() {
+        let now = Utc::now();
+        assert!(!is_stale(now - Duration::minutes(29), now, 30));
+        assert!(is_stale(now - Duration::minutes(31), now, 30));
+    }
+
+    #[test]
+    fn test_decode_audio_rejects_urls() {
+        assert_eq!(decode_audio(" AQID ").unwrap(), vec![1, 2, 3]);
+        assert!(decode_audio("https://cdn.example.com/a.wav").is_err());
+        assert!(decode_audio("not base64!").is_err());
+    }
+}
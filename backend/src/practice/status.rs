@@ -0,0 +1,94 @@
+// src/practice/status.rs
+
+use crate::error::{AppError, AppResult};
+use serde::Serialize;
+
+/// 練習狀態
+///
+/// 只有 in_progress 可以轉換；completed / abandoned / expired 皆為終止狀態。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PracticeStatus {
+    InProgress,
+    Completed,
+    Abandoned,
+    Expired,
+}
+
+impl PracticeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PracticeStatus::InProgress => "in_progress",
+            PracticeStatus::Completed => "completed",
+            PracticeStatus::Abandoned => "abandoned",
+            PracticeStatus::Expired => "expired",
+        }
+    }
+
+    pub fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "in_progress" => Ok(PracticeStatus::InProgress),
+            "completed" => Ok(PracticeStatus::Completed),
+            "abandoned" => Ok(PracticeStatus::Abandoned),
+            "expired" => Ok(PracticeStatus::Expired),
+            other => Err(anyhow::anyhow!("unknown practice status {}", other).into()),
+        }
+    }
+
+    /// 練習必須仍在進行中；已逾時回傳 PracticeExpired，其他終止狀態回傳 Conflict
+    pub fn ensure_in_progress(self) -> AppResult<()> {
+        match self {
+            PracticeStatus::InProgress => Ok(()),
+            PracticeStatus::Expired => Err(AppError::PracticeExpired("Practice has expired".to_string())),
+            other => Err(AppError::Conflict(format!("Practice is already {}", other.as_str()))),
+        }
+    }
+
+    /// 檢查能否從目前狀態轉換到 `next` (只能從 in_progress 進入終止狀態)
+    pub fn transition(self, next: PracticeStatus) -> AppResult<PracticeStatus> {
+        if next == PracticeStatus::InProgress {
+            return Err(AppError::Conflict("Practice cannot be restarted".to_string()));
+        }
+        self.ensure_in_progress()?;
+        Ok(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_round_trip() {
+        for status in [
+            PracticeStatus::InProgress,
+            PracticeStatus::Completed,
+            PracticeStatus::Abandoned,
+            PracticeStatus::Expired,
+        ] {
+            assert_eq!(PracticeStatus::parse(status.as_str()).unwrap(), status);
+        }
+        assert!(PracticeStatus::parse("paused").is_err());
+    }
+
+    #[test]
+    fn test_only_in_progress_transitions() {
+        let in_progress = PracticeStatus::InProgress;
+        assert_eq!(
+            in_progress.transition(PracticeStatus::Completed).unwrap(),
+            PracticeStatus::Completed
+        );
+        assert!(in_progress.transition(PracticeStatus::Abandoned).is_ok());
+        assert!(in_progress.transition(PracticeStatus::Expired).is_ok());
+        assert!(in_progress.transition(PracticeStatus::InProgress).is_err());
+
+        let err = PracticeStatus::Completed
+            .transition(PracticeStatus::Abandoned)
+            .unwrap_err();
+        assert_eq!(err.code(), "CONFLICT");
+        let err = PracticeStatus::Expired
+            .transition(PracticeStatus::Completed)
+            .unwrap_err();
+        assert_eq!(err.code(), "PRACTICE_EXPIRED");
+    }
+}
@@ -0,0 +1,287 @@
+// src/scenario/catalog.rs
+
+use crate::database::redis_key;
+use crate::error::AppResult;
+use crate::subscription::{self, Role, ScenarioSlot, Tier, RANKED_SCENARIOS};
+use chrono::{DateTime, Utc};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySql, MySqlPool, QueryBuilder};
+
+/// 列表快取 (`scenario:list:{tier}:{page}`) 的存活時間；內容變更時會提前清除
+pub const LIST_CACHE_TTL_SECS: i64 = 60 * 60;
+/// 檢查情境內容是否變更的間隔
+const WATCH_INTERVAL_SECS: u64 = 60;
+
+/// 情境列表的篩選條件 (已驗證)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CatalogFilter {
+    pub tier: Option<Tier>,
+    pub category: Option<String>,
+    /// 用戶扮演的角色
+    pub role: Option<Role>,
+    pub difficulty: Option<i32>,
+    pub page: u32,
+    pub limit: u32,
+}
+
+impl CatalogFilter {
+    /// `scenario:list:{tier}:{page}`，未篩選方案時 tier 為 all
+    pub fn cache_key(&self, key_prefix: &str) -> String {
+        let tier = self.tier.map_or("all", |tier| tier.as_str());
+        redis_key(key_prefix, &format!("scenario:list:{}:{}", tier, self.page))
+    }
+
+    /// 同一頁其他篩選條件的 Hash field
+    pub fn cache_field(&self) -> String {
+        format!(
+            "category={}&role={}&difficulty={}&limit={}",
+            self.category.as_deref().unwrap_or(""),
+            self.role.map_or("", |role| role.as_str()),
+            self.difficulty
+                .map(|difficulty| difficulty.to_string())
+                .unwrap_or_default(),
+            self.limit
+        )
+    }
+}
+
+/// 上架中的情境與其在方案矩陣中的位置
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub role_1: String,
+    pub role_2: Option<String>,
+    pub category: String,
+    pub difficulty: i32,
+    pub dialogue_count: i32,
+    pub tier_required: String,
+    pub role_rank: u64,
+    pub combination_rank: u64,
+}
+
+impl CatalogEntry {
+    pub fn slot(&self) -> Option<ScenarioSlot> {
+        subscription::scenario_slot(
+            &self.role_1,
+            self.role_2.as_deref(),
+            &self.tier_required,
+            self.role_rank,
+            self.combination_rank,
+        )
+    }
+}
+
+/// 一頁情境 (與用戶無關的部分，可共用快取)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogPage {
+    pub scenarios: Vec<CatalogEntry>,
+    pub total: i64,
+}
+
+fn entries_query() -> String {
+    format!(
+        r#"
+        SELECT s.id, s.code, s.name, s.description, s.role_1, s.role_2, s.category, s.difficulty,
+               s.dialogue_count, s.tier_required, r.role_rank, r.combination_rank
+        FROM scenarios s
+        JOIN ({}) r ON r.id = s.id
+        "#,
+        RANKED_SCENARIOS
+    )
+}
+
+fn push_filters(builder: &mut QueryBuilder<'_, MySql>, filter: &CatalogFilter) {
+    builder.push(" WHERE s.is_active = 1");
+    if let Some(tier) = filter.tier {
+        builder
+            .push(" AND s.tier_required = ")
+            .push_bind(tier.as_str());
+    }
+    if let Some(category) = &filter.category {
+        builder
+            .push(" AND s.category = ")
+            .push_bind(category.clone());
+    }
+    if let Some(role) = filter.role {
+        builder
+            .push(" AND COALESCE(s.role_2, s.role_1) = ")
+            .push_bind(role.as_str());
+    }
+    if let Some(difficulty) = filter.difficulty {
+        builder.push(" AND s.difficulty = ").push_bind(difficulty);
+    }
+}
+
+/// 依篩選條件查詢一頁情境，依難度與代碼排序
+pub async fn fetch_page(pool: &MySqlPool, filter: &CatalogFilter) -> AppResult<CatalogPage> {
+    let mut count: QueryBuilder<MySql> = QueryBuilder::new("SELECT COUNT(*) FROM scenarios s");
+    push_filters(&mut count, filter);
+    let total: i64 = count.build_query_scalar().fetch_one(pool).await?;
+
+    let mut query: QueryBuilder<MySql> = QueryBuilder::new(entries_query());
+    push_filters(&mut query, filter);
+    query
+        .push(" ORDER BY s.difficulty, s.code LIMIT ")
+        .push_bind(filter.limit)
+        .push(" OFFSET ")
+        .push_bind(u64::from(filter.page.saturating_sub(1)) * u64::from(filter.limit));
+    let scenarios = query.build_query_as().fetch_all(pool).await?;
+
+    Ok(CatalogPage { scenarios, total })
+}
+
+/// 先讀 `scenario:list:{tier}:{page}` 快取，沒有時查詢資料庫並寫入
+pub async fn load_page(
+    pool: &MySqlPool,
+    redis: &ConnectionManager,
+    key_prefix: &str,
+    filter: &CatalogFilter,
+) -> AppResult<CatalogPage> {
+    let key = filter.cache_key(key_prefix);
+    let field = filter.cache_field();
+    let mut redis = redis.clone();
+    match redis.hget::<_, _, Option<String>>(&key, &field).await {
+        Ok(Some(cached)) => match serde_json::from_str(&cached) {
+            Ok(page) => return Ok(page),
+            Err(err) => log::warn!("ignoring malformed {} {}: {}", key, field, err),
+        },
+        Ok(None) => {}
+        Err(err) => log::warn!("failed to read {}: {}", key, err),
+    }
+
+    let page = fetch_page(pool, filter).await?;
+    let cached = serde_json::to_string(&page).map_err(anyhow::Error::from)?;
+    let written: redis::RedisResult<()> = redis::pipe()
+        .hset(&key, &field, cached)
+        .ignore()
+        .expire(&key, LIST_CACHE_TTL_SECS)
+        .ignore()
+        .query_async(&mut redis)
+        .await;
+    if let Err(err) = written {
+        log::warn!("failed to cache {}: {}", key, err);
+    }
+    Ok(page)
+}
+
+/// 單一情境 (已下架或不存在時回傳 None)
+pub async fn fetch_entry(pool: &MySqlPool, scenario_id: &str) -> AppResult<Option<CatalogEntry>> {
+    let entry = sqlx::query_as(&format!(
+        "{} WHERE s.id = ? AND s.is_active = 1",
+        entries_query()
+    ))
+    .bind(scenario_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(entry)
+}
+
+/// 清除所有情境列表快取，回傳刪除的 key 數
+pub async fn invalidate(redis: &ConnectionManager, key_prefix: &str) -> redis::RedisResult<usize> {
+    let mut redis = redis.clone();
+    let pattern = redis_key(key_prefix, "scenario:list:*");
+    let keys: Vec<String> = {
+        let mut iter = redis.scan_match::<_, String>(&pattern).await?;
+        let mut keys = Vec::new();
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        keys
+    };
+    if !keys.is_empty() {
+        redis.del::<_, ()>(&keys).await?;
+    }
+    Ok(keys.len())
+}
+
+/// 情境與對話內容的版本 (筆數 + 最後更新時間)
+async fn content_fingerprint(pool: &MySqlPool) -> AppResult<String> {
+    let (scenarios, scenarios_updated): (i64, Option<DateTime<Utc>>) =
+        sqlx::query_as("SELECT COUNT(*), MAX(updated_at) FROM scenarios")
+            .fetch_one(pool)
+            .await?;
+    let (dialogues, dialogues_updated): (i64, Option<DateTime<Utc>>) =
+        sqlx::query_as("SELECT COUNT(*), MAX(updated_at) FROM dialogues")
+            .fetch_one(pool)
+            .await?;
+    let timestamp = |at: Option<DateTime<Utc>>| at.map_or(0, |at| at.timestamp());
+    Ok(format!(
+        "{}:{}:{}:{}",
+        scenarios,
+        timestamp(scenarios_updated),
+        dialogues,
+        timestamp(dialogues_updated)
+    ))
+}
+
+/// 內容版本與 Redis 記錄的不同時清除列表快取並更新版本，回傳是否清除
+pub async fn refresh_if_changed(
+    pool: &MySqlPool,
+    redis: &ConnectionManager,
+    key_prefix: &str,
+) -> anyhow::Result<bool> {
+    let fingerprint = content_fingerprint(pool).await?;
+    let key = redis_key(key_prefix, "scenario:catalog:fingerprint");
+    let mut conn = redis.clone();
+    let known: Option<String> = conn.get(&key).await?;
+    if known.as_deref() == Some(fingerprint.as_str()) {
+        return Ok(false);
+    }
+    invalidate(redis, key_prefix).await?;
+    conn.set::<_, _, ()>(&key, &fingerprint).await?;
+    Ok(true)
+}
+
+/// 背景定期檢查後台是否修改了情境內容 (多個實例同時執行只會多清一次快取)
+pub fn spawn_catalog_watcher(pool: MySqlPool, redis: ConnectionManager, key_prefix: String) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(WATCH_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match refresh_if_changed(&pool, &redis, &key_prefix).await {
+                Ok(false) => {}
+                Ok(true) => log::info!("scenario content changed, cleared catalog cache"),
+                Err(err) => log::warn!("failed to check scenario content: {:#}", err),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key_and_field() {
+        let filter = CatalogFilter {
+            page: 2,
+            limit: 20,
+            ..Default::default()
+        };
+        assert_eq!(
+            filter.cache_key("nice_speak:test:"),
+            "nice_speak:test:scenario:list:all:2"
+        );
+        assert_eq!(filter.cache_field(), "category=&role=&difficulty=&limit=20");
+
+        let filter = CatalogFilter {
+            tier: Some(Tier::Basic),
+            category: Some("testing".to_string()),
+            role: Some(Role::TechLead),
+            difficulty: Some(3),
+            page: 1,
+            limit: 10,
+        };
+        assert_eq!(filter.cache_key("p:"), "p:scenario:list:basic:1");
+        assert_eq!(
+            filter.cache_field(),
+            "category=testing&role=Tech Lead&difficulty=3&limit=10"
+        );
+    }
+}
@@ -0,0 +1,398 @@
+// src/scenario/mod.rs
+
+mod catalog;
+
+pub use catalog::{
+    invalidate, load_page, spawn_catalog_watcher, CatalogEntry, CatalogFilter, CatalogPage,
+};
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use crate::subscription::{self, Role, ScenarioAccess, Tier};
+use crate::vocabulary::{self, NewWord};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+/// 每頁預設筆數
+const DEFAULT_LIMIT: u32 = 20;
+const MAX_LIMIT: u32 = 100;
+/// `scenarios.category` 欄位長度
+const MAX_CATEGORY_CHARS: usize = 50;
+
+/// 情境分類與顯示名稱 (依工作流程排序)
+pub const CATEGORIES: [(&str, &str); 5] = [
+    ("requirement", "需求分析"),
+    ("development", "開發實作"),
+    ("testing", "測試相關"),
+    ("deployment", "部署上線"),
+    ("communication", "跨國溝通"),
+];
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ScenarioQuery {
+    /// 最低方案
+    tier: Option<String>,
+    category: Option<String>,
+    /// 用戶扮演的角色
+    role: Option<String>,
+    difficulty: Option<i32>,
+    page: Option<u32>,
+    limit: Option<u32>,
+}
+
+impl ScenarioQuery {
+    /// 驗證篩選條件，空字串視為未篩選
+    pub fn into_filter(self) -> AppResult<CatalogFilter> {
+        let present = |value: Option<String>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let tier = match present(self.tier) {
+            Some(value) => Some(
+                Tier::parse(&value.to_lowercase())
+                    .ok_or_else(|| AppError::Validation(format!("Unknown tier {}", value)))?,
+            ),
+            None => None,
+        };
+        let role = match present(self.role) {
+            Some(value) => Some(
+                Role::parse(&value)
+                    .ok_or_else(|| AppError::Validation(format!("Unknown role {}", value)))?,
+            ),
+            None => None,
+        };
+        let category = present(self.category).map(|value| value.to_lowercase());
+        if category
+            .as_ref()
+            .is_some_and(|value| value.chars().count() > MAX_CATEGORY_CHARS)
+        {
+            return Err(AppError::Validation("Category is too long".to_string()));
+        }
+        if let Some(difficulty) = self.difficulty {
+            if !(1..=5).contains(&difficulty) {
+                return Err(AppError::Validation(
+                    "Difficulty must be between 1 and 5".to_string(),
+                ));
+            }
+        }
+
+        Ok(CatalogFilter {
+            tier,
+            category,
+            role,
+            difficulty: self.difficulty,
+            page: self.page.unwrap_or(1).max(1),
+            limit: self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+        })
+    }
+}
+
+/// 情境卡片
+#[derive(Debug, Serialize)]
+pub struct ScenarioCard {
+    id: String,
+    code: String,
+    name: String,
+    description: Option<String>,
+    role_1: String,
+    role_2: Option<String>,
+    category: String,
+    difficulty: i32,
+    dialogue_count: i32,
+    tier_required: String,
+    is_unlocked: bool,
+    /// 未解鎖時，可解鎖的最便宜方案
+    unlock_tier: Option<Tier>,
+}
+
+impl ScenarioCard {
+    fn new(entry: CatalogEntry, access: &ScenarioAccess) -> Self {
+        // 角色或方案無法辨識的情境任何方案都無法練習
+        let lock = match entry.slot() {
+            Some(slot) => access.lock(&slot),
+            None => Some(subscription::Lock::Tier(None)),
+        };
+        Self {
+            id: entry.id,
+            code: entry.code,
+            name: entry.name,
+            description: entry.description,
+            role_1: entry.role_1,
+            role_2: entry.role_2,
+            category: entry.category,
+            difficulty: entry.difficulty,
+            dialogue_count: entry.dialogue_count,
+            tier_required: entry.tier_required,
+            is_unlocked: lock.is_none(),
+            unlock_tier: lock.and_then(|lock| lock.required_tier()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Pagination {
+    page: u32,
+    limit: u32,
+    total: i64,
+}
+
+#[derive(Serialize)]
+pub struct ScenarioListResponse {
+    scenarios: Vec<ScenarioCard>,
+    pagination: Pagination,
+}
+
+#[derive(sqlx::FromRow)]
+struct DialogueRow {
+    sequence_number: i32,
+    speaker_role: String,
+    content: String,
+    audio_url: Option<String>,
+    vocabulary: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DialogueLine {
+    sequence: i32,
+    speaker: String,
+    content: String,
+    vocabulary: Vec<String>,
+    audio_url: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ScenarioDetail {
+    #[serde(flatten)]
+    card: ScenarioCard,
+    /// 未解鎖時不回傳對話內容
+    dialogues: Vec<DialogueLine>,
+    vocabulary: Vec<NewWord>,
+}
+
+#[derive(Serialize)]
+pub struct ScenarioDetailResponse {
+    scenario: ScenarioDetail,
+}
+
+#[derive(Serialize)]
+pub struct Category {
+    code: String,
+    name: String,
+    count: i64,
+}
+
+#[derive(Serialize)]
+pub struct CategoriesResponse {
+    categories: Vec<Category>,
+}
+
+/// `GET /api/v1/scenarios` 情境列表，標示用戶目前是否已解鎖
+pub async fn list(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<ScenarioQuery>,
+) -> AppResult<Json<ScenarioListResponse>> {
+    let filter = query.into_filter()?;
+    let page = load_page(
+        &state.pool,
+        &state.redis,
+        &state.config.redis.key_prefix,
+        &filter,
+    )
+    .await?;
+    let access = subscription::load_scenario_access(&state.pool, &user.user_id).await?;
+
+    Ok(Json(ScenarioListResponse {
+        scenarios: page
+            .scenarios
+            .into_iter()
+            .map(|entry| ScenarioCard::new(entry, &access))
+            .collect(),
+        pagination: Pagination {
+            page: filter.page,
+            limit: filter.limit,
+            total: page.total,
+        },
+    }))
+}
+
+/// `GET /api/v1/scenarios/:id` 情境詳情與對話內容
+pub async fn detail(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<ScenarioDetailResponse>> {
+    let entry = catalog::fetch_entry(&state.pool, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Scenario not found".to_string()))?;
+    let access = subscription::load_scenario_access(&state.pool, &user.user_id).await?;
+    let card = ScenarioCard::new(entry, &access);
+
+    let mut dialogues = Vec::new();
+    let mut key_words = Vec::new();
+    if card.is_unlocked {
+        let rows: Vec<DialogueRow> = sqlx::query_as(
+            r#"
+            SELECT sequence_number, speaker_role, content, audio_url, CAST(vocabulary AS CHAR) AS vocabulary
+            FROM dialogues
+            WHERE scenario_id = ?
+            ORDER BY sequence_number
+            "#,
+        )
+        .bind(&card.id)
+        .fetch_all(&state.pool)
+        .await?;
+
+        key_words =
+            vocabulary::parse_vocabulary(rows.iter().filter_map(|row| row.vocabulary.as_deref()));
+        dialogues = rows
+            .into_iter()
+            .map(|row| DialogueLine {
+                sequence: row.sequence_number,
+                speaker: row.speaker_role,
+                content: row.content,
+                vocabulary: vocabulary::parse_vocabulary(row.vocabulary.as_deref())
+                    .into_iter()
+                    .map(|word| word.word)
+                    .collect(),
+                audio_url: row.audio_url,
+            })
+            .collect();
+    }
+
+    Ok(Json(ScenarioDetailResponse {
+        scenario: ScenarioDetail {
+            card,
+            dialogues,
+            vocabulary: key_words,
+        },
+    }))
+}
+
+/// `GET /api/v1/scenarios/categories` 各分類的上架情境數
+pub async fn categories(
+    State(state): State<AppState>,
+    _user: AuthUser,
+) -> AppResult<Json<CategoriesResponse>> {
+    let counts: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT category, COUNT(*) FROM scenarios WHERE is_active = 1 GROUP BY category ORDER BY category",
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(CategoriesResponse {
+        categories: sort_categories(counts),
+    }))
+}
+
+/// 已知分類依 `CATEGORIES` 排序 (沒有情境的也列出)，其他分類接在後面並以代碼作為名稱
+fn sort_categories(counts: Vec<(String, i64)>) -> Vec<Category> {
+    let count_of = |code: &str| {
+        counts
+            .iter()
+            .filter(|(category, _)| category == code)
+            .map(|(_, count)| *count)
+            .sum()
+    };
+    let mut categories: Vec<Category> = CATEGORIES
+        .iter()
+        .map(|(code, name)| Category {
+            code: code.to_string(),
+            name: name.to_string(),
+            count: count_of(code),
+        })
+        .collect();
+    categories.extend(
+        counts
+            .iter()
+            .filter(|(category, _)| !CATEGORIES.iter().any(|(code, _)| code == category))
+            .map(|(category, count)| Category {
+                code: category.clone(),
+                name: category.clone(),
+                count: *count,
+            }),
+    );
+    categories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_into_filter() {
+        let filter = ScenarioQuery {
+            tier: Some(" Basic ".to_string()),
+            category: Some("Testing".to_string()),
+            role: Some("tl".to_string()),
+            difficulty: Some(3),
+            page: Some(0),
+            limit: Some(500),
+        }
+        .into_filter()
+        .unwrap();
+        assert_eq!(filter.tier, Some(Tier::Basic));
+        assert_eq!(filter.category.as_deref(), Some("testing"));
+        assert_eq!(filter.role, Some(Role::TechLead));
+        assert_eq!((filter.page, filter.limit), (1, MAX_LIMIT));
+
+        let empty = ScenarioQuery {
+            role: Some("".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(empty.into_filter().unwrap().role, None);
+
+        for invalid in [
+            ScenarioQuery {
+                difficulty: Some(6),
+                ..Default::default()
+            },
+            ScenarioQuery {
+                role: Some("Designer".to_string()),
+                ..Default::default()
+            },
+            ScenarioQuery {
+                tier: Some("gold".to_string()),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                invalid.into_filter(),
+                Err(AppError::Validation(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_sort_categories() {
+        let categories = sort_categories(vec![
+            ("career".to_string(), 2),
+            ("development".to_string(), 30),
+            ("testing".to_string(), 25),
+        ]);
+        let codes: Vec<_> = categories
+            .iter()
+            .map(|category| category.code.as_str())
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                "requirement",
+                "development",
+                "testing",
+                "deployment",
+                "communication",
+                "career"
+            ]
+        );
+        assert_eq!(categories[0].count, 0);
+        assert_eq!(categories[1].name, "開發實作");
+        assert_eq!(categories[5].name, "career");
+    }
+}
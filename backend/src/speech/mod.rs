@@ -0,0 +1,7 @@
+// src/speech/mod.rs
+
+pub mod stt;
+pub mod tts;
+
+pub use stt::{build_stt_provider, AudioFormat, SttProvider, Transcription, WordTiming};
+pub use tts::{build_tts_provider, serve_tts_audio, TtsCache, TtsProvider, VoiceCatalog};
@@ -0,0 +1,156 @@
+// src/speech/stt/azure.rs
+
+use super::{AudioFormat, SttProvider, Transcription, WordTiming};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Azure 的時間單位為 100 奈秒
+const TICKS_PER_MS: u64 = 10_000;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RecognitionResponse {
+    recognition_status: String,
+    #[serde(default)]
+    display_text: String,
+    #[serde(default, rename = "NBest")]
+    n_best: Vec<NBest>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NBest {
+    #[serde(default)]
+    confidence: f32,
+    #[serde(default)]
+    display: String,
+    #[serde(default)]
+    words: Vec<AzureWord>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AzureWord {
+    word: String,
+    offset: u64,
+    duration: u64,
+}
+
+/// Azure Speech 短音訊 REST API (60 秒以內)
+pub struct AzureStt {
+    api_key: String,
+    endpoint: String,
+    language: String,
+    client: reqwest::Client,
+}
+
+impl AzureStt {
+    pub fn new(api_key: &str, region: &str, language: &str) -> anyhow::Result<Self> {
+        if api_key.is_empty() {
+            anyhow::bail!("STT_API_KEY is required for the azure STT provider");
+        }
+        Ok(Self {
+            api_key: api_key.to_string(),
+            endpoint: format!(
+                "https://{}.stt.speech.microsoft.com/speech/recognition/conversation/cognitiveservices/v1",
+                region
+            ),
+            language: language.to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()?,
+        })
+    }
+}
+
+#[async_trait]
+impl SttProvider for AzureStt {
+    async fn transcribe(&self, audio: &[u8], format: AudioFormat) -> anyhow::Result<Transcription> {
+        let content_type = match format {
+            AudioFormat::Wav => "audio/wav; codecs=audio/pcm; samplerate=16000",
+            AudioFormat::OggOpus => "audio/ogg; codecs=opus",
+            AudioFormat::WebmOpus => anyhow::bail!("azure STT does not accept webm audio"),
+        };
+
+        let response: RecognitionResponse = self
+            .client
+            .post(&self.endpoint)
+            .query(&[
+                ("language", self.language.as_str()),
+                ("format", "detailed"),
+                ("wordLevelTimestamps", "true"),
+            ])
+            .header("Ocp-Apim-Subscription-Key", &self.api_key)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(audio.to_vec())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        to_transcription(response)
+    }
+}
+
+fn to_transcription(response: RecognitionResponse) -> anyhow::Result<Transcription> {
+    match response.recognition_status.as_str() {
+        "Success" => {}
+        // 沒有聽到語音，視為空白回答
+        "NoMatch" | "InitialSilenceTimeout" | "BabbleTimeout" => {
+            return Ok(Transcription::default())
+        }
+        other => anyhow::bail!("azure STT failed: {}", other),
+    }
+
+    let Some(best) = response.n_best.into_iter().next() else {
+        return Ok(Transcription {
+            transcript: response.display_text,
+            confidence: 0.0,
+            words: Vec::new(),
+        });
+    };
+
+    Ok(Transcription {
+        transcript: best.display,
+        confidence: best.confidence,
+        words: best
+            .words
+            .into_iter()
+            .map(|word| WordTiming {
+                word: word.word,
+                start_ms: word.offset / TICKS_PER_MS,
+                end_ms: (word.offset + word.duration) / TICKS_PER_MS,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_detailed_response() {
+        let body = r#"{
+            "RecognitionStatus": "Success",
+            "DisplayText": "Sure, I can.",
+            "NBest": [{"Confidence": 0.93, "Display": "Sure, I can.",
+                "Words": [{"Word": "sure", "Offset": 1000000, "Duration": 3000000},
+                          {"Word": "i", "Offset": 4000000, "Duration": 1000000},
+                          {"Word": "can", "Offset": 5000000, "Duration": 2500000}]}]
+        }"#;
+        let transcription = to_transcription(serde_json::from_str(body).unwrap()).unwrap();
+        assert_eq!(transcription.transcript, "Sure, I can.");
+        assert_eq!(transcription.words[0].start_ms, 100);
+        assert_eq!(transcription.words[2].end_ms, 750);
+    }
+
+    #[test]
+    fn test_no_match_is_empty() {
+        let body = r#"{"RecognitionStatus": "NoMatch"}"#;
+        let transcription = to_transcription(serde_json::from_str(body).unwrap()).unwrap();
+        assert!(transcription.transcript.is_empty());
+    }
+}
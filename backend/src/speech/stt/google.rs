@@ -0,0 +1,177 @@
+// src/speech/stt/google.rs
+
+use super::{AudioFormat, SttProvider, Transcription, WordTiming};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use std::time::Duration;
+
+const RECOGNIZE_URL: &str = "https://speech.googleapis.com/v1/speech:recognize";
+
+#[derive(Deserialize)]
+struct RecognizeResponse {
+    #[serde(default)]
+    results: Vec<RecognitionResult>,
+}
+
+#[derive(Deserialize)]
+struct RecognitionResult {
+    #[serde(default)]
+    alternatives: Vec<Alternative>,
+}
+
+#[derive(Deserialize)]
+struct Alternative {
+    #[serde(default)]
+    transcript: String,
+    #[serde(default)]
+    confidence: f32,
+    #[serde(default)]
+    words: Vec<WordInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WordInfo {
+    word: String,
+    start_time: String,
+    end_time: String,
+}
+
+/// Google Cloud Speech-to-Text v1 (API key)
+pub struct GoogleStt {
+    api_key: String,
+    language: String,
+    client: reqwest::Client,
+}
+
+impl GoogleStt {
+    pub fn new(api_key: &str, language: &str) -> anyhow::Result<Self> {
+        if api_key.is_empty() {
+            anyhow::bail!("STT_API_KEY is required for the google STT provider");
+        }
+        Ok(Self {
+            api_key: api_key.to_string(),
+            language: language.to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()?,
+        })
+    }
+}
+
+#[async_trait]
+impl SttProvider for GoogleStt {
+    async fn transcribe(&self, audio: &[u8], format: AudioFormat) -> anyhow::Result<Transcription> {
+        // WAV 的取樣率由檔頭判斷，Opus 需明確指定
+        let mut config = serde_json::json!({
+            "languageCode": self.language,
+            "enableWordTimeOffsets": true,
+            "enableAutomaticPunctuation": true,
+        });
+        match format {
+            AudioFormat::Wav => config["encoding"] = "LINEAR16".into(),
+            AudioFormat::OggOpus => {
+                config["encoding"] = "OGG_OPUS".into();
+                config["sampleRateHertz"] = 48000.into();
+            }
+            AudioFormat::WebmOpus => {
+                config["encoding"] = "WEBM_OPUS".into();
+                config["sampleRateHertz"] = 48000.into();
+            }
+        }
+
+        let body = serde_json::json!({
+            "config": config,
+            "audio": { "content": STANDARD.encode(audio) },
+        });
+
+        let response: RecognizeResponse = self
+            .client
+            .post(RECOGNIZE_URL)
+            .query(&[("key", self.api_key.as_str())])
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(to_transcription(response))
+    }
+}
+
+/// 多段結果依序串接；信心值取各段平均
+fn to_transcription(response: RecognizeResponse) -> Transcription {
+    let best: Vec<Alternative> = response
+        .results
+        .into_iter()
+        .filter_map(|result| result.alternatives.into_iter().next())
+        .collect();
+    if best.is_empty() {
+        return Transcription::default();
+    }
+
+    let confidence = best.iter().map(|alt| alt.confidence).sum::<f32>() / best.len() as f32;
+    let transcript = best
+        .iter()
+        .map(|alt| alt.transcript.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let words = best
+        .into_iter()
+        .flat_map(|alt| alt.words)
+        .map(|info| WordTiming {
+            word: info.word,
+            start_ms: parse_offset(&info.start_time),
+            end_ms: parse_offset(&info.end_time),
+        })
+        .collect();
+
+    Transcription {
+        transcript,
+        confidence,
+        words,
+    }
+}
+
+/// Google 的時間格式為秒數字串，例如 "1.300s"
+fn parse_offset(value: &str) -> u64 {
+    value
+        .trim_end_matches('s')
+        .parse::<f64>()
+        .map(|secs| (secs * 1000.0).round() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_recognize_response() {
+        let body = r#"{
+            "results": [
+                {"alternatives": [{"transcript": "Sure I can", "confidence": 0.9,
+                    "words": [{"word": "Sure", "startTime": "0s", "endTime": "0.400s"},
+                              {"word": "I", "startTime": "0.400s", "endTime": "0.500s"},
+                              {"word": "can", "startTime": "0.500s", "endTime": "0.900s"}]}]},
+                {"alternatives": [{"transcript": " help today", "confidence": 0.7,
+                    "words": [{"word": "help", "startTime": "1.200s", "endTime": "1.500s"},
+                              {"word": "today", "startTime": "1.500s", "endTime": "2s"}]}]}
+            ]
+        }"#;
+        let transcription = to_transcription(serde_json::from_str(body).unwrap());
+        assert_eq!(transcription.transcript, "Sure I can help today");
+        assert!((transcription.confidence - 0.8).abs() < 1e-6);
+        assert_eq!(transcription.words.len(), 5);
+        assert_eq!(transcription.words[4].end_ms, 2000);
+    }
+
+    #[test]
+    fn test_parse_empty_response() {
+        let transcription = to_transcription(serde_json::from_str("{}").unwrap());
+        assert_eq!(transcription, Transcription::default());
+    }
+}
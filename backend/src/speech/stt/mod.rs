@@ -0,0 +1,73 @@
+// src/speech/stt/mod.rs
+
+mod azure;
+mod fixture;
+mod google;
+
+pub use azure::AzureStt;
+pub use fixture::FixtureStt;
+pub use google::GoogleStt;
+
+use crate::config::ExternalConfig;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// 客戶端上傳的音訊格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AudioFormat {
+    /// 16-bit PCM WAV
+    #[default]
+    Wav,
+    OggOpus,
+    WebmOpus,
+}
+
+impl AudioFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "wav" => Some(AudioFormat::Wav),
+            "ogg" | "ogg_opus" => Some(AudioFormat::OggOpus),
+            "webm" | "webm_opus" => Some(AudioFormat::WebmOpus),
+            _ => None,
+        }
+    }
+}
+
+/// 單字時間軸 (毫秒，相對於音訊開頭)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WordTiming {
+    pub word: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// 語音辨識結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Transcription {
+    pub transcript: String,
+    /// 0.0 - 1.0
+    pub confidence: f32,
+    pub words: Vec<WordTiming>,
+}
+
+/// 語音轉文字服務
+#[async_trait]
+pub trait SttProvider: Send + Sync {
+    async fn transcribe(&self, audio: &[u8], format: AudioFormat) -> anyhow::Result<Transcription>;
+}
+
+/// 依 `ExternalConfig.stt_provider` 建立語音辨識服務
+pub fn build_stt_provider(config: &ExternalConfig) -> anyhow::Result<Arc<dyn SttProvider>> {
+    let provider: Arc<dyn SttProvider> = match config.stt_provider.as_str() {
+        "google" => Arc::new(GoogleStt::new(&config.stt_api_key, &config.stt_language)?),
+        "azure" => Arc::new(AzureStt::new(
+            &config.stt_api_key,
+            &config.stt_region,
+            &config.stt_language,
+        )?),
+        "fixture" => Arc::new(FixtureStt::new(&config.stt_fixture_dir)),
+        other => anyhow::bail!("unknown STT_PROVIDER {}", other),
+    };
+    Ok(provider)
+}
@@ -0,0 +1,92 @@
+// src/speech/tts/azure.rs
+
+use super::{SpeechFormat, SynthesisRequest, TtsProvider};
+use async_trait::async_trait;
+use std::time::Duration;
+
+const OUTPUT_FORMAT: &str = "audio-24khz-48kbitrate-mono-mp3";
+
+/// Azure Speech 文字轉語音 REST API
+pub struct AzureTts {
+    api_key: String,
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+impl AzureTts {
+    pub fn new(api_key: &str, region: &str) -> anyhow::Result<Self> {
+        if api_key.is_empty() {
+            anyhow::bail!("TTS_API_KEY is required for the azure TTS provider");
+        }
+        Ok(Self {
+            api_key: api_key.to_string(),
+            endpoint: format!("https://{}.tts.speech.microsoft.com/cognitiveservices/v1", region),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()?,
+        })
+    }
+}
+
+#[async_trait]
+impl TtsProvider for AzureTts {
+    fn format(&self) -> SpeechFormat {
+        SpeechFormat::Mp3
+    }
+
+    async fn synthesize(&self, request: &SynthesisRequest) -> anyhow::Result<Vec<u8>> {
+        let audio = self
+            .client
+            .post(&self.endpoint)
+            .header("Ocp-Apim-Subscription-Key", &self.api_key)
+            .header("X-Microsoft-OutputFormat", OUTPUT_FORMAT)
+            .header(reqwest::header::CONTENT_TYPE, "application/ssml+xml")
+            .header(reqwest::header::USER_AGENT, "nice-speak-backend")
+            .body(build_ssml(request))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        Ok(audio.to_vec())
+    }
+}
+
+/// 產生 SSML；voice 名稱的前五碼即語系 (例如 en-US-JennyNeural → en-US)
+fn build_ssml(request: &SynthesisRequest) -> String {
+    let lang = request.voice.get(..5).unwrap_or("en-US");
+    format!(
+        r#"<speak version="1.0" xmlns="http://www.w3.org/2001/10/synthesis" xml:lang="{lang}"><voice name="{voice}"><prosody rate="{rate:.2}">{text}</prosody></voice></speak>"#,
+        lang = escape_xml(lang),
+        voice = escape_xml(&request.voice),
+        rate = request.rate,
+        text = escape_xml(&request.text),
+    )
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_ssml_escapes_text() {
+        let ssml = build_ssml(&SynthesisRequest {
+            text: "Q&A <today>, isn't it?".to_string(),
+            voice: "en-GB-RyanNeural".to_string(),
+            rate: 0.9,
+        });
+        assert!(ssml.contains(r#"xml:lang="en-GB""#));
+        assert!(ssml.contains(r#"<voice name="en-GB-RyanNeural">"#));
+        assert!(ssml.contains(r#"<prosody rate="0.90">"#));
+        assert!(ssml.contains("Q&amp;A &lt;today&gt;, isn&apos;t it?"));
+    }
+}
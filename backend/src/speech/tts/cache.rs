@@ -0,0 +1,196 @@
+// src/speech/tts/cache.rs
+
+use super::{SpeechFormat, SynthesisRequest, TtsProvider, VoiceCatalog};
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 以內容定址的語音快取：檔名為 voice + rate + text 的 SHA-256，同一句話只合成一次
+#[derive(Clone)]
+pub struct TtsCache {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    provider: Arc<dyn TtsProvider>,
+    voices: VoiceCatalog,
+    rate: f32,
+    dir: PathBuf,
+    base_url: String,
+}
+
+impl TtsCache {
+    pub fn new(
+        provider: Arc<dyn TtsProvider>,
+        voices: VoiceCatalog,
+        rate: f32,
+        dir: impl Into<PathBuf>,
+        base_url: &str,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                provider,
+                voices,
+                rate,
+                dir: dir.into(),
+                base_url: base_url.trim_end_matches('/').to_string(),
+            }),
+        }
+    }
+
+    /// 取得角色說這句話的音檔網址，快取中沒有時才呼叫合成服務
+    pub async fn audio_url(&self, text: &str, role: &str) -> anyhow::Result<String> {
+        let request = SynthesisRequest {
+            text: text.trim().to_string(),
+            voice: self.inner.voices.voice_for(role),
+            rate: self.inner.rate,
+        };
+        let format = self.inner.provider.format();
+        let file_name = format!("{}.{}", cache_key(&request), format.extension());
+        let path = self.inner.dir.join(&file_name);
+
+        if !tokio::fs::try_exists(&path).await? {
+            let audio = self.inner.provider.synthesize(&request).await?;
+            tokio::fs::create_dir_all(&self.inner.dir).await?;
+            // 先寫暫存檔再改名，同時合成同一句話時不會讀到寫到一半的檔案
+            let temp = self
+                .inner
+                .dir
+                .join(format!("{}.{}.tmp", file_name, uuid::Uuid::new_v4().simple()));
+            tokio::fs::write(&temp, &audio).await?;
+            tokio::fs::rename(&temp, &path).await?;
+        }
+
+        Ok(format!("{}/media/tts/{}", self.inner.base_url, file_name))
+    }
+
+    /// 讀取快取中的音檔；檔名不合法或不存在時回傳 None
+    async fn read(&self, file_name: &str) -> anyhow::Result<Option<(SpeechFormat, Vec<u8>)>> {
+        let Some((key, extension)) = file_name.split_once('.') else {
+            return Ok(None);
+        };
+        let Some(format) = SpeechFormat::from_extension(extension) else {
+            return Ok(None);
+        };
+        if key.len() != 64 || !key.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Ok(None);
+        }
+
+        match tokio::fs::read(self.inner.dir.join(file_name)).await {
+            Ok(audio) => Ok(Some((format, audio))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// 快取鍵：voice、語速 (小數兩位) 與文字的 SHA-256
+pub fn cache_key(request: &SynthesisRequest) -> String {
+    let material = format!("{}\n{:.2}\n{}", request.voice, request.rate, request.text);
+    Sha256::digest(material.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// `GET /media/tts/:file` 提供快取的合成音檔；內容不會變動，可長期快取
+pub async fn serve_tts_audio(
+    State(state): State<AppState>,
+    Path(file_name): Path<String>,
+) -> AppResult<Response> {
+    let (format, audio) = state
+        .tts
+        .read(&file_name)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Audio not found".to_string()))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type()),
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+        ],
+        audio,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speech::tts::LocalTts;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 計算實際合成次數
+    struct CountingTts {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TtsProvider for CountingTts {
+        fn format(&self) -> SpeechFormat {
+            SpeechFormat::Wav
+        }
+
+        async fn synthesize(&self, request: &SynthesisRequest) -> anyhow::Result<Vec<u8>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            LocalTts::new().synthesize(request).await
+        }
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_voice_rate_and_text() {
+        let base = SynthesisRequest {
+            text: "Hello".to_string(),
+            voice: "en-US-JennyNeural".to_string(),
+            rate: 1.0,
+        };
+        let key = cache_key(&base);
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, cache_key(&base.clone()));
+        assert_ne!(key, cache_key(&SynthesisRequest { rate: 0.9, ..base.clone() }));
+        assert_ne!(
+            key,
+            cache_key(&SynthesisRequest {
+                voice: "en-US-GuyNeural".to_string(),
+                ..base.clone()
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audio_is_synthesized_once() {
+        let dir = std::env::temp_dir().join(format!("tts-{}", uuid::Uuid::new_v4()));
+        let provider = Arc::new(CountingTts {
+            calls: AtomicUsize::new(0),
+        });
+        let cache = TtsCache::new(
+            provider.clone(),
+            VoiceCatalog::new("en-US-JennyNeural", ""),
+            1.0,
+            &dir,
+            "http://localhost:3000/",
+        );
+
+        let first = cache.audio_url("Let's ship it", "Tech Lead").await.unwrap();
+        let second = cache.audio_url("Let's ship it ", "Tech Lead").await.unwrap();
+        assert_eq!(first, second);
+        assert!(first.starts_with("http://localhost:3000/media/tts/"));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+        let file_name = first.rsplit('/').next().unwrap();
+        let (format, audio) = cache.read(file_name).await.unwrap().unwrap();
+        assert_eq!(format, SpeechFormat::Wav);
+        assert_eq!(&audio[..4], b"RIFF");
+        assert!(cache.read("../secret.wav").await.unwrap().is_none());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
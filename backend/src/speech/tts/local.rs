@@ -0,0 +1,98 @@
+// src/speech/tts/local.rs
+
+use super::{SpeechFormat, SynthesisRequest, TtsProvider};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+const SAMPLE_RATE: u32 = 16_000;
+/// 每個字的音長與字間停頓 (正常語速)
+const WORD_MS: f32 = 300.0;
+const GAP_MS: f32 = 60.0;
+
+/// 本機替代的語音合成：每個字產生一段短音，音高依 voice 決定
+///
+/// 不需外部服務且輸出固定，供開發與測試時讓 App 有音檔可播放。
+#[derive(Default)]
+pub struct LocalTts;
+
+impl LocalTts {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TtsProvider for LocalTts {
+    fn format(&self) -> SpeechFormat {
+        SpeechFormat::Wav
+    }
+
+    async fn synthesize(&self, request: &SynthesisRequest) -> anyhow::Result<Vec<u8>> {
+        Ok(render_wav(request))
+    }
+}
+
+fn render_wav(request: &SynthesisRequest) -> Vec<u8> {
+    let rate = if request.rate > 0.0 { request.rate } else { 1.0 };
+    let words = request.text.split_whitespace().count().max(1);
+    let word_samples = (WORD_MS / rate / 1000.0 * SAMPLE_RATE as f32) as usize;
+    let gap_samples = (GAP_MS / rate / 1000.0 * SAMPLE_RATE as f32) as usize;
+
+    let pitch = 180.0 + f32::from(Sha256::digest(request.voice.as_bytes())[0] % 16) * 15.0;
+    let mut samples: Vec<i16> = Vec::with_capacity(words * (word_samples + gap_samples));
+    for _ in 0..words {
+        samples.extend((0..word_samples).map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            ((t * pitch * std::f32::consts::TAU).sin() * 6_000.0) as i16
+        }));
+        samples.extend(std::iter::repeat_n(0, gap_samples));
+    }
+
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(text: &str, rate: f32) -> SynthesisRequest {
+        SynthesisRequest {
+            text: text.to_string(),
+            voice: "en-US-JennyNeural".to_string(),
+            rate,
+        }
+    }
+
+    #[test]
+    fn test_render_wav_is_deterministic() {
+        let first = render_wav(&request("Good morning team", 1.0));
+        let second = render_wav(&request("Good morning team", 1.0));
+        assert_eq!(first, second);
+        assert_eq!(&first[..4], b"RIFF");
+        assert_eq!(&first[8..12], b"WAVE");
+    }
+
+    #[test]
+    fn test_slower_rate_is_longer() {
+        let normal = render_wav(&request("Good morning team", 1.0));
+        let slow = render_wav(&request("Good morning team", 0.5));
+        assert!(slow.len() > normal.len());
+    }
+}
@@ -0,0 +1,74 @@
+// src/speech/tts/mod.rs
+
+mod azure;
+mod cache;
+mod local;
+mod voices;
+
+pub use azure::AzureTts;
+pub use cache::{serve_tts_audio, TtsCache};
+pub use local::LocalTts;
+pub use voices::VoiceCatalog;
+
+use crate::config::ExternalConfig;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// 合成請求
+#[derive(Debug, Clone, PartialEq)]
+pub struct SynthesisRequest {
+    pub text: String,
+    pub voice: String,
+    /// 語速倍率，1.0 為正常速度
+    pub rate: f32,
+}
+
+/// 合成音訊的檔案格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeechFormat {
+    Mp3,
+    Wav,
+}
+
+impl SpeechFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SpeechFormat::Mp3 => "mp3",
+            SpeechFormat::Wav => "wav",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            SpeechFormat::Mp3 => "audio/mpeg",
+            SpeechFormat::Wav => "audio/wav",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "mp3" => Some(SpeechFormat::Mp3),
+            "wav" => Some(SpeechFormat::Wav),
+            _ => None,
+        }
+    }
+}
+
+/// 文字轉語音服務
+#[async_trait]
+pub trait TtsProvider: Send + Sync {
+    /// 輸出的音訊格式
+    fn format(&self) -> SpeechFormat;
+
+    async fn synthesize(&self, request: &SynthesisRequest) -> anyhow::Result<Vec<u8>>;
+}
+
+/// 依 `ExternalConfig.tts_provider` 建立語音合成服務
+pub fn build_tts_provider(config: &ExternalConfig) -> anyhow::Result<Arc<dyn TtsProvider>> {
+    let provider: Arc<dyn TtsProvider> = match config.tts_provider.as_str() {
+        "azure" => Arc::new(AzureTts::new(&config.tts_api_key, &config.tts_region)?),
+        "local" => Arc::new(LocalTts::new()),
+        other => anyhow::bail!("unknown TTS_PROVIDER {}", other),
+    };
+    Ok(provider)
+}
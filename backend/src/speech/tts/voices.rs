@@ -0,0 +1,68 @@
+// src/speech/tts/voices.rs
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// 未指定聲音的角色會依角色名稱固定分配其中一個
+const VOICE_POOL: &[&str] = &[
+    "en-US-JennyNeural",
+    "en-US-GuyNeural",
+    "en-US-AriaNeural",
+    "en-US-DavisNeural",
+    "en-GB-SoniaNeural",
+    "en-GB-RyanNeural",
+];
+
+/// 情境角色 → 合成聲音
+#[derive(Debug, Clone)]
+pub struct VoiceCatalog {
+    default_voice: String,
+    overrides: HashMap<String, String>,
+}
+
+impl VoiceCatalog {
+    /// `mapping` 格式為 `角色=聲音;角色=聲音`，例如 `Tech Lead=en-US-GuyNeural;Client=en-GB-SoniaNeural`
+    pub fn new(default_voice: &str, mapping: &str) -> Self {
+        let overrides = mapping
+            .split(';')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(role, voice)| (role.trim().to_lowercase(), voice.trim().to_string()))
+            .filter(|(role, voice)| !role.is_empty() && !voice.is_empty())
+            .collect();
+
+        Self {
+            default_voice: default_voice.to_string(),
+            overrides,
+        }
+    }
+
+    /// 同一個角色永遠得到同一個聲音，讓整段對話的聲音一致
+    pub fn voice_for(&self, role: &str) -> String {
+        let role = role.trim().to_lowercase();
+        if let Some(voice) = self.overrides.get(&role) {
+            return voice.clone();
+        }
+        if role.is_empty() {
+            return self.default_voice.clone();
+        }
+
+        let index = Sha256::digest(role.as_bytes())[0] as usize % VOICE_POOL.len();
+        VOICE_POOL[index].to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_voice_for_role() {
+        let catalog = VoiceCatalog::new("en-US-JennyNeural", "Tech Lead = en-US-GuyNeural; bad;");
+        assert_eq!(catalog.voice_for("tech lead"), "en-US-GuyNeural");
+        assert_eq!(catalog.voice_for(""), "en-US-JennyNeural");
+
+        let pm = catalog.voice_for("Product Manager");
+        assert_eq!(pm, catalog.voice_for("product manager "));
+        assert!(VOICE_POOL.contains(&pm.as_str()));
+    }
+}
@@ -0,0 +1,104 @@
+// src/state.rs
+
+use crate::config::Config;
+use crate::device::UsageEventWriter;
+use crate::evaluation::{self, Evaluator};
+use crate::notebook::ErrorNotebook;
+use crate::notification::{self, Notifier};
+use crate::payment::{self, EcPay};
+use crate::practice;
+use crate::scenario;
+use crate::speech::{self, SttProvider, TtsCache, VoiceCatalog};
+use crate::subscription::{self, SubscriptionEvents};
+use axum::extract::FromRef;
+use mongodb::Database;
+use redis::aio::ConnectionManager;
+use sqlx::MySqlPool;
+use std::sync::Arc;
+
+/// 各 handler 共用的應用狀態
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: MySqlPool,
+    pub redis: ConnectionManager,
+    pub config: Arc<Config>,
+    pub usage_events: UsageEventWriter,
+    pub notifier: Notifier,
+    pub stt: Arc<dyn SttProvider>,
+    pub tts: TtsCache,
+    pub evaluator: Arc<dyn Evaluator>,
+    pub error_notebook: ErrorNotebook,
+    pub payments: EcPay,
+    pub subscription_events: SubscriptionEvents,
+}
+
+impl AppState {
+    /// 建立狀態並啟動背景 worker (需在 tokio runtime 內呼叫)
+    pub fn new(
+        pool: MySqlPool,
+        redis: ConnectionManager,
+        mongo: Database,
+        config: Config,
+    ) -> anyhow::Result<Self> {
+        let transport = notification::build_transport(&config.external)?;
+        let stt = speech::build_stt_provider(&config.external)?;
+        let tts = TtsCache::new(
+            speech::build_tts_provider(&config.external)?,
+            VoiceCatalog::new(&config.external.tts_voice, &config.external.tts_voice_map),
+            config.external.tts_rate,
+            &config.external.tts_cache_dir,
+            &config.app_url,
+        );
+        let evaluator = evaluation::build_evaluator(&config.external)?;
+        let payments = payment::build_gateway(&config)?;
+        let error_notebook = ErrorNotebook::new(&mongo);
+        let indexing = error_notebook.clone();
+        tokio::spawn(async move {
+            if let Err(err) = indexing.ensure_indexes().await {
+                log::warn!("failed to create error_logs indexes: {}", err);
+            }
+        });
+        practice::spawn_expiry_sweeper(pool.clone(), config.practice.timeout_minutes);
+        scenario::spawn_catalog_watcher(pool.clone(), redis.clone(), config.redis.key_prefix.clone());
+        let notifier = Notifier::new(pool.clone(), transport);
+        let subscription_events = SubscriptionEvents::new();
+        subscription::spawn_event_listener(
+            &subscription_events,
+            redis.clone(),
+            config.redis.key_prefix.clone(),
+            notifier.clone(),
+        );
+        subscription::spawn_lifecycle_scheduler(
+            pool.clone(),
+            payments.clone(),
+            subscription_events.clone(),
+            config.subscription.clone(),
+        );
+
+        Ok(Self {
+            usage_events: UsageEventWriter::spawn(pool.clone()),
+            notifier,
+            stt,
+            tts,
+            evaluator,
+            error_notebook,
+            payments,
+            subscription_events,
+            pool,
+            redis,
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl FromRef<AppState> for MySqlPool {
+    fn from_ref(state: &AppState) -> Self {
+        state.pool.clone()
+    }
+}
+
+impl FromRef<AppState> for ConnectionManager {
+    fn from_ref(state: &AppState) -> Self {
+        state.redis.clone()
+    }
+}
@@ -0,0 +1,230 @@
+// src/statistics/mod.rs
+
+mod streak;
+
+pub use streak::{current_streak, parse_timezone, DEFAULT_TIMEZONE};
+
+use crate::auth::AuthUser;
+use crate::database::redis_key;
+use crate::error::{AppError, AppResult};
+use crate::level::{self, MAX_LEVEL};
+use crate::state::AppState;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+/// 統計快取 (`user:{id}:statistics`) 的存活時間；練習完成時清除
+const CACHE_TTL_SECS: i64 = 60 * 60;
+
+#[derive(Deserialize)]
+pub struct OverviewQuery {
+    /// IANA 時區；省略時使用用戶設定的時區
+    timezone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelProgress {
+    current: i32,
+    next: i32,
+    progress_percent: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Overview {
+    total_practices: i64,
+    average_score: i64,
+    /// 秒
+    total_practice_time: i64,
+    /// 天
+    current_streak: u32,
+    level_progress: LevelProgress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CategoryStat {
+    category: String,
+    count: i64,
+    average_score: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DifficultyStat {
+    difficulty: i32,
+    count: i64,
+    average_score: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Breakdown {
+    by_category: Vec<CategoryStat>,
+    by_difficulty: Vec<DifficultyStat>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverviewResponse {
+    overview: Overview,
+    breakdown: Breakdown,
+    timezone: String,
+}
+
+/// 用戶統計快取的 Redis key (Hash，field 為時區與當地日期)
+pub fn cache_key(key_prefix: &str, user_id: &str) -> String {
+    redis_key(key_prefix, &format!("user:{}:statistics", user_id))
+}
+
+/// 練習完成後清除統計快取
+pub async fn invalidate(redis: &ConnectionManager, key_prefix: &str, user_id: &str) {
+    let key = cache_key(key_prefix, user_id);
+    if let Err(err) = redis.clone().del::<_, ()>(&key).await {
+        log::warn!("failed to invalidate {}: {}", key, err);
+    }
+}
+
+/// `GET /api/v1/statistics/overview` 練習總覽與分類 / 難度統計
+pub async fn overview(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<OverviewQuery>,
+) -> AppResult<Json<OverviewResponse>> {
+    let timezone = match query.timezone.as_deref().filter(|name| !name.trim().is_empty()) {
+        Some(name) => parse_timezone(name)
+            .ok_or_else(|| AppError::Validation(format!("Unknown timezone {}", name)))?,
+        None => user_timezone(&state.pool, &user.user_id).await?,
+    };
+
+    // 連續天數依當地日期計算，換日後 field 不同，不會讀到前一天的結果
+    let now = Utc::now();
+    let key = cache_key(&state.config.redis.key_prefix, &user.user_id);
+    let field = format!("{}:{}", timezone.name(), now.with_timezone(&timezone).date_naive());
+    let mut redis = state.redis.clone();
+    match redis.hget::<_, _, Option<String>>(&key, &field).await {
+        Ok(Some(cached)) => match serde_json::from_str(&cached) {
+            Ok(response) => return Ok(Json(response)),
+            Err(err) => log::warn!("ignoring malformed {} {}: {}", key, field, err),
+        },
+        Ok(None) => {}
+        Err(err) => log::warn!("failed to read {}: {}", key, err),
+    }
+
+    let response = aggregate(&state.pool, &user.user_id, timezone, now).await?;
+    let cached = serde_json::to_string(&response).map_err(anyhow::Error::from)?;
+    let written: redis::RedisResult<()> = redis::pipe()
+        .hset(&key, &field, cached)
+        .ignore()
+        .expire(&key, CACHE_TTL_SECS)
+        .ignore()
+        .query_async(&mut redis)
+        .await;
+    if let Err(err) = written {
+        log::warn!("failed to cache {}: {}", key, err);
+    }
+    Ok(Json(response))
+}
+
+async fn user_timezone(pool: &MySqlPool, user_id: &str) -> AppResult<Tz> {
+    let name: Option<Option<String>> = sqlx::query_scalar("SELECT timezone FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(name
+        .flatten()
+        .and_then(|name| parse_timezone(&name))
+        .unwrap_or(DEFAULT_TIMEZONE))
+}
+
+/// 彙整已完成的練習 (`practice_records` × `scenarios`)
+async fn aggregate(
+    pool: &MySqlPool,
+    user_id: &str,
+    timezone: Tz,
+    now: DateTime<Utc>,
+) -> AppResult<OverviewResponse> {
+    let (total_practices, average_score, total_practice_time): (i64, Option<i64>, Option<i64>) =
+        sqlx::query_as(
+            r#"
+            SELECT COUNT(*),
+                   CAST(ROUND(AVG(total_score)) AS SIGNED),
+                   CAST(SUM(TIMESTAMPDIFF(SECOND, started_at, completed_at)) AS SIGNED)
+            FROM practice_records
+            WHERE user_id = ? AND status = 'completed'
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+    let completed_at: Vec<DateTime<Utc>> = sqlx::query_scalar(
+        r#"
+        SELECT completed_at FROM practice_records
+        WHERE user_id = ? AND status = 'completed' AND completed_at IS NOT NULL
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let by_category: Vec<CategoryStat> = sqlx::query_as(
+        r#"
+        SELECT s.category, COUNT(*) AS count, CAST(ROUND(AVG(p.total_score)) AS SIGNED) AS average_score
+        FROM practice_records p
+        JOIN scenarios s ON s.id = p.scenario_id
+        WHERE p.user_id = ? AND p.status = 'completed'
+        GROUP BY s.category
+        ORDER BY count DESC, s.category
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let by_difficulty: Vec<DifficultyStat> = sqlx::query_as(
+        r#"
+        SELECT s.difficulty, COUNT(*) AS count, CAST(ROUND(AVG(p.total_score)) AS SIGNED) AS average_score
+        FROM practice_records p
+        JOIN scenarios s ON s.id = p.scenario_id
+        WHERE p.user_id = ? AND p.status = 'completed'
+        GROUP BY s.difficulty
+        ORDER BY s.difficulty
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let level = level::load_level(pool, user_id).await?;
+    Ok(OverviewResponse {
+        overview: Overview {
+            total_practices,
+            average_score: average_score.unwrap_or(0),
+            total_practice_time: total_practice_time.unwrap_or(0).max(0),
+            current_streak: current_streak(&completed_at, timezone, now),
+            level_progress: LevelProgress {
+                current: level.current_level,
+                next: (level.current_level + 1).min(MAX_LEVEL),
+                progress_percent: level::progress_percent(&level),
+            },
+        },
+        breakdown: Breakdown {
+            by_category,
+            by_difficulty,
+        },
+        timezone: timezone.name().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(cache_key("nice_speak:test:", "u1"), "nice_speak:test:user:u1:statistics");
+    }
+}
@@ -0,0 +1,79 @@
+// src/statistics/streak.rs
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+
+/// 用戶沒有設定時區時使用台灣時間
+pub const DEFAULT_TIMEZONE: Tz = chrono_tz::Asia::Taipei;
+
+/// 解析 IANA 時區名稱 (例如 `Asia/Taipei`)
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.trim().parse().ok()
+}
+
+/// 連續練習天數：以用戶時區的日期計算，今天還沒練習時從昨天往回算
+///
+/// `completed_at` 不需排序，同一天多次練習只算一天。
+pub fn current_streak(completed_at: &[DateTime<Utc>], timezone: Tz, now: DateTime<Utc>) -> u32 {
+    let mut days: Vec<NaiveDate> = completed_at
+        .iter()
+        .map(|at| at.with_timezone(&timezone).date_naive())
+        .collect();
+    days.sort_unstable_by(|a, b| b.cmp(a));
+    days.dedup();
+
+    let today = now.with_timezone(&timezone).date_naive();
+    let mut expected = match days.first() {
+        Some(day) if *day == today => today,
+        Some(day) if *day == today - Duration::days(1) => *day,
+        _ => return 0,
+    };
+    let mut streak = 0;
+    for day in days {
+        if day != expected {
+            break;
+        }
+        streak += 1;
+        expected = day - Duration::days(1);
+    }
+    streak
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_streak_counts_consecutive_days() {
+        let now = at(10, 12);
+        let completed = [at(10, 1), at(9, 3), at(9, 5), at(8, 2), at(6, 2)];
+        assert_eq!(current_streak(&completed, chrono_tz::UTC, now), 3);
+        // 今天還沒練習，昨天以前的連續天數仍有效
+        assert_eq!(current_streak(&completed[1..], chrono_tz::UTC, now), 2);
+        assert_eq!(current_streak(&[at(7, 2)], chrono_tz::UTC, now), 0);
+        assert_eq!(current_streak(&[], chrono_tz::UTC, now), 0);
+    }
+
+    #[test]
+    fn test_streak_uses_learner_timezone() {
+        // UTC 3/9 20:00 與 3/10 02:00 在台北是 3/10 同一天，在紐約是 3/9 兩次
+        let completed = [at(9, 20), at(10, 2), at(8, 20)];
+        let now = at(10, 12);
+        assert_eq!(current_streak(&completed, DEFAULT_TIMEZONE, now), 2);
+        let new_york = parse_timezone("America/New_York").unwrap();
+        // 紐約的 3/10 08:00 還沒練習；3/9 與 3/8 連續
+        assert_eq!(current_streak(&completed, new_york, now), 2);
+        assert_eq!(current_streak(&completed[..2], new_york, now), 1);
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone(" Asia/Taipei "), Some(DEFAULT_TIMEZONE));
+        assert_eq!(parse_timezone("Mars/Olympus"), None);
+    }
+}
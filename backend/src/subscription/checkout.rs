@@ -0,0 +1,379 @@
+// src/subscription/checkout.rs
+
+use super::entitlement::Role;
+use super::{load_entitlement, CURRENT_SUBSCRIPTION};
+use super::pricing::{self, BillingCycle, LineItem, Promotion, Quote};
+use super::tier::{PartnerScope, Tier};
+use super::trial_period::evaluation_purchased;
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::level;
+use crate::payment::{self, CheckoutForm, CheckoutOrder, Period};
+use crate::state::AppState;
+use axum::{extract::State, Json};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+/// 報價鎖定時間，期間內付款金額與報價相同
+pub const QUOTE_LOCK_MINUTES: i64 = 15;
+
+/// 支援的付款方式 (PRICING.md「支付方式」)
+pub const PAYMENT_METHODS: &[&str] = &["credit_card", "apple_pay", "google_pay", "atm", "cvs"];
+
+/// 可以自動續訂 (綠界信用卡定期定額) 的付款方式
+const RECURRING_PAYMENT_METHODS: &[&str] = &["credit_card", "google_pay"];
+
+#[derive(Serialize)]
+pub struct PlanPrice {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    monthly: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    yearly: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    one_time: Option<u32>,
+    /// 已解鎖的等級折扣 (0.8 / 0.6)，沒有時為 null
+    discount: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct PlanFeatures {
+    scenarios: u32,
+    roles: Vec<Role>,
+    dialogue_partners: u32,
+}
+
+#[derive(Serialize)]
+pub struct Plan {
+    tier: Tier,
+    name: &'static str,
+    price: PlanPrice,
+    features: PlanFeatures,
+    is_current: bool,
+}
+
+#[derive(Serialize)]
+pub struct PlansResponse {
+    plans: Vec<Plan>,
+}
+
+#[derive(Deserialize)]
+pub struct QuoteRequest {
+    plan_tier: String,
+    billing_cycle: String,
+    promo_code: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct QuoteResponse {
+    quote_id: String,
+    expires_at: DateTime<Utc>,
+    #[serde(flatten)]
+    quote: Quote,
+}
+
+#[derive(Deserialize)]
+pub struct PurchaseRequest {
+    quote_id: String,
+    payment_method: String,
+    /// 以信用卡定期定額自動續訂 (只適用月繳 / 年繳)
+    #[serde(default)]
+    auto_renew: bool,
+}
+
+#[derive(Serialize)]
+pub struct PurchaseResponse {
+    order_id: String,
+    status: &'static str,
+    amount: u32,
+    currency: String,
+    items: Vec<LineItem>,
+    /// 綠界結帳頁面，需以 POST 送出 `payment_form.fields`
+    payment_url: String,
+    payment_form: CheckoutForm,
+}
+
+#[derive(sqlx::FromRow)]
+struct QuotedPayment {
+    tier: String,
+    billing_cycle: String,
+    status: String,
+    amount: i32,
+    currency: String,
+    line_items: String,
+    promo_code: Option<String>,
+    quote_expires_at: DateTime<Utc>,
+}
+
+/// `GET /api/v1/subscription/plans` 方案列表 (含用戶已解鎖的等級折扣)
+pub async fn plans(State(state): State<AppState>, user: AuthUser) -> AppResult<Json<PlansResponse>> {
+    let pool = &state.pool;
+    let current = load_entitlement(pool, &user.user_id).await?.tier;
+    let discount = level::discount_percentage(&level::load_level(pool, &user.user_id).await?);
+
+    let mut plans = Vec::new();
+    for tier in Tier::ALL.into_iter().filter(|tier| *tier != Tier::Free) {
+        let spec = tier.spec();
+        let price_for = |cycle| pricing::calculate(tier, cycle, 0, None).map(|quote| quote.total);
+        let price = if tier == Tier::Evaluation {
+            PlanPrice {
+                monthly: None,
+                yearly: None,
+                one_time: Some(price_for(BillingCycle::OneTime)?),
+                discount: None,
+            }
+        } else {
+            PlanPrice {
+                monthly: Some(price_for(BillingCycle::Monthly)?),
+                yearly: Some(price_for(BillingCycle::Yearly)?),
+                one_time: None,
+                discount: (discount > 0).then(|| (100 - discount) as f64 / 100.0),
+            }
+        };
+
+        plans.push(Plan {
+            tier,
+            name: spec.name,
+            price,
+            features: PlanFeatures {
+                scenarios: spec.total_scenarios(),
+                roles: Role::ALL.to_vec(),
+                dialogue_partners: match spec.partners {
+                    PartnerScope::Any => 0,
+                    _ => spec.partner_count(),
+                },
+            },
+            is_current: tier == current,
+        });
+    }
+
+    Ok(Json(PlansResponse { plans }))
+}
+
+/// `POST /api/v1/subscription/quote` 計算並鎖定報價
+pub async fn quote(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<QuoteRequest>,
+) -> AppResult<Json<QuoteResponse>> {
+    let tier = Tier::parse(payload.plan_tier.trim())
+        .ok_or_else(|| AppError::Validation(format!("Unknown plan {}", payload.plan_tier)))?;
+    let cycle = BillingCycle::parse(payload.billing_cycle.trim()).ok_or_else(|| {
+        AppError::Validation(format!("Unknown billing cycle {}", payload.billing_cycle))
+    })?;
+
+    let pool = &state.pool;
+    if tier == Tier::Evaluation {
+        ensure_evaluation_available(&mut *pool.acquire().await?, &user.user_id).await?;
+    }
+    let promotion = match payload.promo_code.as_deref().map(str::trim).filter(|code| !code.is_empty()) {
+        Some(code) => Some(find_promotion(pool, code).await?),
+        None => None,
+    };
+    let discount = level::discount_percentage(&level::load_level(pool, &user.user_id).await?);
+    let quote = pricing::calculate(tier, cycle, discount, promotion.as_ref())?;
+
+    let quote_id = uuid::Uuid::new_v4().to_string();
+    let expires_at = Utc::now() + Duration::minutes(QUOTE_LOCK_MINUTES);
+    sqlx::query(
+        r#"
+        INSERT INTO payments
+            (id, user_id, tier, billing_cycle, list_price, cycle_discount, level_discount_percentage,
+             level_discount, promo_code, promo_discount, amount, currency, line_items, status,
+             quote_expires_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'quoted', ?)
+        "#,
+    )
+    .bind(&quote_id)
+    .bind(&user.user_id)
+    .bind(quote.tier.as_str())
+    .bind(quote.billing_cycle.as_str())
+    .bind(quote.list_price)
+    .bind(quote.cycle_discount)
+    .bind(quote.level_discount_percentage)
+    .bind(quote.level_discount)
+    .bind(&quote.promo_code)
+    .bind(quote.promo_discount)
+    .bind(quote.total)
+    .bind(quote.currency)
+    .bind(serde_json::to_string(&quote.items).map_err(anyhow::Error::from)?)
+    .bind(expires_at)
+    .execute(pool)
+    .await?;
+
+    Ok(Json(QuoteResponse {
+        quote_id,
+        expires_at,
+        quote,
+    }))
+}
+
+/// `POST /api/v1/subscription/purchase` 以鎖定的報價建立訂單
+///
+/// 付款金額以報價當下為準，之後等級或優惠碼變動不影響；報價逾時需重新報價。
+/// 回傳的結帳表單送出後，付款結果由綠界通知 `payment_notify` 開通訂閱。
+pub async fn purchase(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<PurchaseRequest>,
+) -> AppResult<Json<PurchaseResponse>> {
+    let payment_method = payload.payment_method.trim();
+    if !PAYMENT_METHODS.contains(&payment_method) {
+        return Err(AppError::Validation(format!(
+            "Unsupported payment method {}",
+            payment_method
+        )));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let quoted: Option<QuotedPayment> = sqlx::query_as(
+        r#"
+        SELECT tier, billing_cycle, status, amount, currency, CAST(line_items AS CHAR) AS line_items, promo_code,
+               quote_expires_at
+        FROM payments
+        WHERE id = ? AND user_id = ?
+        FOR UPDATE
+        "#,
+    )
+    .bind(&payload.quote_id)
+    .bind(&user.user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let quoted = quoted.ok_or_else(|| AppError::NotFound("Quote not found".to_string()))?;
+    let period = match (payload.auto_renew, BillingCycle::parse(&quoted.billing_cycle)) {
+        (false, _) => None,
+        (true, Some(BillingCycle::Monthly)) => Some(Period::Monthly),
+        (true, Some(BillingCycle::Yearly)) => Some(Period::Yearly),
+        (true, _) => {
+            return Err(AppError::Validation(
+                "Only monthly or yearly plans can renew automatically".to_string(),
+            ))
+        }
+    };
+    if period.is_some() && !RECURRING_PAYMENT_METHODS.contains(&payment_method) {
+        return Err(AppError::Validation(
+            "Automatic renewal requires a credit card".to_string(),
+        ));
+    }
+
+    if quoted.status != "quoted" {
+        return Err(AppError::Conflict("Quote has already been used".to_string()));
+    }
+    if quoted.quote_expires_at <= Utc::now() {
+        sqlx::query("UPDATE payments SET status = 'expired' WHERE id = ?")
+            .bind(&payload.quote_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        return Err(AppError::Conflict("Quote has expired, request a new quote".to_string()));
+    }
+    if quoted.tier == Tier::Evaluation.as_str() {
+        ensure_evaluation_available(&mut tx, &user.user_id).await?;
+    }
+
+    if let Some(code) = &quoted.promo_code {
+        let redeemed = sqlx::query(
+            r#"
+            UPDATE promotions SET redemptions = redemptions + 1
+            WHERE code = ? AND (max_redemptions IS NULL OR redemptions < max_redemptions)
+            "#,
+        )
+        .bind(code)
+        .execute(&mut *tx)
+        .await?;
+        if redeemed.rows_affected() == 0 {
+            return Err(AppError::Conflict("Promo code is no longer available".to_string()));
+        }
+    }
+
+    let merchant_trade_no = payment::new_merchant_trade_no();
+    sqlx::query(
+        r#"
+        UPDATE payments SET status = 'pending', payment_method = ?, merchant_trade_no = ?, auto_renew = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(payment_method)
+    .bind(&merchant_trade_no)
+    .bind(period.is_some())
+    .bind(&payload.quote_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let items: Vec<LineItem> = serde_json::from_str(&quoted.line_items).map_err(anyhow::Error::from)?;
+    let amount = quoted.amount.max(0) as u32;
+    // 綠界的商品名稱使用方案項目的說明 (例如「入門版 × 12 個月」)
+    let item_name = items
+        .iter()
+        .find(|item| item.kind == "plan")
+        .map(|item| item.description.clone())
+        .unwrap_or_else(|| "訂閱方案".to_string());
+    let payment_form = state.payments.checkout_form(&CheckoutOrder {
+        merchant_trade_no,
+        trade_date: Utc::now(),
+        amount,
+        description: format!("{} 訂閱", state.config.app_name),
+        item_name,
+        payment_method: payment_method.to_string(),
+        period,
+    });
+
+    Ok(Json(PurchaseResponse {
+        order_id: payload.quote_id,
+        status: "pending",
+        amount,
+        currency: quoted.currency,
+        items,
+        payment_url: payment_form.action.clone(),
+        payment_form,
+    }))
+}
+
+/// 評估版每個帳號與設備只能購買一次，且已有付費方案時不能購買 (開通時會取代目前的訂閱)
+async fn ensure_evaluation_available(conn: &mut MySqlConnection, user_id: &str) -> AppResult<()> {
+    if evaluation_purchased(&mut *conn, user_id).await? {
+        return Err(AppError::Conflict(
+            "The evaluation plan can only be purchased once per account and device".to_string(),
+        ));
+    }
+    let current: Vec<String> = sqlx::query_scalar(&format!(
+        "SELECT tier FROM subscriptions WHERE user_id = ? AND {}",
+        CURRENT_SUBSCRIPTION
+    ))
+    .bind(user_id)
+    .bind(Utc::now())
+    .fetch_all(&mut *conn)
+    .await?;
+    if current.iter().any(|tier| Tier::parse(tier).is_some_and(|tier| !tier.is_trial())) {
+        return Err(AppError::Conflict(
+            "The evaluation plan is not available while a paid plan is active".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 查詢目前可用的優惠碼 (不分大小寫)
+async fn find_promotion(pool: &MySqlPool, code: &str) -> AppResult<Promotion> {
+    let now = Utc::now();
+    let row: Option<(String, i32, i32)> = sqlx::query_as(
+        r#"
+        SELECT code, percent_off, amount_off FROM promotions
+        WHERE code = ? AND is_active = 1 AND starts_at <= ? AND (ends_at IS NULL OR ends_at > ?)
+          AND (max_redemptions IS NULL OR redemptions < max_redemptions)
+        "#,
+    )
+    .bind(code.to_uppercase())
+    .bind(now)
+    .bind(now)
+    .fetch_optional(pool)
+    .await?;
+
+    let (code, percent_off, amount_off) =
+        row.ok_or_else(|| AppError::Validation("Invalid or expired promo code".to_string()))?;
+    Ok(Promotion {
+        code,
+        percent_off: percent_off.max(0) as u32,
+        amount_off: amount_off.max(0) as u32,
+    })
+}
@@ -0,0 +1,316 @@
+// src/subscription/entitlement.rs
+
+use super::tier::{PartnerScope, RoleScope, Tier};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// 練習角色 (用戶扮演的角色與對話角色共用)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Role {
+    #[serde(rename = "Developer")]
+    Developer,
+    #[serde(rename = "SA")]
+    Sa,
+    #[serde(rename = "PM")]
+    Pm,
+    #[serde(rename = "QA")]
+    Qa,
+    #[serde(rename = "Tech Lead")]
+    TechLead,
+    #[serde(rename = "CTO")]
+    Cto,
+}
+
+impl Role {
+    pub const ALL: [Role; 6] = [Role::Developer, Role::Sa, Role::Pm, Role::Qa, Role::TechLead, Role::Cto];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Developer => "Developer",
+            Role::Sa => "SA",
+            Role::Pm => "PM",
+            Role::Qa => "QA",
+            Role::TechLead => "Tech Lead",
+            Role::Cto => "CTO",
+        }
+    }
+
+    /// 接受顯示名稱與常見縮寫 (不分大小寫)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().replace(['_', '-'], " ").as_str() {
+            "developer" | "dev" => Some(Role::Developer),
+            "sa" => Some(Role::Sa),
+            "pm" => Some(Role::Pm),
+            "qa" => Some(Role::Qa),
+            "tech lead" | "tl" => Some(Role::TechLead),
+            "cto" => Some(Role::Cto),
+            _ => None,
+        }
+    }
+}
+
+/// 入門版各角色固定的對話角色 (PRICING.md「情境分配」)
+pub fn basic_partners(role: Role) -> [Role; 3] {
+    match role {
+        Role::Developer => [Role::Sa, Role::Pm, Role::Qa],
+        Role::Sa => [Role::Developer, Role::Pm, Role::TechLead],
+        Role::Pm => [Role::Developer, Role::Sa, Role::Qa],
+        Role::Qa => [Role::Developer, Role::Pm, Role::TechLead],
+        Role::TechLead => [Role::Sa, Role::Pm, Role::Cto],
+        Role::Cto => [Role::Developer, Role::Sa, Role::Pm],
+    }
+}
+
+/// 用戶的角色選擇
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selections {
+    /// 免費版 / 評估版選擇的角色；尚未選擇時為 None
+    pub primary_role: Option<Role>,
+    /// 進階版每個角色自選的對話角色；未選擇的角色沿用入門版的組合
+    pub partners: HashMap<Role, Vec<Role>>,
+}
+
+impl Selections {
+    /// 進階版某個角色目前的對話角色
+    pub fn partners_for(&self, role: Role) -> Vec<Role> {
+        match self.partners.get(&role) {
+            Some(partners) if !partners.is_empty() => partners.clone(),
+            _ => basic_partners(role).to_vec(),
+        }
+    }
+}
+
+/// 情境在方案矩陣中的位置
+///
+/// `role_rank` 為該情境在用戶角色所有情境中的順序，`combination_rank` 為在同一組
+/// 角色 × 對話角色中的順序 (皆從 1 開始，依難度與代碼排序)。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScenarioSlot {
+    pub learner_role: Role,
+    pub partner_role: Role,
+    pub tier_required: Tier,
+    pub role_rank: u32,
+    pub combination_rank: u32,
+}
+
+/// 方案 + 角色選擇決定的可練習範圍
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entitlement {
+    pub tier: Tier,
+    pub selections: Selections,
+}
+
+impl Entitlement {
+    pub fn new(tier: Tier, selections: Selections) -> Self {
+        Self { tier, selections }
+    }
+
+    /// 情境是否已解鎖
+    pub fn unlocks(&self, slot: &ScenarioSlot) -> bool {
+        if !tier_covers(self.tier, slot) {
+            return false;
+        }
+
+        let spec = self.tier.spec();
+        if let RoleScope::One { .. } = spec.roles {
+            if self
+                .selections
+                .primary_role
+                .is_some_and(|role| role != slot.learner_role)
+            {
+                return false;
+            }
+        }
+        if let PartnerScope::Chosen { .. } = spec.partners {
+            return self
+                .selections
+                .partners_for(slot.learner_role)
+                .contains(&slot.partner_role);
+        }
+        true
+    }
+
+    /// 可解鎖這個情境的最便宜方案 (高於目前方案，且不含免費版)
+    ///
+    /// 需要選擇角色的方案假設用戶會選擇這個情境的角色組合。
+    pub fn cheapest_unlocking_tier(&self, slot: &ScenarioSlot) -> Option<Tier> {
+        Tier::ALL
+            .into_iter()
+            .filter(|tier| *tier > self.tier && *tier != Tier::Free)
+            .find(|tier| tier_covers(*tier, slot))
+    }
+}
+
+/// 情境未解鎖的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lock {
+    /// 免費版 / 評估版試用期已結束，需升級到這個方案
+    TrialEnded(Tier),
+    /// 目前方案未涵蓋；None 表示沒有方案可以解鎖
+    Tier(Option<Tier>),
+}
+
+impl Lock {
+    /// 解鎖需要的方案
+    pub fn required_tier(&self) -> Option<Tier> {
+        match self {
+            Lock::TrialEnded(tier) => Some(*tier),
+            Lock::Tier(tier) => *tier,
+        }
+    }
+}
+
+/// 用戶目前的練習範圍 (方案權益 + 試用期)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScenarioAccess {
+    pub entitlement: Entitlement,
+    /// 試用期結束時需要升級的方案
+    pub trial_upgrade: Option<Tier>,
+}
+
+impl ScenarioAccess {
+    /// 情境已解鎖時回傳 None
+    pub fn lock(&self, slot: &ScenarioSlot) -> Option<Lock> {
+        if let Some(upgrade) = self.trial_upgrade {
+            return Some(Lock::TrialEnded(upgrade));
+        }
+        if self.entitlement.unlocks(slot) {
+            None
+        } else {
+            Some(Lock::Tier(self.entitlement.cheapest_unlocking_tier(slot)))
+        }
+    }
+}
+
+/// 不考慮用戶選擇時，方案能否涵蓋這個情境
+fn tier_covers(tier: Tier, slot: &ScenarioSlot) -> bool {
+    if tier < slot.tier_required {
+        return false;
+    }
+
+    let spec = tier.spec();
+    if let Some(limit) = spec.role_limit {
+        if slot.role_rank > limit {
+            return false;
+        }
+    }
+    if let Some(limit) = spec.per_combination {
+        if slot.combination_rank > limit {
+            return false;
+        }
+    }
+    match spec.partners {
+        PartnerScope::Fixed => basic_partners(slot.learner_role).contains(&slot.partner_role),
+        PartnerScope::Chosen { count } => count > 0,
+        PartnerScope::Any | PartnerScope::All => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(learner: Role, partner: Role, role_rank: u32, combination_rank: u32) -> ScenarioSlot {
+        ScenarioSlot {
+            learner_role: learner,
+            partner_role: partner,
+            tier_required: Tier::Free,
+            role_rank,
+            combination_rank,
+        }
+    }
+
+    #[test]
+    fn test_parse_roles() {
+        assert_eq!(Role::parse("Tech Lead"), Some(Role::TechLead));
+        assert_eq!(Role::parse("tech_lead"), Some(Role::TechLead));
+        assert_eq!(Role::parse(" dev "), Some(Role::Developer));
+        assert_eq!(Role::parse("Designer"), None);
+        for role in Role::ALL {
+            assert_eq!(Role::parse(role.as_str()), Some(role));
+        }
+    }
+
+    #[test]
+    fn test_free_tier_limited_to_chosen_role() {
+        let free = Entitlement::new(
+            Tier::Free,
+            Selections {
+                primary_role: Some(Role::Developer),
+                ..Default::default()
+            },
+        );
+        assert!(free.unlocks(&slot(Role::Developer, Role::Cto, 10, 1)));
+        assert!(!free.unlocks(&slot(Role::Developer, Role::Sa, 11, 4)));
+        assert!(!free.unlocks(&slot(Role::Pm, Role::Developer, 1, 1)));
+
+        // 尚未選擇角色時任一角色的前 10 個情境都可開始
+        let undecided = Entitlement::new(Tier::Free, Selections::default());
+        assert!(undecided.unlocks(&slot(Role::Pm, Role::Developer, 1, 1)));
+    }
+
+    #[test]
+    fn test_basic_uses_fixed_partners() {
+        let basic = Entitlement::new(Tier::Basic, Selections::default());
+        assert!(basic.unlocks(&slot(Role::Developer, Role::Qa, 25, 10)));
+        assert!(!basic.unlocks(&slot(Role::Developer, Role::Qa, 26, 11)));
+        assert!(!basic.unlocks(&slot(Role::Developer, Role::Cto, 1, 1)));
+    }
+
+    #[test]
+    fn test_advanced_uses_chosen_partners() {
+        let mut selections = Selections::default();
+        selections
+            .partners
+            .insert(Role::Developer, vec![Role::Cto, Role::TechLead, Role::Qa]);
+        let advanced = Entitlement::new(Tier::Advanced, selections);
+        assert!(advanced.unlocks(&slot(Role::Developer, Role::Cto, 90, 30)));
+        assert!(!advanced.unlocks(&slot(Role::Developer, Role::Sa, 1, 1)));
+        // 沒有自選的角色沿用入門版的組合
+        assert!(advanced.unlocks(&slot(Role::Sa, Role::TechLead, 1, 1)));
+    }
+
+    #[test]
+    fn test_tier_required_is_a_floor() {
+        let mut premium_only = slot(Role::Developer, Role::Sa, 1, 1);
+        premium_only.tier_required = Tier::Premium;
+        let basic = Entitlement::new(Tier::Basic, Selections::default());
+        assert!(!basic.unlocks(&premium_only));
+        assert_eq!(basic.cheapest_unlocking_tier(&premium_only), Some(Tier::Premium));
+    }
+
+    #[test]
+    fn test_cheapest_unlocking_tier() {
+        let free = Entitlement::new(Tier::Free, Selections::default());
+        assert_eq!(
+            free.cheapest_unlocking_tier(&slot(Role::Developer, Role::Sa, 5, 5)),
+            Some(Tier::Evaluation)
+        );
+        assert_eq!(
+            free.cheapest_unlocking_tier(&slot(Role::Developer, Role::Cto, 40, 12)),
+            Some(Tier::Advanced)
+        );
+        assert_eq!(
+            free.cheapest_unlocking_tier(&slot(Role::Developer, Role::Cto, 400, 150)),
+            Some(Tier::Unlimited)
+        );
+        assert_eq!(free.cheapest_unlocking_tier(&slot(Role::Developer, Role::Cto, 900, 301)), None);
+    }
+
+    #[test]
+    fn test_access_lock() {
+        let mut access = ScenarioAccess {
+            entitlement: Entitlement::new(Tier::Basic, Selections::default()),
+            trial_upgrade: None,
+        };
+        assert_eq!(access.lock(&slot(Role::Developer, Role::Qa, 1, 1)), None);
+        let locked = access.lock(&slot(Role::Developer, Role::Cto, 1, 1));
+        assert_eq!(locked, Some(Lock::Tier(Some(Tier::Advanced))));
+
+        // 試用期結束後所有情境都鎖定
+        access.entitlement.tier = Tier::Free;
+        access.trial_upgrade = Some(Tier::Evaluation);
+        let locked = access.lock(&slot(Role::Developer, Role::Qa, 1, 1)).unwrap();
+        assert_eq!(locked.required_tier(), Some(Tier::Evaluation));
+    }
+}
@@ -0,0 +1,163 @@
+// src/subscription/events.rs
+
+use super::tier::Tier;
+use crate::database::redis_key;
+use crate::notification::{Notifier, Template};
+use chrono::{DateTime, FixedOffset, Utc};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+
+/// 事件佇列容量；listener 落後太多時舊事件會被丟棄 (快取仍會在 TTL 後過期)
+const EVENT_CAPACITY: usize = 256;
+
+/// 訂閱狀態快取 (`user:{id}:subscription`) 的存活時間 (Document/DATABASE.md)
+pub const CACHE_TTL_SECS: u64 = 30 * 60;
+
+/// 訂閱狀態變化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionEventKind {
+    /// 付款完成，開通新方案
+    Activated,
+    /// 定期定額扣款成功，延長到期時間
+    Renewed,
+    /// 不會自動續訂的訂閱即將到期
+    ExpiringSoon,
+    /// 自動續訂扣款未完成，進入寬限期
+    PastDue,
+    /// 申請取消 (到期前仍可使用)
+    Cancelled,
+    Expired,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionEvent {
+    pub user_id: String,
+    pub kind: SubscriptionEventKind,
+    pub tier: Tier,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl SubscriptionEvent {
+    /// 需要推播時的範本與參數
+    fn push(&self) -> Option<(Template, HashMap<&'static str, String>)> {
+        let template = match self.kind {
+            SubscriptionEventKind::Renewed => Template::SubscriptionRenewed,
+            SubscriptionEventKind::ExpiringSoon => Template::SubscriptionExpiring,
+            SubscriptionEventKind::Expired => Template::SubscriptionExpired,
+            _ => return None,
+        };
+        let mut params = HashMap::from([("plan", self.tier.spec().name.to_string())]);
+        if let Some(expires_at) = self.expires_at {
+            let taipei = FixedOffset::east_opt(8 * 3600).expect("valid offset");
+            params.insert("date", expires_at.with_timezone(&taipei).format("%Y-%m-%d").to_string());
+        }
+        Some((template, params))
+    }
+}
+
+/// 訂閱事件匯流排，排程器與付款通知發布，listener 負責清除快取與推播
+#[derive(Clone)]
+pub struct SubscriptionEvents {
+    sender: broadcast::Sender<SubscriptionEvent>,
+}
+
+impl Default for SubscriptionEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubscriptionEvents {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+        Self { sender }
+    }
+
+    /// 發布事件 (沒有 listener 時直接丟棄)
+    pub fn publish(&self, event: SubscriptionEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SubscriptionEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// 用戶訂閱狀態快取的 Redis key
+pub fn cache_key(key_prefix: &str, user_id: &str) -> String {
+    redis_key(key_prefix, &format!("user:{}:subscription", user_id))
+}
+
+/// 背景處理訂閱事件：清除 `user:{id}:subscription` 快取並推播通知
+pub fn spawn_event_listener(
+    events: &SubscriptionEvents,
+    redis: ConnectionManager,
+    key_prefix: String,
+    notifier: Notifier,
+) {
+    let mut receiver = events.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("subscription event listener skipped {} events", skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let mut redis = redis.clone();
+            let key = cache_key(&key_prefix, &event.user_id);
+            if let Err(err) = redis.del::<_, ()>(&key).await {
+                log::warn!("failed to invalidate {}: {}", key, err);
+            }
+            if let Some((template, params)) = event.push() {
+                if let Err(err) = notifier.notify_user(&event.user_id, template, &params).await {
+                    log::warn!("failed to notify {} of {:?}: {:#}", event.user_id, event.kind, err);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(kind: SubscriptionEventKind) -> SubscriptionEvent {
+        SubscriptionEvent {
+            user_id: "u1".to_string(),
+            kind,
+            tier: Tier::Basic,
+            expires_at: Some(Utc.with_ymd_and_hms(2024, 3, 31, 20, 0, 0).unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_push_uses_taipei_date() {
+        let (template, params) = event(SubscriptionEventKind::Renewed).push().unwrap();
+        assert_eq!(template, Template::SubscriptionRenewed);
+        assert_eq!(params["plan"], "入門版");
+        assert_eq!(params["date"], "2024-04-01");
+        assert!(event(SubscriptionEventKind::PastDue).push().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_publish_reaches_subscribers() {
+        let events = SubscriptionEvents::new();
+        events.publish(event(SubscriptionEventKind::Expired));
+
+        let mut receiver = events.subscribe();
+        events.publish(event(SubscriptionEventKind::Cancelled));
+        assert_eq!(receiver.recv().await.unwrap().kind, SubscriptionEventKind::Cancelled);
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(cache_key("nice_speak:test:", "u1"), "nice_speak:test:user:u1:subscription");
+    }
+}
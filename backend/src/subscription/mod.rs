@@ -0,0 +1,563 @@
+// src/subscription/mod.rs
+
+mod checkout;
+mod entitlement;
+mod events;
+mod notice_store;
+mod notify;
+mod pricing;
+mod scheduler;
+mod tier;
+mod trial_period;
+
+pub use checkout::{plans, purchase, quote, PAYMENT_METHODS, QUOTE_LOCK_MINUTES};
+pub use entitlement::{basic_partners, Entitlement, Lock, Role, ScenarioAccess, ScenarioSlot, Selections};
+pub use events::{cache_key, spawn_event_listener, SubscriptionEvent, SubscriptionEventKind, SubscriptionEvents};
+pub use notify::{payment_notify, period_notify};
+pub use pricing::{BillingCycle, LineItem, Promotion, Quote, YEARLY_DISCOUNT_PERCENTAGE};
+pub use scheduler::{run_lifecycle, spawn_lifecycle_scheduler, LifecycleReport};
+pub use tier::{PartnerScope, RoleScope, Tier, TierSpec};
+pub use trial_period::{load_trial_parameters, TrialParameters};
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::level;
+use crate::state::AppState;
+use axum::{extract::State, Json};
+use redis::AsyncCommands;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+/// 目前有效的訂閱條件 (寬限期內的 past_due 仍保留權益)，需綁定現在時間
+pub(crate) const CURRENT_SUBSCRIPTION: &str = "status IN ('active', 'past_due') \
+    AND (COALESCE(grace_until, expires_at) IS NULL OR COALESCE(grace_until, expires_at) > ?)";
+
+/// 情境在方案矩陣中的順序 (role_2 為空時視為與 role_1 同角色的對話)
+pub(crate) const RANKED_SCENARIOS: &str = r#"
+    SELECT id, role_1, role_2, tier_required, role_rank, combination_rank
+    FROM (
+        SELECT id, role_1, role_2, tier_required,
+               ROW_NUMBER() OVER (
+                   PARTITION BY COALESCE(role_2, role_1) ORDER BY difficulty, code
+               ) AS role_rank,
+               ROW_NUMBER() OVER (
+                   PARTITION BY COALESCE(role_2, role_1), role_1 ORDER BY difficulty, code
+               ) AS combination_rank
+        FROM scenarios
+        WHERE is_active = 1
+    ) ranked
+"#;
+
+#[derive(sqlx::FromRow)]
+struct ScenarioSlotRow {
+    id: String,
+    role_1: String,
+    role_2: Option<String>,
+    tier_required: String,
+    role_rank: u64,
+    combination_rank: u64,
+}
+
+impl ScenarioSlotRow {
+    /// 角色或方案無法辨識時回傳 None
+    fn to_slot(&self) -> Option<ScenarioSlot> {
+        scenario_slot(
+            &self.role_1,
+            self.role_2.as_deref(),
+            &self.tier_required,
+            self.role_rank,
+            self.combination_rank,
+        )
+    }
+}
+
+/// 由 `RANKED_SCENARIOS` 的欄位建立情境位置，角色或方案無法辨識時回傳 None
+pub(crate) fn scenario_slot(
+    role_1: &str,
+    role_2: Option<&str>,
+    tier_required: &str,
+    role_rank: u64,
+    combination_rank: u64,
+) -> Option<ScenarioSlot> {
+    let partner_role = Role::parse(role_1)?;
+    let learner_role = match role_2 {
+        Some(role) => Role::parse(role)?,
+        None => partner_role,
+    };
+    Some(ScenarioSlot {
+        learner_role,
+        partner_role,
+        tier_required: Tier::parse(tier_required)?,
+        role_rank: role_rank as u32,
+        combination_rank: combination_rank as u32,
+    })
+}
+
+#[derive(sqlx::FromRow)]
+struct SubscriptionRow {
+    id: String,
+    tier: String,
+    status: String,
+    started_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    auto_renew: bool,
+    cancel_at_period_end: bool,
+    grace_until: Option<DateTime<Utc>>,
+    period_trade_no: Option<String>,
+}
+
+impl SubscriptionRow {
+    fn tier(&self) -> Tier {
+        Tier::parse(&self.tier).unwrap_or(Tier::Free)
+    }
+
+    fn info(&self) -> SubscriptionInfo {
+        SubscriptionInfo {
+            tier: self.tier(),
+            status: self.status.clone(),
+            started_at: Some(self.started_at),
+            expires_at: self.expires_at,
+            auto_renew: self.auto_renew,
+            cancel_at_period_end: self.cancel_at_period_end,
+            grace_until: self.grace_until,
+        }
+    }
+}
+
+/// 訂閱狀態 (快取於 `user:{id}:subscription`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionInfo {
+    tier: Tier,
+    status: String,
+    started_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    auto_renew: bool,
+    cancel_at_period_end: bool,
+    /// past_due 時保留權益到這個時間
+    grace_until: Option<DateTime<Utc>>,
+}
+
+impl SubscriptionInfo {
+    /// 沒有付費訂閱時的免費版，試用期結束後為 expired
+    fn free(trial_ends_at: DateTime<Utc>, now: DateTime<Utc>) -> Self {
+        Self {
+            tier: Tier::Free,
+            status: if trial_ends_at > now { "active" } else { "expired" }.to_string(),
+            started_at: None,
+            expires_at: Some(trial_ends_at),
+            auto_renew: false,
+            cancel_at_period_end: false,
+            grace_until: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Benefits {
+    scenarios_available: u32,
+    scenarios_used: i64,
+    discount_available: bool,
+    discount_percentage: u32,
+}
+
+#[derive(Serialize)]
+pub struct SubscriptionStatusResponse {
+    subscription: SubscriptionInfo,
+    benefits: Benefits,
+}
+
+#[derive(Deserialize)]
+pub struct RoleSelectionRequest {
+    role: String,
+    /// 進階版自選的對話角色；省略時不變更
+    partners: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+pub struct RoleSelectionResponse {
+    role: Role,
+    partners: Vec<Role>,
+}
+
+/// 取得用戶的方案與角色選擇
+pub async fn load_entitlement(pool: &MySqlPool, user_id: &str) -> AppResult<Entitlement> {
+    let tier = load_subscription(pool, user_id)
+        .await?
+        .map_or(Tier::Free, |row| row.tier());
+    Ok(Entitlement::new(tier, load_selections(pool, user_id).await?))
+}
+
+/// 取得用戶的方案權益與試用期狀態，用來標示情境是否解鎖
+pub async fn load_scenario_access(pool: &MySqlPool, user_id: &str) -> AppResult<ScenarioAccess> {
+    let subscription = load_subscription(pool, user_id).await?;
+    let tier = subscription.as_ref().map_or(Tier::Free, SubscriptionRow::tier);
+    let mut trial_upgrade = None;
+    if let Some(ends_at) = trial_ends_at(pool, user_id, subscription.as_ref()).await? {
+        if ends_at <= Utc::now() {
+            let mut conn = pool.acquire().await?;
+            let purchased = trial_period::evaluation_purchased(&mut conn, user_id).await?;
+            trial_upgrade = Some(trial_period::upgrade_after_trial(tier, purchased));
+        }
+    }
+    Ok(ScenarioAccess {
+        entitlement: Entitlement::new(tier, load_selections(pool, user_id).await?),
+        trial_upgrade,
+    })
+}
+
+/// 確認用戶可以練習這個情境，否則回傳 SUBSCRIPTION_REQUIRED 與可解鎖的最便宜方案
+///
+/// 免費版 / 評估版的試用期結束後一律拒絕；尚未選擇角色時，以第一次練習的情境角色作為選擇。
+pub async fn ensure_scenario_access(pool: &MySqlPool, user_id: &str, scenario_id: &str) -> AppResult<()> {
+    let row: Option<ScenarioSlotRow> = sqlx::query_as(&format!("{} WHERE id = ?", RANKED_SCENARIOS))
+        .bind(scenario_id)
+        .fetch_optional(pool)
+        .await?;
+    let row = row.ok_or_else(|| AppError::NotFound("Scenario not found".to_string()))?;
+    let slot = row.to_slot().ok_or_else(|| {
+        anyhow::anyhow!("scenario {} has an unknown role or tier", row.id)
+    })?;
+
+    let access = load_scenario_access(pool, user_id).await?;
+    let entitlement = &access.entitlement;
+    if let Some(lock) = access.lock(&slot) {
+        let message = match lock {
+            Lock::TrialEnded(upgrade) => format!(
+                "The {} period has ended, upgrade to the {} plan to keep practicing",
+                entitlement.tier, upgrade
+            ),
+            Lock::Tier(Some(tier)) => format!("Upgrade to the {} plan to unlock this scenario", tier),
+            Lock::Tier(None) => "This scenario is not available on any plan".to_string(),
+        };
+        return Err(AppError::SubscriptionRequired {
+            message,
+            required_tier: lock.required_tier().map(|tier| tier.as_str().to_string()),
+        });
+    }
+
+    if let RoleScope::One { .. } = entitlement.tier.spec().roles {
+        if entitlement.selections.primary_role.is_none() {
+            sqlx::query("UPDATE users SET practice_role = ? WHERE id = ? AND practice_role IS NULL")
+                .bind(slot.learner_role.as_str())
+                .bind(user_id)
+                .execute(pool)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// `GET /api/v1/subscription/status` 目前方案與可用情境數
+pub async fn status(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> AppResult<Json<SubscriptionStatusResponse>> {
+    let pool = &state.pool;
+    let subscription = load_subscription_info(&state, &user.user_id).await?;
+    let entitlement = load_entitlement(pool, &user.user_id).await?;
+
+    let rows: Vec<ScenarioSlotRow> = sqlx::query_as(RANKED_SCENARIOS).fetch_all(pool).await?;
+    let unlocked = rows
+        .iter()
+        .filter_map(ScenarioSlotRow::to_slot)
+        .filter(|slot| entitlement.unlocks(slot))
+        .count() as u32;
+    let spec = entitlement.tier.spec();
+    let scenarios_available = unlocked.min(spec.total_scenarios());
+
+    let period_start = subscription.started_at;
+    let scenarios_used: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(DISTINCT scenario_id) FROM practice_records
+        WHERE user_id = ? AND status = 'completed' AND started_at >= ?
+        "#,
+    )
+    .bind(&user.user_id)
+    .bind(period_start.unwrap_or(DateTime::<Utc>::UNIX_EPOCH))
+    .fetch_one(pool)
+    .await?;
+
+    let discount = level::discount_percentage(&level::load_level(pool, &user.user_id).await?);
+
+    Ok(Json(SubscriptionStatusResponse {
+        subscription,
+        benefits: Benefits {
+            scenarios_available,
+            scenarios_used,
+            discount_available: discount > 0,
+            discount_percentage: discount,
+        },
+    }))
+}
+
+/// `PUT /api/v1/subscription/roles` 選擇練習角色與進階版的對話角色
+pub async fn update_roles(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(payload): Json<RoleSelectionRequest>,
+) -> AppResult<Json<RoleSelectionResponse>> {
+    let role = Role::parse(&payload.role)
+        .ok_or_else(|| AppError::Validation(format!("Unknown role {}", payload.role)))?;
+    let partners = match &payload.partners {
+        Some(names) => Some(parse_partners(names)?),
+        None => None,
+    };
+
+    let pool = &state.pool;
+    let entitlement = load_entitlement(pool, &user.user_id).await?;
+    if let RoleScope::One { switchable: false } = entitlement.tier.spec().roles {
+        if entitlement.selections.primary_role.is_some_and(|current| current != role) {
+            return Err(AppError::SubscriptionRequired {
+                message: "Upgrade to the evaluation plan to switch roles".to_string(),
+                required_tier: Some(Tier::Evaluation.as_str().to_string()),
+            });
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE users SET practice_role = ? WHERE id = ?")
+        .bind(role.as_str())
+        .bind(&user.user_id)
+        .execute(&mut *tx)
+        .await?;
+    if let Some(partners) = &partners {
+        sqlx::query("DELETE FROM user_dialogue_partners WHERE user_id = ? AND learner_role = ?")
+            .bind(&user.user_id)
+            .bind(role.as_str())
+            .execute(&mut *tx)
+            .await?;
+        for partner in partners {
+            sqlx::query(
+                "INSERT INTO user_dialogue_partners (id, user_id, learner_role, partner_role) VALUES (?, ?, ?, ?)",
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&user.user_id)
+            .bind(role.as_str())
+            .bind(partner.as_str())
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+    tx.commit().await?;
+
+    let selections = load_selections(pool, &user.user_id).await?;
+    Ok(Json(RoleSelectionResponse {
+        role,
+        partners: selections.partners_for(role),
+    }))
+}
+
+/// `POST /api/v1/subscription/cancel` 取消自動續訂，到期前仍可使用 (寬限期內取消則立即結束)
+pub async fn cancel(State(state): State<AppState>, user: AuthUser) -> AppResult<Json<SubscriptionInfo>> {
+    let row = load_subscription(&state.pool, &user.user_id)
+        .await?
+        .filter(|row| row.tier != Tier::Free.as_str())
+        .ok_or_else(|| AppError::NotFound("No active subscription".to_string()))?;
+    if row.cancel_at_period_end {
+        return Ok(Json(row.info()));
+    }
+
+    // 先停止綠界的定期定額，失敗時不改變狀態讓用戶重試
+    if let Some(trade_no) = &row.period_trade_no {
+        state.payments.cancel_period(trade_no).await?;
+    }
+    let status = if row.status == "past_due" { "cancelled" } else { "active" };
+    sqlx::query(
+        r#"
+        UPDATE subscriptions
+        SET status = ?, auto_renew = 0, cancel_at_period_end = 1, cancelled_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(status)
+    .bind(Utc::now())
+    .bind(&row.id)
+    .execute(&state.pool)
+    .await?;
+
+    let info = SubscriptionInfo {
+        status: status.to_string(),
+        auto_renew: false,
+        cancel_at_period_end: true,
+        ..row.info()
+    };
+    state.subscription_events.publish(SubscriptionEvent {
+        user_id: user.user_id,
+        kind: SubscriptionEventKind::Cancelled,
+        tier: info.tier,
+        expires_at: info.expires_at,
+    });
+    Ok(Json(info))
+}
+
+/// 解析自選的對話角色 (去除重複，數量不超過進階版上限)
+fn parse_partners(names: &[String]) -> AppResult<Vec<Role>> {
+    let PartnerScope::Chosen { count } = Tier::Advanced.spec().partners else {
+        unreachable!("advanced plan lets learners choose partners");
+    };
+
+    let mut partners = Vec::new();
+    for name in names {
+        let partner = Role::parse(name)
+            .ok_or_else(|| AppError::Validation(format!("Unknown role {}", name)))?;
+        if !partners.contains(&partner) {
+            partners.push(partner);
+        }
+    }
+    if partners.is_empty() || partners.len() > count {
+        return Err(AppError::Validation(format!(
+            "Choose between 1 and {} dialogue partners",
+            count
+        )));
+    }
+    Ok(partners)
+}
+
+async fn load_subscription(pool: &MySqlPool, user_id: &str) -> AppResult<Option<SubscriptionRow>> {
+    let row = sqlx::query_as(&format!(
+        r#"
+        SELECT id, tier, status, started_at, expires_at, auto_renew, cancel_at_period_end, grace_until,
+               period_trade_no
+        FROM subscriptions
+        WHERE user_id = ? AND {}
+        ORDER BY started_at DESC
+        LIMIT 1
+        "#,
+        CURRENT_SUBSCRIPTION
+    ))
+    .bind(user_id)
+    .bind(Utc::now())
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+/// 讀取訂閱狀態，優先使用 Redis 快取 (狀態變化時由事件清除)
+pub(crate) async fn load_subscription_info(state: &AppState, user_id: &str) -> AppResult<SubscriptionInfo> {
+    let key = cache_key(&state.config.redis.key_prefix, user_id);
+    let mut redis = state.redis.clone();
+    match redis.get::<_, Option<String>>(&key).await {
+        Ok(Some(cached)) => match serde_json::from_str(&cached) {
+            Ok(info) => return Ok(info),
+            Err(err) => log::warn!("ignoring malformed {}: {}", key, err),
+        },
+        Ok(None) => {}
+        Err(err) => log::warn!("failed to read {}: {}", key, err),
+    }
+
+    let now = Utc::now();
+    let info = match load_subscription(&state.pool, user_id).await? {
+        Some(row) => row.info(),
+        None => match trial_ends_at(&state.pool, user_id, None).await? {
+            Some(ends_at) => SubscriptionInfo::free(ends_at, now),
+            None => return Err(AppError::NotFound("User not found".to_string())),
+        },
+    };
+    // 免費版試用期結束沒有事件清除快取，快取不超過到期時間
+    let ttl = match info.expires_at {
+        Some(expires_at) if expires_at > now => {
+            (expires_at - now).num_seconds().clamp(1, events::CACHE_TTL_SECS as i64) as u64
+        }
+        _ => events::CACHE_TTL_SECS,
+    };
+    let cached = serde_json::to_string(&info).map_err(anyhow::Error::from)?;
+    if let Err(err) = redis.set_ex::<_, _, ()>(&key, cached, ttl).await {
+        log::warn!("failed to cache {}: {}", key, err);
+    }
+    Ok(info)
+}
+
+/// 免費版 / 評估版的試用結束時間 (免費版從註冊、評估版從購買開始計算)；月付方案為 None
+///
+/// 免費版的用戶不存在時也回傳 None。
+async fn trial_ends_at(
+    pool: &MySqlPool,
+    user_id: &str,
+    subscription: Option<&SubscriptionRow>,
+) -> AppResult<Option<DateTime<Utc>>> {
+    let (tier, started_at) = match subscription {
+        Some(row) if row.tier() != Tier::Free => (row.tier(), row.started_at),
+        _ => {
+            let registered_at: Option<DateTime<Utc>> =
+                sqlx::query_scalar("SELECT created_at FROM users WHERE id = ?")
+                    .bind(user_id)
+                    .fetch_optional(pool)
+                    .await?;
+            match registered_at {
+                Some(registered_at) => (Tier::Free, registered_at),
+                None => return Ok(None),
+            }
+        }
+    };
+    if tier.spec().duration_days.is_none() {
+        return Ok(None);
+    }
+    let parameters = load_trial_parameters(pool).await?;
+    Ok(parameters.ends_at(tier, started_at))
+}
+
+async fn load_selections(pool: &MySqlPool, user_id: &str) -> AppResult<Selections> {
+    let primary: Option<Option<String>> =
+        sqlx::query_scalar("SELECT practice_role FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT learner_role, partner_role FROM user_dialogue_partners WHERE user_id = ? ORDER BY created_at",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut selections = Selections {
+        primary_role: primary.flatten().as_deref().and_then(Role::parse),
+        ..Default::default()
+    };
+    for (learner, partner) in rows {
+        if let (Some(learner), Some(partner)) = (Role::parse(&learner), Role::parse(&partner)) {
+            selections.partners.entry(learner).or_default().push(partner);
+        }
+    }
+    Ok(selections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_from_row() {
+        let row = ScenarioSlotRow {
+            id: "s1".to_string(),
+            role_1: "Tech Lead".to_string(),
+            role_2: Some("Developer".to_string()),
+            tier_required: "basic".to_string(),
+            role_rank: 12,
+            combination_rank: 3,
+        };
+        let slot = row.to_slot().unwrap();
+        assert_eq!(slot.learner_role, Role::Developer);
+        assert_eq!(slot.partner_role, Role::TechLead);
+        assert_eq!(slot.tier_required, Tier::Basic);
+
+        let solo = ScenarioSlotRow {
+            role_2: None,
+            tier_required: "gold".to_string(),
+            ..row
+        };
+        assert!(solo.to_slot().is_none());
+    }
+
+    #[test]
+    fn test_parse_partners() {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_partners(&names(&["CTO", "cto", "TL"])).unwrap(),
+            vec![Role::Cto, Role::TechLead]
+        );
+        assert!(parse_partners(&names(&["SA", "PM", "QA", "CTO"])).is_err());
+        assert!(parse_partners(&names(&[])).is_err());
+        assert!(parse_partners(&names(&["Designer"])).is_err());
+    }
+}
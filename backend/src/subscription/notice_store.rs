@@ -0,0 +1,356 @@
+// src/subscription/notice_store.rs
+
+use super::pricing::BillingCycle;
+use super::tier::Tier;
+use super::trial_period::{evaluation_purchased, load_trial_parameters, TrialParameters};
+use super::CURRENT_SUBSCRIPTION;
+use crate::error::AppResult;
+use crate::payment::PeriodNotice;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, MySqlPool, Transaction};
+
+/// 等待付款結果的訂單
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PendingPayment {
+    pub id: String,
+    pub user_id: String,
+    pub tier: String,
+    pub billing_cycle: String,
+    pub auto_renew: bool,
+    pub amount: i32,
+    pub status: String,
+}
+
+/// 用戶目前有效的訂閱
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CurrentPlan {
+    pub tier: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub period_trade_no: Option<String>,
+}
+
+/// 定期定額扣款對應的訂閱
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RenewingSubscription {
+    pub id: String,
+    pub user_id: String,
+    pub tier: String,
+    pub billing_cycle: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub cancel_at_period_end: bool,
+}
+
+/// 付款開通的新訂閱
+#[derive(Debug, Clone)]
+pub struct NewSubscription {
+    pub id: String,
+    pub user_id: String,
+    pub tier: Tier,
+    pub billing_cycle: BillingCycle,
+    pub started_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub auto_renew: bool,
+    /// 定期定額的第一次付款交易編號
+    pub period_trade_no: Option<String>,
+}
+
+/// 付款通知的資料存取；正式環境為 MySQL，測試可換成記憶體實作
+#[async_trait]
+pub trait NoticeStore: Send + Sync {
+    async fn trial_parameters(&self) -> AppResult<TrialParameters>;
+
+    /// 開始交易，`commit` 之前的寫入都不會生效
+    async fn begin(&self) -> AppResult<Box<dyn NoticeTx>>;
+}
+
+/// 處理一筆通知的交易
+#[async_trait]
+pub trait NoticeTx: Send {
+    /// 鎖定訂單
+    async fn lock_payment(&mut self, merchant_trade_no: &str) -> AppResult<Option<PendingPayment>>;
+
+    async fn fail_payment(&mut self, payment_id: &str, reason: &str, trade_no: &str) -> AppResult<()>;
+
+    /// 已收款但不能開通，記為 `refund_required` 等待人工退款
+    async fn flag_refund(
+        &mut self,
+        payment_id: &str,
+        reason: &str,
+        trade_no: &str,
+        paid_at: DateTime<Utc>,
+    ) -> AppResult<()>;
+
+    /// 用戶或其綁定的設備是否已購買過評估版
+    async fn evaluation_purchased(&mut self, user_id: &str) -> AppResult<bool>;
+
+    /// 鎖定用戶目前有效的訂閱 (新的在前)
+    async fn lock_current_plans(&mut self, user_id: &str, now: DateTime<Utc>) -> AppResult<Vec<CurrentPlan>>;
+
+    /// 結束用戶仍有效的訂閱並建立新訂閱
+    async fn replace_subscription(&mut self, subscription: &NewSubscription) -> AppResult<()>;
+
+    /// 用戶綁定的設備標記為已購買評估版
+    async fn mark_evaluation_purchased(&mut self, user_id: &str) -> AppResult<()>;
+
+    async fn mark_paid(
+        &mut self,
+        payment_id: &str,
+        subscription_id: &str,
+        trade_no: &str,
+        paid_at: DateTime<Utc>,
+    ) -> AppResult<()>;
+
+    /// 鎖定定期定額對應的訂閱 (最新的一筆)
+    async fn lock_period_subscription(&mut self, merchant_trade_no: &str) -> AppResult<Option<RenewingSubscription>>;
+
+    /// 這一期扣款是否已記錄過
+    async fn period_charge_recorded(&mut self, gwsr: &str, subscription_id: &str) -> AppResult<bool>;
+
+    /// 記錄一期扣款 (沿用第一次付款的報價明細)
+    async fn record_period_charge(
+        &mut self,
+        subscription_id: &str,
+        notice: &PeriodNotice,
+        now: DateTime<Utc>,
+    ) -> AppResult<()>;
+
+    /// 扣款成功後延長訂閱並恢復為 active
+    async fn extend_subscription(&mut self, subscription_id: &str, expires_at: DateTime<Utc>) -> AppResult<()>;
+
+    async fn commit(self: Box<Self>) -> AppResult<()>;
+}
+
+/// MySQL 實作
+pub struct MySqlNoticeStore {
+    pool: MySqlPool,
+}
+
+impl MySqlNoticeStore {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl NoticeStore for MySqlNoticeStore {
+    async fn trial_parameters(&self) -> AppResult<TrialParameters> {
+        load_trial_parameters(&self.pool).await
+    }
+
+    async fn begin(&self) -> AppResult<Box<dyn NoticeTx>> {
+        Ok(Box::new(MySqlNoticeTx {
+            tx: self.pool.begin().await?,
+        }))
+    }
+}
+
+struct MySqlNoticeTx {
+    tx: Transaction<'static, MySql>,
+}
+
+#[async_trait]
+impl NoticeTx for MySqlNoticeTx {
+    async fn lock_payment(&mut self, merchant_trade_no: &str) -> AppResult<Option<PendingPayment>> {
+        let payment = sqlx::query_as(
+            r#"
+            SELECT id, user_id, tier, billing_cycle, auto_renew, amount, status FROM payments
+            WHERE merchant_trade_no = ?
+            FOR UPDATE
+            "#,
+        )
+        .bind(merchant_trade_no)
+        .fetch_optional(&mut *self.tx)
+        .await?;
+        Ok(payment)
+    }
+
+    async fn fail_payment(&mut self, payment_id: &str, reason: &str, trade_no: &str) -> AppResult<()> {
+        sqlx::query("UPDATE payments SET status = 'failed', failure_reason = ?, transaction_id = ? WHERE id = ?")
+            .bind(reason)
+            .bind(trade_no)
+            .bind(payment_id)
+            .execute(&mut *self.tx)
+            .await?;
+        Ok(())
+    }
+
+    async fn flag_refund(
+        &mut self,
+        payment_id: &str,
+        reason: &str,
+        trade_no: &str,
+        paid_at: DateTime<Utc>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE payments SET status = 'refund_required', failure_reason = ?, transaction_id = ?, paid_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(reason)
+        .bind(trade_no)
+        .bind(paid_at)
+        .bind(payment_id)
+        .execute(&mut *self.tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn evaluation_purchased(&mut self, user_id: &str) -> AppResult<bool> {
+        evaluation_purchased(&mut self.tx, user_id).await
+    }
+
+    async fn lock_current_plans(&mut self, user_id: &str, now: DateTime<Utc>) -> AppResult<Vec<CurrentPlan>> {
+        let plans = sqlx::query_as(&format!(
+            r#"
+            SELECT tier, expires_at, period_trade_no FROM subscriptions
+            WHERE user_id = ? AND {}
+            ORDER BY started_at DESC
+            FOR UPDATE
+            "#,
+            CURRENT_SUBSCRIPTION
+        ))
+        .bind(user_id)
+        .bind(now)
+        .fetch_all(&mut *self.tx)
+        .await?;
+        Ok(plans)
+    }
+
+    async fn replace_subscription(&mut self, subscription: &NewSubscription) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE subscriptions SET status = 'expired', auto_renew = 0 WHERE user_id = ? AND status IN ('active', 'past_due')",
+        )
+        .bind(&subscription.user_id)
+        .execute(&mut *self.tx)
+        .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO subscriptions
+                (id, user_id, tier, billing_cycle, status, started_at, expires_at, auto_renew, period_trade_no)
+            VALUES (?, ?, ?, ?, 'active', ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&subscription.id)
+        .bind(&subscription.user_id)
+        .bind(subscription.tier.as_str())
+        .bind(subscription.billing_cycle.as_str())
+        .bind(subscription.started_at)
+        .bind(subscription.expires_at)
+        .bind(subscription.auto_renew)
+        .bind(&subscription.period_trade_no)
+        .execute(&mut *self.tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_evaluation_purchased(&mut self, user_id: &str) -> AppResult<()> {
+        sqlx::query("UPDATE devices SET has_purchased_evaluation = 1 WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *self.tx)
+            .await?;
+        Ok(())
+    }
+
+    async fn mark_paid(
+        &mut self,
+        payment_id: &str,
+        subscription_id: &str,
+        trade_no: &str,
+        paid_at: DateTime<Utc>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE payments
+            SET status = 'paid', subscription_id = ?, transaction_id = ?, paid_at = ?, failure_reason = NULL
+            WHERE id = ?
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(trade_no)
+        .bind(paid_at)
+        .bind(payment_id)
+        .execute(&mut *self.tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn lock_period_subscription(&mut self, merchant_trade_no: &str) -> AppResult<Option<RenewingSubscription>> {
+        let subscription = sqlx::query_as(
+            r#"
+            SELECT id, user_id, tier, billing_cycle, expires_at, cancel_at_period_end FROM subscriptions
+            WHERE period_trade_no = ?
+            ORDER BY started_at DESC
+            LIMIT 1
+            FOR UPDATE
+            "#,
+        )
+        .bind(merchant_trade_no)
+        .fetch_optional(&mut *self.tx)
+        .await?;
+        Ok(subscription)
+    }
+
+    async fn period_charge_recorded(&mut self, gwsr: &str, subscription_id: &str) -> AppResult<bool> {
+        let recorded: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM payments WHERE transaction_id = ? AND subscription_id = ?")
+                .bind(gwsr)
+                .bind(subscription_id)
+                .fetch_one(&mut *self.tx)
+                .await?;
+        Ok(recorded > 0)
+    }
+
+    async fn record_period_charge(
+        &mut self,
+        subscription_id: &str,
+        notice: &PeriodNotice,
+        now: DateTime<Utc>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO payments
+                (id, user_id, subscription_id, tier, billing_cycle, auto_renew, list_price, cycle_discount,
+                 level_discount_percentage, level_discount, promo_code, promo_discount, amount, currency,
+                 line_items, payment_method, transaction_id, status, failure_reason, quote_expires_at, paid_at)
+            SELECT ?, user_id, ?, tier, billing_cycle, 1, list_price, cycle_discount,
+                   level_discount_percentage, level_discount, promo_code, promo_discount, ?, currency,
+                   line_items, payment_method, ?, ?, ?, ?, ?
+            FROM payments WHERE merchant_trade_no = ?
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(subscription_id)
+        .bind(notice.amount)
+        .bind(&notice.gwsr)
+        .bind(if notice.paid { "paid" } else { "failed" })
+        .bind((!notice.paid).then_some(&notice.message))
+        .bind(now)
+        .bind(notice.paid.then(|| notice.processed_at.unwrap_or(now)))
+        .bind(&notice.merchant_trade_no)
+        .execute(&mut *self.tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn extend_subscription(&mut self, subscription_id: &str, expires_at: DateTime<Utc>) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET status = 'active', expires_at = ?, grace_until = NULL, expiry_notified_at = NULL
+            WHERE id = ?
+            "#,
+        )
+        .bind(expires_at)
+        .bind(subscription_id)
+        .execute(&mut *self.tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn commit(self: Box<Self>) -> AppResult<()> {
+        self.tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,912 @@
+// src/subscription/notify.rs
+
+use super::events::{SubscriptionEvent, SubscriptionEventKind, SubscriptionEvents};
+use super::notice_store::{MySqlNoticeStore, NewSubscription, NoticeStore, PendingPayment};
+use super::pricing::BillingCycle;
+use super::tier::Tier;
+use crate::error::{AppError, AppResult};
+use crate::payment::{ecpay, EcPay, PaymentNotice, PeriodNotice};
+use crate::state::AppState;
+use axum::{extract::State, http::StatusCode, Form};
+use chrono::{DateTime, Duration, Months, Utc};
+use std::collections::HashMap;
+
+/// 付款開通的結果：事件與被取代的訂閱仍在進行的定期定額
+struct Activation {
+    event: SubscriptionEvent,
+    replaced_periods: Vec<String>,
+}
+
+/// `POST /api/v1/payments/ecpay/notify` 綠界付款結果通知 (server-to-server)
+///
+/// 回應 `1|OK` 以外的內容綠界會重送，因此只有簽章錯誤或暫時性錯誤才回應失敗。
+pub async fn payment_notify(
+    State(state): State<AppState>,
+    Form(params): Form<HashMap<String, String>>,
+) -> (StatusCode, String) {
+    let store = MySqlNoticeStore::new(state.pool.clone());
+    handle_payment_notice(&state.payments, &store, &state.subscription_events, &params).await
+}
+
+/// `POST /api/v1/payments/ecpay/period-notify` 定期定額每期扣款結果通知
+pub async fn period_notify(
+    State(state): State<AppState>,
+    Form(params): Form<HashMap<String, String>>,
+) -> (StatusCode, String) {
+    let store = MySqlNoticeStore::new(state.pool.clone());
+    handle_period_notice(&state.payments, &store, &state.subscription_events, &params).await
+}
+
+async fn handle_payment_notice(
+    payments: &EcPay,
+    store: &dyn NoticeStore,
+    events: &SubscriptionEvents,
+    params: &HashMap<String, String>,
+) -> (StatusCode, String) {
+    let notice = match payments.verify_notification(params) {
+        Ok(notice) => notice,
+        Err(err) => {
+            log::warn!("rejected payment notification: {:#}", err);
+            return (StatusCode::BAD_REQUEST, format!("0|{}", err));
+        }
+    };
+
+    match apply_notice(store, &notice).await {
+        Ok(activation) => {
+            if let Some(activation) = activation {
+                // 換方案時停止舊方案的定期定額，避免重複扣款
+                for trade_no in &activation.replaced_periods {
+                    if let Err(err) = payments.cancel_period(trade_no).await {
+                        log::error!("failed to cancel replaced ECPay period {}: {:#}", trade_no, err);
+                    }
+                }
+                events.publish(activation.event);
+            }
+            (StatusCode::OK, ecpay::NOTIFY_ACK.to_string())
+        }
+        Err(err) => reject(&notice.merchant_trade_no, err),
+    }
+}
+
+async fn handle_period_notice(
+    payments: &EcPay,
+    store: &dyn NoticeStore,
+    events: &SubscriptionEvents,
+    params: &HashMap<String, String>,
+) -> (StatusCode, String) {
+    let notice = match payments.verify_period_notification(params) {
+        Ok(notice) => notice,
+        Err(err) => {
+            log::warn!("rejected period notification: {:#}", err);
+            return (StatusCode::BAD_REQUEST, format!("0|{}", err));
+        }
+    };
+
+    match apply_period_notice(store, &notice).await {
+        Ok(event) => {
+            if let Some(event) = event {
+                events.publish(event);
+            }
+            (StatusCode::OK, ecpay::NOTIFY_ACK.to_string())
+        }
+        Err(err) => reject(&notice.merchant_trade_no, err),
+    }
+}
+
+fn reject(merchant_trade_no: &str, err: AppError) -> (StatusCode, String) {
+    match err {
+        AppError::NotFound(message) => {
+            log::warn!("payment notification for unknown order {}", merchant_trade_no);
+            (StatusCode::NOT_FOUND, format!("0|{}", message))
+        }
+        err => (StatusCode::INTERNAL_SERVER_ERROR, format!("0|{}", err.public_message())),
+    }
+}
+
+/// 付款通知對訂單的處理方式
+#[derive(Debug, PartialEq, Eq)]
+enum NoticeOutcome {
+    /// 已處理過的訂單 (含待退款)，或失敗訂單又收到失敗通知
+    Ignore,
+    /// 綠界後台的模擬付款，沒有實際收款
+    Simulated,
+    /// 付款失敗或金額不符
+    Failed(String),
+    Activate,
+}
+
+fn classify(payment: &PendingPayment, notice: &PaymentNotice) -> NoticeOutcome {
+    // 已付款的訂單不再變動；失敗後綠界仍可能補送成功的通知
+    if matches!(payment.status.as_str(), "paid" | "refund_required")
+        || (payment.status != "pending" && !notice.paid)
+    {
+        return NoticeOutcome::Ignore;
+    }
+    if notice.simulated {
+        return NoticeOutcome::Simulated;
+    }
+    if !notice.paid {
+        return NoticeOutcome::Failed(notice.message.clone());
+    }
+    if i64::from(notice.amount) != i64::from(payment.amount) {
+        log::error!(
+            "payment {} amount mismatch: expected {}, notified {}",
+            payment.id,
+            payment.amount,
+            notice.amount
+        );
+        return NoticeOutcome::Failed(format!("Amount mismatch: notified {}", notice.amount));
+    }
+    NoticeOutcome::Activate
+}
+
+/// 在同一個交易內更新付款記錄並開通訂閱；重送的通知不會重複開通，模擬付款不開通
+async fn apply_notice(store: &dyn NoticeStore, notice: &PaymentNotice) -> AppResult<Option<Activation>> {
+    let parameters = store.trial_parameters().await?;
+    let mut tx = store.begin().await?;
+    let payment = tx
+        .lock_payment(&notice.merchant_trade_no)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Order not found".to_string()))?;
+
+    match classify(&payment, notice) {
+        NoticeOutcome::Ignore => return Ok(None),
+        NoticeOutcome::Simulated => {
+            log::warn!(
+                "ignoring simulated ECPay payment for {} (payment {} stays {})",
+                notice.merchant_trade_no,
+                payment.id,
+                payment.status
+            );
+            return Ok(None);
+        }
+        NoticeOutcome::Failed(reason) => {
+            tx.fail_payment(&payment.id, &reason, &notice.trade_no).await?;
+            tx.commit().await?;
+            return Ok(None);
+        }
+        NoticeOutcome::Activate => {}
+    }
+
+    let tier = Tier::parse(&payment.tier)
+        .ok_or_else(|| anyhow::anyhow!("payment {} has unknown tier {}", payment.id, payment.tier))?;
+    let cycle = BillingCycle::parse(&payment.billing_cycle).ok_or_else(|| {
+        anyhow::anyhow!("payment {} has unknown billing cycle {}", payment.id, payment.billing_cycle)
+    })?;
+
+    let now = Utc::now();
+    let current = tx.lock_current_plans(&payment.user_id, now).await?;
+    // 報價後才購買評估版或其他付費方案時，收款了也不開通，避免重複購買評估版或取代付費方案
+    if tier == Tier::Evaluation {
+        let conflict = if tx.evaluation_purchased(&payment.user_id).await? {
+            Some("Evaluation plan was already purchased")
+        } else if current
+            .iter()
+            .any(|plan| Tier::parse(&plan.tier).is_some_and(|tier| !tier.is_trial()))
+        {
+            Some("A paid plan is active")
+        } else {
+            None
+        };
+        if let Some(reason) = conflict {
+            log::error!("payment {} needs a refund: {}", payment.id, reason);
+            tx.flag_refund(&payment.id, reason, &notice.trade_no, notice.paid_at.unwrap_or(now))
+                .await?;
+            tx.commit().await?;
+            return Ok(None);
+        }
+    }
+    let renewing_until = current
+        .first()
+        .filter(|plan| plan.tier == payment.tier)
+        .and_then(|plan| plan.expires_at);
+    let expires_at = period_end(parameters.duration_days(tier), cycle, now, renewing_until);
+    let replaced_periods = current.into_iter().filter_map(|plan| plan.period_trade_no).collect();
+
+    let subscription = NewSubscription {
+        id: uuid::Uuid::new_v4().to_string(),
+        user_id: payment.user_id.clone(),
+        tier,
+        billing_cycle: cycle,
+        started_at: now,
+        expires_at,
+        auto_renew: payment.auto_renew,
+        period_trade_no: payment.auto_renew.then(|| notice.merchant_trade_no.clone()),
+    };
+    tx.replace_subscription(&subscription).await?;
+    if tier == Tier::Evaluation {
+        tx.mark_evaluation_purchased(&payment.user_id).await?;
+    }
+    tx.mark_paid(&payment.id, &subscription.id, &notice.trade_no, notice.paid_at.unwrap_or(now))
+        .await?;
+    tx.commit().await?;
+
+    log::info!("payment {} paid, {} subscription active until {}", payment.id, tier, expires_at);
+    Ok(Some(Activation {
+        event: SubscriptionEvent {
+            user_id: payment.user_id,
+            kind: SubscriptionEventKind::Activated,
+            tier,
+            expires_at: Some(expires_at),
+        },
+        replaced_periods,
+    }))
+}
+
+/// 記錄定期定額的一期扣款；成功時延長訂閱 (寬限期內扣款成功也恢復為 active)
+async fn apply_period_notice(store: &dyn NoticeStore, notice: &PeriodNotice) -> AppResult<Option<SubscriptionEvent>> {
+    let mut tx = store.begin().await?;
+    let subscription = tx
+        .lock_period_subscription(&notice.merchant_trade_no)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Subscription not found".to_string()))?;
+    if tx.period_charge_recorded(&notice.gwsr, &subscription.id).await? {
+        return Ok(None);
+    }
+
+    let now = Utc::now();
+    tx.record_period_charge(&subscription.id, notice, now).await?;
+    if !notice.paid {
+        // 扣款失敗時維持原狀態，到期後由排程進入寬限期
+        tx.commit().await?;
+        log::warn!("ECPay period {} charge failed: {}", notice.merchant_trade_no, notice.message);
+        return Ok(None);
+    }
+    if subscription.cancel_at_period_end {
+        log::error!("ECPay period {} charged after cancellation", notice.merchant_trade_no);
+    }
+
+    let tier = Tier::parse(&subscription.tier).ok_or_else(|| {
+        anyhow::anyhow!("subscription {} has unknown tier {}", subscription.id, subscription.tier)
+    })?;
+    let cycle = subscription
+        .billing_cycle
+        .as_deref()
+        .and_then(BillingCycle::parse)
+        .unwrap_or(BillingCycle::Monthly);
+    let expires_at = period_end(tier.spec().duration_days, cycle, now, subscription.expires_at);
+    tx.extend_subscription(&subscription.id, expires_at).await?;
+    tx.commit().await?;
+
+    Ok(Some(SubscriptionEvent {
+        user_id: subscription.user_id,
+        kind: SubscriptionEventKind::Renewed,
+        tier,
+        expires_at: Some(expires_at),
+    }))
+}
+
+/// 訂閱到期時間：單次方案依天數 (`duration_days`)，月付方案依付款週期；續訂同一方案時從原到期時間往後延
+fn period_end(
+    duration_days: Option<i64>,
+    cycle: BillingCycle,
+    now: DateTime<Utc>,
+    renewing_until: Option<DateTime<Utc>>,
+) -> DateTime<Utc> {
+    let start = renewing_until.filter(|until| *until > now).unwrap_or(now);
+    match duration_days {
+        Some(days) => start + Duration::days(days),
+        None => start
+            .checked_add_months(Months::new(cycle.months()))
+            .unwrap_or(start + Duration::days(30 * i64::from(cycle.months()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::notice_store::{CurrentPlan, NoticeTx, RenewingSubscription};
+    use super::super::trial_period::TrialParameters;
+    use super::*;
+    use crate::payment::{CheckoutOrder, FakeEcPay, Period};
+    use async_trait::async_trait;
+    use axum::{routing::post, Router};
+    use chrono::TimeZone;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    const MERCHANT_ID: &str = "3002607";
+    const HASH_KEY: &str = "pwFHCqoQZGmho4w6";
+    const HASH_IV: &str = "EkRm7iFt8PBc7l9v";
+
+    #[test]
+    fn test_period_end() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 31, 12, 0, 0).unwrap();
+        assert_eq!(
+            period_end(None, BillingCycle::Monthly, now, None),
+            Utc.with_ymd_and_hms(2024, 2, 29, 12, 0, 0).unwrap()
+        );
+        assert_eq!(
+            period_end(None, BillingCycle::Yearly, now, None),
+            Utc.with_ymd_and_hms(2025, 1, 31, 12, 0, 0).unwrap()
+        );
+        assert_eq!(
+            period_end(Some(7), BillingCycle::OneTime, now, None),
+            Utc.with_ymd_and_hms(2024, 2, 7, 12, 0, 0).unwrap()
+        );
+    }
+
+    fn pending(status: &str) -> PendingPayment {
+        PendingPayment {
+            id: "payment-1".to_string(),
+            user_id: "user-1".to_string(),
+            tier: "basic".to_string(),
+            billing_cycle: "monthly".to_string(),
+            auto_renew: false,
+            amount: 691,
+            status: status.to_string(),
+        }
+    }
+
+    fn notice(paid: bool, amount: u32) -> PaymentNotice {
+        PaymentNotice {
+            merchant_trade_no: "NS00000000000000TEST".to_string(),
+            trade_no: "2401010000000001".to_string(),
+            amount,
+            paid,
+            message: if paid { "交易成功" } else { "交易失敗" }.to_string(),
+            paid_at: None,
+            simulated: false,
+        }
+    }
+
+    #[test]
+    fn test_classify_notice() {
+        assert_eq!(classify(&pending("pending"), &notice(true, 691)), NoticeOutcome::Activate);
+        assert_eq!(
+            classify(&pending("pending"), &notice(true, 1)),
+            NoticeOutcome::Failed("Amount mismatch: notified 1".to_string())
+        );
+        assert_eq!(
+            classify(&pending("pending"), &notice(false, 691)),
+            NoticeOutcome::Failed("交易失敗".to_string())
+        );
+        // 重送與失敗後補送的成功通知
+        assert_eq!(classify(&pending("paid"), &notice(true, 691)), NoticeOutcome::Ignore);
+        assert_eq!(classify(&pending("failed"), &notice(false, 691)), NoticeOutcome::Ignore);
+        assert_eq!(classify(&pending("failed"), &notice(true, 691)), NoticeOutcome::Activate);
+    }
+
+    #[test]
+    fn test_simulated_payment_is_not_activated() {
+        let simulated = PaymentNotice {
+            simulated: true,
+            ..notice(true, 691)
+        };
+        assert_eq!(classify(&pending("pending"), &simulated), NoticeOutcome::Simulated);
+        assert_eq!(classify(&pending("paid"), &simulated), NoticeOutcome::Ignore);
+    }
+
+    #[test]
+    fn test_renewal_extends_current_period() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let until = Utc.with_ymd_and_hms(2024, 3, 10, 0, 0, 0).unwrap();
+        assert_eq!(
+            period_end(None, BillingCycle::Monthly, now, Some(until)),
+            Utc.with_ymd_and_hms(2024, 4, 10, 0, 0, 0).unwrap()
+        );
+        // 已過期的訂閱從現在開始計算
+        let lapsed = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            period_end(None, BillingCycle::Monthly, now, Some(lapsed)),
+            Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[derive(Debug, Clone)]
+    struct PaymentRow {
+        payment: PendingPayment,
+        merchant_trade_no: Option<String>,
+        subscription_id: Option<String>,
+        transaction_id: Option<String>,
+        failure_reason: Option<String>,
+    }
+
+    #[derive(Debug, Clone)]
+    struct SubscriptionRow {
+        id: String,
+        user_id: String,
+        tier: String,
+        billing_cycle: String,
+        status: String,
+        started_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+        period_trade_no: Option<String>,
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct Tables {
+        payments: Vec<PaymentRow>,
+        subscriptions: Vec<SubscriptionRow>,
+        evaluation_users: Vec<String>,
+    }
+
+    /// 記憶體版的付款資料，交易在 commit 時才寫回
+    #[derive(Default)]
+    struct MemoryStore {
+        tables: Arc<Mutex<Tables>>,
+        fail_mark_paid: AtomicBool,
+    }
+
+    impl MemoryStore {
+        fn add_payment(&self, merchant_trade_no: &str, tier: &str, amount: i32, auto_renew: bool) {
+            self.tables.lock().unwrap().payments.push(PaymentRow {
+                payment: PendingPayment {
+                    id: format!("payment-{}", merchant_trade_no),
+                    user_id: "user-1".to_string(),
+                    tier: tier.to_string(),
+                    billing_cycle: "monthly".to_string(),
+                    auto_renew,
+                    amount,
+                    status: "pending".to_string(),
+                },
+                merchant_trade_no: Some(merchant_trade_no.to_string()),
+                subscription_id: None,
+                transaction_id: None,
+                failure_reason: None,
+            });
+        }
+
+        fn payment(&self, merchant_trade_no: &str) -> PaymentRow {
+            self.tables
+                .lock()
+                .unwrap()
+                .payments
+                .iter()
+                .find(|row| row.merchant_trade_no.as_deref() == Some(merchant_trade_no))
+                .cloned()
+                .unwrap()
+        }
+
+        fn subscriptions(&self) -> Vec<SubscriptionRow> {
+            self.tables.lock().unwrap().subscriptions.clone()
+        }
+
+        fn period_charges(&self) -> usize {
+            let tables = self.tables.lock().unwrap();
+            tables.payments.iter().filter(|row| row.merchant_trade_no.is_none()).count()
+        }
+    }
+
+    #[async_trait]
+    impl NoticeStore for MemoryStore {
+        async fn trial_parameters(&self) -> AppResult<TrialParameters> {
+            Ok(TrialParameters::default())
+        }
+
+        async fn begin(&self) -> AppResult<Box<dyn NoticeTx>> {
+            Ok(Box::new(MemoryTx {
+                staged: self.tables.lock().unwrap().clone(),
+                tables: self.tables.clone(),
+                fail_mark_paid: self.fail_mark_paid.load(Ordering::SeqCst),
+            }))
+        }
+    }
+
+    struct MemoryTx {
+        tables: Arc<Mutex<Tables>>,
+        staged: Tables,
+        fail_mark_paid: bool,
+    }
+
+    impl MemoryTx {
+        fn payment_mut(&mut self, payment_id: &str) -> &mut PaymentRow {
+            self.staged
+                .payments
+                .iter_mut()
+                .find(|row| row.payment.id == payment_id)
+                .unwrap()
+        }
+    }
+
+    #[async_trait]
+    impl NoticeTx for MemoryTx {
+        async fn lock_payment(&mut self, merchant_trade_no: &str) -> AppResult<Option<PendingPayment>> {
+            Ok(self
+                .staged
+                .payments
+                .iter()
+                .find(|row| row.merchant_trade_no.as_deref() == Some(merchant_trade_no))
+                .map(|row| row.payment.clone()))
+        }
+
+        async fn fail_payment(&mut self, payment_id: &str, reason: &str, trade_no: &str) -> AppResult<()> {
+            let row = self.payment_mut(payment_id);
+            row.payment.status = "failed".to_string();
+            row.failure_reason = Some(reason.to_string());
+            row.transaction_id = Some(trade_no.to_string());
+            Ok(())
+        }
+
+        async fn flag_refund(
+            &mut self,
+            payment_id: &str,
+            reason: &str,
+            trade_no: &str,
+            _paid_at: DateTime<Utc>,
+        ) -> AppResult<()> {
+            let row = self.payment_mut(payment_id);
+            row.payment.status = "refund_required".to_string();
+            row.failure_reason = Some(reason.to_string());
+            row.transaction_id = Some(trade_no.to_string());
+            Ok(())
+        }
+
+        async fn evaluation_purchased(&mut self, user_id: &str) -> AppResult<bool> {
+            Ok(self.staged.evaluation_users.iter().any(|user| user == user_id)
+                || self
+                    .staged
+                    .subscriptions
+                    .iter()
+                    .any(|row| row.user_id == user_id && row.tier == "evaluation"))
+        }
+
+        async fn lock_current_plans(&mut self, user_id: &str, now: DateTime<Utc>) -> AppResult<Vec<CurrentPlan>> {
+            let mut current: Vec<&SubscriptionRow> = self
+                .staged
+                .subscriptions
+                .iter()
+                .filter(|row| row.user_id == user_id && row.status == "active" && row.expires_at > now)
+                .collect();
+            current.sort_by_key(|row| std::cmp::Reverse(row.started_at));
+            Ok(current
+                .into_iter()
+                .map(|row| CurrentPlan {
+                    tier: row.tier.clone(),
+                    expires_at: Some(row.expires_at),
+                    period_trade_no: row.period_trade_no.clone(),
+                })
+                .collect())
+        }
+
+        async fn replace_subscription(&mut self, subscription: &NewSubscription) -> AppResult<()> {
+            for row in &mut self.staged.subscriptions {
+                if row.user_id == subscription.user_id {
+                    row.status = "expired".to_string();
+                }
+            }
+            self.staged.subscriptions.push(SubscriptionRow {
+                id: subscription.id.clone(),
+                user_id: subscription.user_id.clone(),
+                tier: subscription.tier.as_str().to_string(),
+                billing_cycle: subscription.billing_cycle.as_str().to_string(),
+                status: "active".to_string(),
+                started_at: subscription.started_at,
+                expires_at: subscription.expires_at,
+                period_trade_no: subscription.period_trade_no.clone(),
+            });
+            Ok(())
+        }
+
+        async fn mark_evaluation_purchased(&mut self, user_id: &str) -> AppResult<()> {
+            self.staged.evaluation_users.push(user_id.to_string());
+            Ok(())
+        }
+
+        async fn mark_paid(
+            &mut self,
+            payment_id: &str,
+            subscription_id: &str,
+            trade_no: &str,
+            _paid_at: DateTime<Utc>,
+        ) -> AppResult<()> {
+            if self.fail_mark_paid {
+                return Err(anyhow::anyhow!("lost connection to the database").into());
+            }
+            let row = self.payment_mut(payment_id);
+            row.payment.status = "paid".to_string();
+            row.subscription_id = Some(subscription_id.to_string());
+            row.transaction_id = Some(trade_no.to_string());
+            row.failure_reason = None;
+            Ok(())
+        }
+
+        async fn lock_period_subscription(
+            &mut self,
+            merchant_trade_no: &str,
+        ) -> AppResult<Option<RenewingSubscription>> {
+            Ok(self
+                .staged
+                .subscriptions
+                .iter()
+                .filter(|row| row.period_trade_no.as_deref() == Some(merchant_trade_no))
+                .max_by_key(|row| row.started_at)
+                .map(|row| RenewingSubscription {
+                    id: row.id.clone(),
+                    user_id: row.user_id.clone(),
+                    tier: row.tier.clone(),
+                    billing_cycle: Some(row.billing_cycle.clone()),
+                    expires_at: Some(row.expires_at),
+                    cancel_at_period_end: false,
+                }))
+        }
+
+        async fn period_charge_recorded(&mut self, gwsr: &str, subscription_id: &str) -> AppResult<bool> {
+            Ok(self.staged.payments.iter().any(|row| {
+                row.transaction_id.as_deref() == Some(gwsr)
+                    && row.subscription_id.as_deref() == Some(subscription_id)
+            }))
+        }
+
+        async fn record_period_charge(
+            &mut self,
+            subscription_id: &str,
+            notice: &PeriodNotice,
+            _now: DateTime<Utc>,
+        ) -> AppResult<()> {
+            let first = self
+                .staged
+                .payments
+                .iter()
+                .find(|row| row.merchant_trade_no.as_deref() == Some(notice.merchant_trade_no.as_str()))
+                .cloned()
+                .unwrap();
+            self.staged.payments.push(PaymentRow {
+                payment: PendingPayment {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    amount: notice.amount as i32,
+                    status: if notice.paid { "paid" } else { "failed" }.to_string(),
+                    ..first.payment
+                },
+                merchant_trade_no: None,
+                subscription_id: Some(subscription_id.to_string()),
+                transaction_id: Some(notice.gwsr.clone()),
+                failure_reason: (!notice.paid).then(|| notice.message.clone()),
+            });
+            Ok(())
+        }
+
+        async fn extend_subscription(&mut self, subscription_id: &str, expires_at: DateTime<Utc>) -> AppResult<()> {
+            let row = self
+                .staged
+                .subscriptions
+                .iter_mut()
+                .find(|row| row.id == subscription_id)
+                .unwrap();
+            row.status = "active".to_string();
+            row.expires_at = expires_at;
+            Ok(())
+        }
+
+        async fn commit(self: Box<Self>) -> AppResult<()> {
+            *self.tables.lock().unwrap() = self.staged;
+            Ok(())
+        }
+    }
+
+    struct Merchant {
+        gateway: EcPay,
+        events: SubscriptionEvents,
+        notify_url: String,
+        period_notify_url: String,
+    }
+
+    /// 以記憶體資料啟動特店的兩個通知 endpoint，gateway 指向假綠界
+    async fn spawn_merchant(fake: &FakeEcPay, store: Arc<MemoryStore>) -> Merchant {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let notify_url = format!("http://{}/notify", addr);
+        let period_notify_url = format!("http://{}/period-notify", addr);
+        let gateway = EcPay::new(
+            MERCHANT_ID,
+            HASH_KEY,
+            HASH_IV,
+            &fake.base_url(),
+            &notify_url,
+            &period_notify_url,
+        );
+        let events = SubscriptionEvents::new();
+
+        let (payments, periods) = (gateway.clone(), gateway.clone());
+        let (payment_store, period_store) = (store.clone(), store);
+        let (payment_events, period_events) = (events.clone(), events.clone());
+        let app = Router::new()
+            .route(
+                "/notify",
+                post(move |Form(params): Form<HashMap<String, String>>| async move {
+                    handle_payment_notice(&payments, &*payment_store, &payment_events, &params).await
+                }),
+            )
+            .route(
+                "/period-notify",
+                post(move |Form(params): Form<HashMap<String, String>>| async move {
+                    handle_period_notice(&periods, &*period_store, &period_events, &params).await
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        Merchant {
+            gateway,
+            events,
+            notify_url,
+            period_notify_url,
+        }
+    }
+
+    impl Merchant {
+        /// 送出結帳表單，假綠界通知特店後回傳特店的回應
+        async fn checkout(&self, merchant_trade_no: &str, amount: u32, period: Option<Period>) -> String {
+            let form = self.gateway.checkout_form(&CheckoutOrder {
+                merchant_trade_no: merchant_trade_no.to_string(),
+                trade_date: Utc::now(),
+                amount,
+                description: "Nice Speak 訂閱".to_string(),
+                item_name: "入門版 × 1 個月".to_string(),
+                payment_method: "credit_card".to_string(),
+                period,
+            });
+            post_form(&form.action, &form.fields).await
+        }
+    }
+
+    async fn post_form<T: serde::Serialize + ?Sized>(url: &str, params: &T) -> String {
+        reqwest::Client::new()
+            .post(url)
+            .form(params)
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap()
+    }
+
+    async fn setup(trade_nos: &[(&str, &str, i32, bool)]) -> (FakeEcPay, Arc<MemoryStore>, Merchant) {
+        let fake = FakeEcPay::spawn(MERCHANT_ID, HASH_KEY, HASH_IV).await.unwrap();
+        let store = Arc::new(MemoryStore::default());
+        for (trade_no, tier, amount, auto_renew) in trade_nos {
+            store.add_payment(trade_no, tier, *amount, *auto_renew);
+        }
+        let merchant = spawn_merchant(&fake, store.clone()).await;
+        (fake, store, merchant)
+    }
+
+    #[tokio::test]
+    async fn test_payment_activates_once() {
+        let (fake, store, merchant) = setup(&[("NS0000000000000000A1", "basic", 691, false)]).await;
+        let mut events = merchant.events.subscribe();
+
+        let response = merchant.checkout("NS0000000000000000A1", 691, None).await;
+        assert_eq!(response, ecpay::NOTIFY_ACK);
+        let payment = store.payment("NS0000000000000000A1");
+        assert_eq!(payment.payment.status, "paid");
+        let subscriptions = store.subscriptions();
+        assert_eq!(subscriptions.len(), 1);
+        assert_eq!(payment.subscription_id.as_deref(), Some(subscriptions[0].id.as_str()));
+        assert_eq!(subscriptions[0].tier, "basic");
+        let event = events.try_recv().unwrap();
+        assert_eq!((event.kind, event.tier), (SubscriptionEventKind::Activated, Tier::Basic));
+
+        // 綠界重送同一則通知
+        let retried = fake.notifications()[0].params.clone();
+        assert_eq!(post_form(&merchant.notify_url, &retried).await, ecpay::NOTIFY_ACK);
+        assert_eq!(store.subscriptions().len(), 1);
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_amount_mismatch_fails_payment() {
+        let (_fake, store, merchant) = setup(&[("NS0000000000000000B1", "basic", 691, false)]).await;
+
+        assert_eq!(merchant.checkout("NS0000000000000000B1", 100, None).await, ecpay::NOTIFY_ACK);
+        let payment = store.payment("NS0000000000000000B1");
+        assert_eq!(payment.payment.status, "failed");
+        assert_eq!(payment.failure_reason.as_deref(), Some("Amount mismatch: notified 100"));
+        assert!(store.subscriptions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_payment_can_succeed_later() {
+        let (fake, store, merchant) = setup(&[("NS0000000000000000C1", "basic", 691, false)]).await;
+
+        fake.set_rtn_code(10100248);
+        assert_eq!(merchant.checkout("NS0000000000000000C1", 691, None).await, ecpay::NOTIFY_ACK);
+        assert_eq!(store.payment("NS0000000000000000C1").payment.status, "failed");
+        assert!(store.subscriptions().is_empty());
+
+        fake.set_rtn_code(1);
+        assert_eq!(merchant.checkout("NS0000000000000000C1", 691, None).await, ecpay::NOTIFY_ACK);
+        let payment = store.payment("NS0000000000000000C1");
+        assert_eq!(payment.payment.status, "paid");
+        assert_eq!(payment.failure_reason, None);
+        assert_eq!(store.subscriptions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_activation_is_atomic() {
+        let (fake, store, merchant) = setup(&[("NS0000000000000000D1", "basic", 691, false)]).await;
+
+        // 更新付款記錄失敗時訂閱也不能留下，並要求綠界重送
+        store.fail_mark_paid.store(true, Ordering::SeqCst);
+        let response = merchant.checkout("NS0000000000000000D1", 691, None).await;
+        assert!(response.starts_with("0|"));
+        assert_eq!(store.payment("NS0000000000000000D1").payment.status, "pending");
+        assert!(store.subscriptions().is_empty());
+
+        store.fail_mark_paid.store(false, Ordering::SeqCst);
+        let retried = fake.notifications()[0].params.clone();
+        assert_eq!(post_form(&merchant.notify_url, &retried).await, ecpay::NOTIFY_ACK);
+        assert_eq!(store.payment("NS0000000000000000D1").payment.status, "paid");
+        assert_eq!(store.subscriptions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_simulated_payment_is_only_acknowledged() {
+        let (fake, store, merchant) = setup(&[("NS0000000000000000E1", "basic", 691, false)]).await;
+        let mut events = merchant.events.subscribe();
+
+        fake.set_simulate_paid(true);
+        assert_eq!(merchant.checkout("NS0000000000000000E1", 691, None).await, ecpay::NOTIFY_ACK);
+        assert_eq!(store.payment("NS0000000000000000E1").payment.status, "pending");
+        assert!(store.subscriptions().is_empty());
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_period_renewal_and_plan_change() {
+        let (fake, store, merchant) = setup(&[
+            ("NS0000000000000000F1", "basic", 691, true),
+            ("NS0000000000000000F2", "advanced", 1490, false),
+        ])
+        .await;
+
+        merchant.checkout("NS0000000000000000F1", 691, Some(Period::Monthly)).await;
+        let first = store.subscriptions()[0].clone();
+        assert_eq!(first.period_trade_no.as_deref(), Some("NS0000000000000000F1"));
+
+        assert_eq!(fake.charge_period("NS0000000000000000F1").await.unwrap(), ecpay::NOTIFY_ACK);
+        let renewed = store.subscriptions()[0].clone();
+        assert!(renewed.expires_at > first.expires_at + Duration::days(27));
+        assert_eq!(store.period_charges(), 1);
+
+        // 重送的扣款通知不會重複延長
+        let retried = fake.notifications().last().unwrap().params.clone();
+        assert_eq!(post_form(&merchant.period_notify_url, &retried).await, ecpay::NOTIFY_ACK);
+        assert_eq!(store.period_charges(), 1);
+        assert_eq!(store.subscriptions()[0].expires_at, renewed.expires_at);
+
+        // 換方案後停止舊方案的定期定額
+        merchant.checkout("NS0000000000000000F2", 1490, None).await;
+        let subscriptions = store.subscriptions();
+        assert_eq!(subscriptions[0].status, "expired");
+        assert_eq!((subscriptions[1].tier.as_str(), subscriptions[1].status.as_str()), ("advanced", "active"));
+        assert!(fake.is_cancelled("NS0000000000000000F1"));
+    }
+
+    #[tokio::test]
+    async fn test_repeat_evaluation_payment_needs_refund() {
+        let (_fake, store, merchant) = setup(&[
+            ("NS0000000000000000G1", "evaluation", 39, false),
+            ("NS0000000000000000G2", "evaluation", 39, false),
+        ])
+        .await;
+
+        // 兩張評估版報價都付款，只有第一筆開通
+        merchant.checkout("NS0000000000000000G1", 39, None).await;
+        assert_eq!(merchant.checkout("NS0000000000000000G2", 39, None).await, ecpay::NOTIFY_ACK);
+        let payment = store.payment("NS0000000000000000G2");
+        assert_eq!(payment.payment.status, "refund_required");
+        assert_eq!(payment.failure_reason.as_deref(), Some("Evaluation plan was already purchased"));
+        assert_eq!(store.subscriptions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_evaluation_payment_does_not_replace_paid_plan() {
+        let (_fake, store, merchant) = setup(&[
+            ("NS0000000000000000H1", "basic", 691, false),
+            ("NS0000000000000000H2", "evaluation", 39, false),
+        ])
+        .await;
+
+        merchant.checkout("NS0000000000000000H1", 691, None).await;
+        assert_eq!(merchant.checkout("NS0000000000000000H2", 39, None).await, ecpay::NOTIFY_ACK);
+        let payment = store.payment("NS0000000000000000H2");
+        assert_eq!(payment.payment.status, "refund_required");
+        assert_eq!(payment.failure_reason.as_deref(), Some("A paid plan is active"));
+        let subscriptions = store.subscriptions();
+        assert_eq!(subscriptions.len(), 1);
+        assert_eq!((subscriptions[0].tier.as_str(), subscriptions[0].status.as_str()), ("basic", "active"));
+
+        // 重送的通知不會再處理
+        assert_eq!(classify(&payment.payment, &notice(true, 39)), NoticeOutcome::Ignore);
+    }
+}
@@ -0,0 +1,249 @@
+// src/subscription/pricing.rs
+
+use super::tier::Tier;
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+
+/// 年繳折扣 (PRICING.md「年費優惠」8 折)
+pub const YEARLY_DISCOUNT_PERCENTAGE: u32 = 20;
+/// 綠界最低交易金額 (NT$1)，優惠碼不能把訂單折到免費
+pub const MIN_CHARGE: u32 = 1;
+
+/// 付款週期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BillingCycle {
+    Monthly,
+    Yearly,
+    /// 評估版單次購買
+    OneTime,
+}
+
+impl BillingCycle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BillingCycle::Monthly => "monthly",
+            BillingCycle::Yearly => "yearly",
+            BillingCycle::OneTime => "one_time",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "monthly" => Some(BillingCycle::Monthly),
+            "yearly" => Some(BillingCycle::Yearly),
+            "one_time" => Some(BillingCycle::OneTime),
+            _ => None,
+        }
+    }
+
+    /// 一期包含的月數
+    pub fn months(&self) -> u32 {
+        match self {
+            BillingCycle::Yearly => 12,
+            BillingCycle::Monthly | BillingCycle::OneTime => 1,
+        }
+    }
+}
+
+/// 促銷折扣 (百分比與固定金額擇一)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Promotion {
+    pub code: String,
+    pub percent_off: u32,
+    pub amount_off: u32,
+}
+
+/// 報價明細
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineItem {
+    /// plan / billing_cycle / level_discount / promotion
+    pub kind: String,
+    pub description: String,
+    /// 折扣為負數 (NT$)
+    pub amount: i64,
+}
+
+/// 報價
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Quote {
+    pub tier: Tier,
+    pub billing_cycle: BillingCycle,
+    pub currency: &'static str,
+    pub items: Vec<LineItem>,
+    /// 原價小計
+    pub list_price: u32,
+    pub cycle_discount: u32,
+    pub level_discount_percentage: u32,
+    pub level_discount: u32,
+    pub promo_code: Option<String>,
+    pub promo_discount: u32,
+    /// 應付金額
+    pub total: u32,
+}
+
+/// 計算報價：原價 × 月數 → 年繳折扣 → 等級折扣 → 促銷折扣，每一步以前一步的小計計算並四捨五入到元
+///
+/// 等級折扣只適用月付方案 (入門版以上)，5 級與 10 級不疊加，由 `level_discount_percentage` 傳入較高者。
+pub fn calculate(
+    tier: Tier,
+    cycle: BillingCycle,
+    level_discount_percentage: u32,
+    promotion: Option<&Promotion>,
+) -> AppResult<Quote> {
+    let spec = tier.spec();
+    match (tier, cycle) {
+        (Tier::Free, _) => {
+            return Err(AppError::Validation("The free plan cannot be purchased".to_string()))
+        }
+        (Tier::Evaluation, BillingCycle::OneTime) => {}
+        (Tier::Evaluation, _) => {
+            return Err(AppError::Validation(
+                "The evaluation plan is a one_time purchase".to_string(),
+            ))
+        }
+        (_, BillingCycle::OneTime) => {
+            return Err(AppError::Validation(format!(
+                "The {} plan is billed monthly or yearly",
+                tier
+            )))
+        }
+        _ => {}
+    }
+
+    let percent_of = |amount: u32, percentage: u32| (amount * percentage.min(100) + 50) / 100;
+    let mut items = Vec::new();
+
+    let list_price = spec.price * cycle.months();
+    items.push(LineItem {
+        kind: "plan".to_string(),
+        description: format!("{} × {} 個月", spec.name, cycle.months()),
+        amount: list_price as i64,
+    });
+    let mut subtotal = list_price;
+
+    let cycle_discount = if cycle == BillingCycle::Yearly {
+        percent_of(subtotal, YEARLY_DISCOUNT_PERCENTAGE)
+    } else {
+        0
+    };
+    if cycle_discount > 0 {
+        items.push(LineItem {
+            kind: "billing_cycle".to_string(),
+            description: format!("年繳 {}% 折扣", YEARLY_DISCOUNT_PERCENTAGE),
+            amount: -(cycle_discount as i64),
+        });
+        subtotal -= cycle_discount;
+    }
+
+    let level_discount_percentage = if tier >= Tier::Basic { level_discount_percentage.min(100) } else { 0 };
+    let level_discount = percent_of(subtotal, level_discount_percentage);
+    if level_discount > 0 {
+        items.push(LineItem {
+            kind: "level_discount".to_string(),
+            description: format!("等級 {}% 折扣", level_discount_percentage),
+            amount: -(level_discount as i64),
+        });
+        subtotal -= level_discount;
+    }
+
+    let promo_discount = match promotion {
+        Some(promotion) => {
+            let discount = if promotion.percent_off > 0 {
+                percent_of(subtotal, promotion.percent_off)
+            } else {
+                promotion.amount_off
+            };
+            if subtotal.saturating_sub(discount) < MIN_CHARGE {
+                return Err(AppError::Validation(format!(
+                    "Promo code {} cannot bring the total below NT${}",
+                    promotion.code, MIN_CHARGE
+                )));
+            }
+            discount
+        }
+        None => 0,
+    };
+    if let Some(promotion) = promotion.filter(|_| promo_discount > 0) {
+        items.push(LineItem {
+            kind: "promotion".to_string(),
+            description: format!("優惠碼 {}", promotion.code),
+            amount: -(promo_discount as i64),
+        });
+        subtotal -= promo_discount;
+    }
+
+    Ok(Quote {
+        tier,
+        billing_cycle: cycle,
+        currency: "TWD",
+        items,
+        list_price,
+        cycle_discount,
+        level_discount_percentage: if level_discount > 0 { level_discount_percentage } else { 0 },
+        level_discount,
+        promo_code: promotion.filter(|_| promo_discount > 0).map(|promotion| promotion.code.clone()),
+        promo_discount,
+        total: subtotal,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_discounts_match_pricing_table() {
+        let monthly = |tier, discount| calculate(tier, BillingCycle::Monthly, discount, None).unwrap().total;
+        assert_eq!(monthly(Tier::Basic, 0), 100);
+        assert_eq!(monthly(Tier::Basic, 20), 80);
+        assert_eq!(monthly(Tier::Advanced, 20), 240);
+        assert_eq!(monthly(Tier::Premium, 40), 600);
+        assert_eq!(monthly(Tier::Unlimited, 40), 6_000);
+    }
+
+    #[test]
+    fn test_yearly_then_level_then_promotion() {
+        let promotion = Promotion {
+            code: "SPRING".to_string(),
+            percent_off: 10,
+            amount_off: 0,
+        };
+        let quote = calculate(Tier::Basic, BillingCycle::Yearly, 20, Some(&promotion)).unwrap();
+        assert_eq!(quote.list_price, 1_200);
+        assert_eq!(quote.cycle_discount, 240);
+        assert_eq!(quote.level_discount, 192);
+        assert_eq!(quote.promo_discount, 77);
+        assert_eq!(quote.total, 691);
+        let sum: i64 = quote.items.iter().map(|item| item.amount).sum();
+        assert_eq!(sum, quote.total as i64);
+    }
+
+    #[test]
+    fn test_evaluation_is_one_time_without_level_discount() {
+        let quote = calculate(Tier::Evaluation, BillingCycle::OneTime, 40, None).unwrap();
+        assert_eq!(quote.total, 39);
+        assert_eq!(quote.level_discount_percentage, 0);
+        assert!(calculate(Tier::Evaluation, BillingCycle::Monthly, 0, None).is_err());
+        assert!(calculate(Tier::Basic, BillingCycle::OneTime, 0, None).is_err());
+        assert!(calculate(Tier::Free, BillingCycle::Monthly, 0, None).is_err());
+    }
+
+    #[test]
+    fn test_promotion_cannot_make_order_free() {
+        let promotion = |amount_off, percent_off| Promotion {
+            code: "FREE39".to_string(),
+            percent_off,
+            amount_off,
+        };
+        let quote = calculate(Tier::Evaluation, BillingCycle::OneTime, 0, Some(&promotion(38, 0))).unwrap();
+        assert_eq!(quote.promo_discount, 38);
+        assert_eq!(quote.total, 1);
+        for free in [promotion(39, 0), promotion(100, 0), promotion(0, 100)] {
+            assert!(matches!(
+                calculate(Tier::Evaluation, BillingCycle::OneTime, 0, Some(&free)),
+                Err(AppError::Validation(_))
+            ));
+        }
+    }
+}
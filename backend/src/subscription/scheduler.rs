@@ -0,0 +1,231 @@
+// src/subscription/scheduler.rs
+
+use super::events::{SubscriptionEvent, SubscriptionEventKind, SubscriptionEvents};
+use super::tier::Tier;
+use crate::config::SubscriptionConfig;
+use crate::error::AppResult;
+use crate::payment::EcPay;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::MySqlPool;
+
+/// 排程執行間隔
+const SWEEP_INTERVAL_SECS: u64 = 60;
+
+#[derive(sqlx::FromRow)]
+struct DueSubscription {
+    id: String,
+    user_id: String,
+    tier: String,
+    expires_at: Option<DateTime<Utc>>,
+    auto_renew: bool,
+    cancel_at_period_end: bool,
+    period_trade_no: Option<String>,
+}
+
+impl DueSubscription {
+    fn event(&self, kind: SubscriptionEventKind) -> SubscriptionEvent {
+        SubscriptionEvent {
+            user_id: self.user_id.clone(),
+            kind,
+            tier: Tier::parse(&self.tier).unwrap_or(Tier::Free),
+            expires_at: self.expires_at,
+        }
+    }
+}
+
+/// 到期的訂閱接下來的狀態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lapse {
+    /// 等待定期定額扣款，寬限期內保留權益
+    PastDue { grace_until: DateTime<Utc> },
+    /// 直接結束 (`expired` 或用戶取消的 `cancelled`)
+    Ended { status: &'static str },
+}
+
+fn lapse(
+    auto_renew: bool,
+    cancel_at_period_end: bool,
+    expires_at: DateTime<Utc>,
+    grace_period_days: i64,
+) -> Lapse {
+    if cancel_at_period_end {
+        Lapse::Ended { status: "cancelled" }
+    } else if auto_renew {
+        Lapse::PastDue {
+            grace_until: expires_at + Duration::days(grace_period_days.max(0)),
+        }
+    } else {
+        Lapse::Ended { status: "expired" }
+    }
+}
+
+/// 一次排程處理的筆數
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LifecycleReport {
+    pub notified: u64,
+    pub past_due: u64,
+    pub ended: u64,
+}
+
+/// 執行一次訂閱生命週期：到期提醒 → 到期 (進入寬限期或結束) → 寬限期結束
+///
+/// 每一筆都以條件式 UPDATE 轉換狀態，多個實例同時執行時事件只會發布一次。
+pub async fn run_lifecycle(
+    pool: &MySqlPool,
+    gateway: &EcPay,
+    events: &SubscriptionEvents,
+    config: &SubscriptionConfig,
+    now: DateTime<Utc>,
+) -> AppResult<LifecycleReport> {
+    let mut report = LifecycleReport::default();
+
+    let expiring: Vec<DueSubscription> = sqlx::query_as(
+        r#"
+        SELECT id, user_id, tier, expires_at, auto_renew, cancel_at_period_end, period_trade_no
+        FROM subscriptions
+        WHERE status = 'active' AND tier <> 'free' AND expiry_notified_at IS NULL
+          AND (auto_renew = 0 OR cancel_at_period_end = 1)
+          AND expires_at > ? AND expires_at <= ?
+        "#,
+    )
+    .bind(now)
+    .bind(now + Duration::days(config.expiry_notice_days))
+    .fetch_all(pool)
+    .await?;
+    for subscription in expiring {
+        let updated = sqlx::query(
+            "UPDATE subscriptions SET expiry_notified_at = ? WHERE id = ? AND expiry_notified_at IS NULL",
+        )
+        .bind(now)
+        .bind(&subscription.id)
+        .execute(pool)
+        .await?;
+        if updated.rows_affected() > 0 {
+            events.publish(subscription.event(SubscriptionEventKind::ExpiringSoon));
+            report.notified += 1;
+        }
+    }
+
+    let lapsed: Vec<DueSubscription> = sqlx::query_as(
+        r#"
+        SELECT id, user_id, tier, expires_at, auto_renew, cancel_at_period_end, period_trade_no
+        FROM subscriptions
+        WHERE status = 'active' AND expires_at <= ?
+        "#,
+    )
+    .bind(now)
+    .fetch_all(pool)
+    .await?;
+    for subscription in lapsed {
+        let Some(expires_at) = subscription.expires_at else {
+            continue;
+        };
+        match lapse(
+            subscription.auto_renew,
+            subscription.cancel_at_period_end,
+            expires_at,
+            config.grace_period_days,
+        ) {
+            Lapse::PastDue { grace_until } => {
+                let updated = sqlx::query(
+                    "UPDATE subscriptions SET status = 'past_due', grace_until = ? WHERE id = ? AND status = 'active'",
+                )
+                .bind(grace_until)
+                .bind(&subscription.id)
+                .execute(pool)
+                .await?;
+                if updated.rows_affected() > 0 {
+                    events.publish(subscription.event(SubscriptionEventKind::PastDue));
+                    report.past_due += 1;
+                }
+            }
+            Lapse::Ended { status } => {
+                let updated = sqlx::query("UPDATE subscriptions SET status = ? WHERE id = ? AND status = 'active'")
+                    .bind(status)
+                    .bind(&subscription.id)
+                    .execute(pool)
+                    .await?;
+                if updated.rows_affected() > 0 {
+                    events.publish(subscription.event(SubscriptionEventKind::Expired));
+                    report.ended += 1;
+                }
+            }
+        }
+    }
+
+    let grace_over: Vec<DueSubscription> = sqlx::query_as(
+        r#"
+        SELECT id, user_id, tier, expires_at, auto_renew, cancel_at_period_end, period_trade_no
+        FROM subscriptions
+        WHERE status = 'past_due' AND grace_until <= ?
+        "#,
+    )
+    .bind(now)
+    .fetch_all(pool)
+    .await?;
+    for subscription in grace_over {
+        let updated = sqlx::query(
+            "UPDATE subscriptions SET status = 'expired', auto_renew = 0 WHERE id = ? AND status = 'past_due'",
+        )
+        .bind(&subscription.id)
+        .execute(pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            continue;
+        }
+        // 寬限期內都沒有扣款成功，停止之後的定期定額，避免訂閱結束後仍被扣款
+        if let Some(trade_no) = &subscription.period_trade_no {
+            if let Err(err) = gateway.cancel_period(trade_no).await {
+                log::error!("failed to cancel ECPay period {}: {:#}", trade_no, err);
+            }
+        }
+        events.publish(subscription.event(SubscriptionEventKind::Expired));
+        report.ended += 1;
+    }
+
+    Ok(report)
+}
+
+/// 背景定期執行訂閱生命週期
+pub fn spawn_lifecycle_scheduler(
+    pool: MySqlPool,
+    gateway: EcPay,
+    events: SubscriptionEvents,
+    config: SubscriptionConfig,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(SWEEP_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match run_lifecycle(&pool, &gateway, &events, &config, Utc::now()).await {
+                Ok(report) if report == LifecycleReport::default() => {}
+                Ok(report) => log::info!("subscription lifecycle: {:?}", report),
+                Err(err) => log::warn!("subscription lifecycle failed: {}", err),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_lapse() {
+        let expires_at = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            lapse(true, false, expires_at, 3),
+            Lapse::PastDue {
+                grace_until: Utc.with_ymd_and_hms(2024, 3, 4, 0, 0, 0).unwrap()
+            }
+        );
+        assert_eq!(lapse(false, false, expires_at, 3), Lapse::Ended { status: "expired" });
+        // 取消後不再續訂，也不給寬限期
+        assert_eq!(lapse(true, true, expires_at, 3), Lapse::Ended { status: "cancelled" });
+        assert_eq!(
+            lapse(true, false, expires_at, -1),
+            Lapse::PastDue { grace_until: expires_at }
+        );
+    }
+}
@@ -0,0 +1,173 @@
+// src/subscription/tier.rs
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 訂閱方案 (依價格由低到高排列，見 Document/PRICING.md)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    Free,
+    Evaluation,
+    Basic,
+    Advanced,
+    Premium,
+    Platinum,
+    Unlimited,
+}
+
+/// 可練習的角色範圍
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoleScope {
+    /// 6 選 1；`switchable` 表示之後可以換角色
+    One { switchable: bool },
+    All,
+}
+
+/// 可對話的角色範圍
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartnerScope {
+    /// 不限對話角色 (總情境數另有上限)
+    Any,
+    /// 每個角色固定 3 個對話角色 (見 `basic_partners`)
+    Fixed,
+    /// 每個角色自選 `count` 個對話角色
+    Chosen { count: usize },
+    /// 6 個對話角色全部開放
+    All,
+}
+
+/// 方案內容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TierSpec {
+    pub name: &'static str,
+    pub roles: RoleScope,
+    pub partners: PartnerScope,
+    /// 每個角色 × 對話角色組合可練習的情境數；None 表示只有總數上限
+    pub per_combination: Option<u32>,
+    /// 所選角色可練習的情境總數 (只用於 6 選 1 的方案)
+    pub role_limit: Option<u32>,
+    /// 單次方案的天數；月付方案為 None
+    pub duration_days: Option<i64>,
+    /// 原價 (NT$，單次或月費)
+    pub price: u32,
+}
+
+impl Tier {
+    pub const ALL: [Tier; 7] = [
+        Tier::Free,
+        Tier::Evaluation,
+        Tier::Basic,
+        Tier::Advanced,
+        Tier::Premium,
+        Tier::Platinum,
+        Tier::Unlimited,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Tier::Free => "free",
+            Tier::Evaluation => "evaluation",
+            Tier::Basic => "basic",
+            Tier::Advanced => "advanced",
+            Tier::Premium => "premium",
+            Tier::Platinum => "platinum",
+            Tier::Unlimited => "unlimited",
+        }
+    }
+
+    /// 試用方案 (免費版、評估版)，其他為付費的月付方案
+    pub fn is_trial(&self) -> bool {
+        matches!(self, Tier::Free | Tier::Evaluation)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Tier::ALL.into_iter().find(|tier| tier.as_str() == value)
+    }
+
+    pub fn spec(&self) -> TierSpec {
+        let monthly = |name, partners, per_combination, price| TierSpec {
+            name,
+            roles: RoleScope::All,
+            partners,
+            per_combination: Some(per_combination),
+            role_limit: None,
+            duration_days: None,
+            price,
+        };
+
+        match self {
+            Tier::Free => TierSpec {
+                name: "免費版",
+                roles: RoleScope::One { switchable: false },
+                partners: PartnerScope::Any,
+                per_combination: None,
+                role_limit: Some(10),
+                duration_days: Some(3),
+                price: 0,
+            },
+            Tier::Evaluation => TierSpec {
+                name: "評估版",
+                roles: RoleScope::One { switchable: true },
+                partners: PartnerScope::Any,
+                per_combination: None,
+                role_limit: Some(10),
+                duration_days: Some(7),
+                price: 39,
+            },
+            Tier::Basic => monthly("入門版", PartnerScope::Fixed, 10, 100),
+            Tier::Advanced => monthly("進階版", PartnerScope::Chosen { count: 3 }, 30, 300),
+            Tier::Premium => monthly("高階版", PartnerScope::All, 30, 1_000),
+            Tier::Platinum => monthly("白金版", PartnerScope::All, 100, 3_000),
+            Tier::Unlimited => monthly("無限版", PartnerScope::All, 300, 10_000),
+        }
+    }
+}
+
+impl TierSpec {
+    /// 每個角色可對話的角色數
+    pub fn partner_count(&self) -> u32 {
+        match self.partners {
+            PartnerScope::Any => 0,
+            PartnerScope::Fixed => 3,
+            PartnerScope::Chosen { count } => count as u32,
+            PartnerScope::All => 6,
+        }
+    }
+
+    /// 方案的總情境數 (PRICING.md「總情境」)
+    pub fn total_scenarios(&self) -> u32 {
+        match (self.role_limit, self.per_combination) {
+            (Some(limit), _) => limit,
+            (None, Some(per_combination)) => 6 * self.partner_count() * per_combination,
+            (None, None) => 0,
+        }
+    }
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_scenarios_match_pricing() {
+        let totals: Vec<u32> = Tier::ALL.iter().map(|tier| tier.spec().total_scenarios()).collect();
+        assert_eq!(totals, vec![10, 10, 180, 540, 1_080, 3_600, 10_800]);
+    }
+
+    #[test]
+    fn test_tiers_ordered_by_price() {
+        for pair in Tier::ALL.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(pair[0].spec().price < pair[1].spec().price);
+        }
+        assert_eq!(Tier::parse("premium"), Some(Tier::Premium));
+        assert_eq!(Tier::parse("gold"), None);
+    }
+}
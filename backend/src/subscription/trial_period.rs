@@ -0,0 +1,117 @@
+// src/subscription/trial_period.rs
+
+use super::tier::Tier;
+use crate::error::AppResult;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{MySqlConnection, MySqlPool};
+
+/// 免費版與評估版的使用天數 (管理後台 `settings/parameters`，存於 `system_parameters`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrialParameters {
+    pub free_trial_days: i64,
+    pub evaluation_trial_days: i64,
+}
+
+impl Default for TrialParameters {
+    /// 沒有設定參數時依 PRICING.md 的天數
+    fn default() -> Self {
+        Self {
+            free_trial_days: Tier::Free.spec().duration_days.unwrap_or(3),
+            evaluation_trial_days: Tier::Evaluation.spec().duration_days.unwrap_or(7),
+        }
+    }
+}
+
+impl TrialParameters {
+    /// 方案的使用天數；月付方案為 None
+    pub fn duration_days(&self, tier: Tier) -> Option<i64> {
+        match tier {
+            Tier::Free => Some(self.free_trial_days),
+            Tier::Evaluation => Some(self.evaluation_trial_days),
+            _ => tier.spec().duration_days,
+        }
+    }
+
+    /// 試用結束時間：免費版從註冊、評估版從購買開始計算；月付方案為 None
+    pub fn ends_at(&self, tier: Tier, started_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.duration_days(tier)
+            .map(|days| started_at + Duration::days(days.max(0)))
+    }
+}
+
+/// 試用結束後建議升級的方案：評估版只能購買一次
+pub fn upgrade_after_trial(tier: Tier, evaluation_purchased: bool) -> Tier {
+    if tier == Tier::Free && !evaluation_purchased {
+        Tier::Evaluation
+    } else {
+        Tier::Basic
+    }
+}
+
+/// 讀取試用天數，未設定的參數使用預設值
+pub async fn load_trial_parameters(pool: &MySqlPool) -> AppResult<TrialParameters> {
+    let rows: Vec<(String, i32)> = sqlx::query_as(
+        "SELECT name, value FROM system_parameters WHERE name IN ('free_trial_days', 'evaluation_trial_days')",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut parameters = TrialParameters::default();
+    for (name, value) in rows {
+        match name.as_str() {
+            "free_trial_days" => parameters.free_trial_days = i64::from(value),
+            "evaluation_trial_days" => parameters.evaluation_trial_days = i64::from(value),
+            _ => {}
+        }
+    }
+    Ok(parameters)
+}
+
+/// 帳號或帳號綁定的設備是否已購買過評估版
+pub async fn evaluation_purchased(conn: &mut MySqlConnection, user_id: &str) -> AppResult<bool> {
+    let purchased: i64 = sqlx::query_scalar(
+        r#"
+        SELECT (SELECT COUNT(*) FROM subscriptions WHERE user_id = ? AND tier = 'evaluation')
+             + (SELECT COUNT(*) FROM devices WHERE user_id = ? AND has_purchased_evaluation = 1)
+        "#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(purchased > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_ends_at() {
+        let parameters = TrialParameters {
+            free_trial_days: 5,
+            evaluation_trial_days: 10,
+        };
+        let started_at = Utc.with_ymd_and_hms(2024, 3, 1, 8, 0, 0).unwrap();
+        assert_eq!(
+            parameters.ends_at(Tier::Free, started_at),
+            Some(Utc.with_ymd_and_hms(2024, 3, 6, 8, 0, 0).unwrap())
+        );
+        assert_eq!(
+            parameters.ends_at(Tier::Evaluation, started_at),
+            Some(Utc.with_ymd_and_hms(2024, 3, 11, 8, 0, 0).unwrap())
+        );
+        assert_eq!(parameters.ends_at(Tier::Basic, started_at), None);
+
+        let defaults = TrialParameters::default();
+        assert_eq!((defaults.free_trial_days, defaults.evaluation_trial_days), (3, 7));
+    }
+
+    #[test]
+    fn test_upgrade_after_trial() {
+        assert_eq!(upgrade_after_trial(Tier::Free, false), Tier::Evaluation);
+        assert_eq!(upgrade_after_trial(Tier::Free, true), Tier::Basic);
+        assert_eq!(upgrade_after_trial(Tier::Evaluation, true), Tier::Basic);
+    }
+}
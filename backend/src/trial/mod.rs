@@ -0,0 +1,315 @@
+// src/trial/mod.rs
+
+mod scoring;
+
+pub use scoring::{assess, email_identity, ip_subnet, TrialAssessment, TrialDecision, TrialSignals};
+
+use crate::auth::AuthUser;
+use crate::config::TrustedProxies;
+use crate::device::validate_device_id;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use axum::{
+    extract::{ConnectInfo, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+use std::net::{IpAddr, SocketAddr};
+
+/// 試用申請
+#[derive(Deserialize)]
+pub struct TrialRequest {
+    device_id: String,
+}
+
+/// 試用申請結果
+#[derive(Serialize)]
+pub struct TrialResponse {
+    decision: TrialDecision,
+    score: u32,
+    reasons: Vec<String>,
+    trial_started: bool,
+}
+
+#[derive(sqlx::FromRow)]
+struct TrialDevice {
+    user_id: Option<String>,
+    platform: String,
+    fcm_token: Option<String>,
+    has_used_free_trial: bool,
+    is_banned: bool,
+    registered_at: DateTime<Utc>,
+}
+
+/// 申請免費試用
+///
+/// 設備必須已綁定目前帳號。依設備、FCM token、信箱別名、IP / 網段與使用記錄計分：
+/// allow 直接開始試用，review 等待人工審核，deny 拒絕；每次申請都會記錄，
+/// 設備濫用訊號的分數超過 `TrialConfig.ban_score` 時以 `sp_ban_device` 封禁設備。
+pub async fn request_trial(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    Json(payload): Json<TrialRequest>,
+) -> AppResult<Json<TrialResponse>> {
+    let device_id = validate_device_id(&payload.device_id)?;
+    let pool = &state.pool;
+
+    // 同一帳號重複申請直接回傳先前的核准結果，不重新計分
+    let granted: Option<i32> = sqlx::query_scalar(
+        "SELECT score FROM trial_attempts WHERE user_id = ? AND decision = 'allow' LIMIT 1",
+    )
+    .bind(&user.user_id)
+    .fetch_optional(pool)
+    .await?;
+    if let Some(score) = granted {
+        return Ok(Json(TrialResponse {
+            decision: TrialDecision::Allow,
+            score: score.max(0) as u32,
+            reasons: vec!["already_granted".to_string()],
+            trial_started: false,
+        }));
+    }
+
+    let device: Option<TrialDevice> = sqlx::query_as(
+        "SELECT user_id, platform, fcm_token, has_used_free_trial, is_banned, registered_at FROM devices WHERE device_id = ?",
+    )
+    .bind(device_id)
+    .fetch_optional(pool)
+    .await?;
+    let device = device.ok_or_else(|| AppError::NotFound("Device not registered".to_string()))?;
+    // 只能替自己綁定的設備申請，避免用他人的設備 ID 消耗或封禁別人的設備
+    if device.user_id.as_deref() != Some(user.user_id.as_str()) {
+        return Err(AppError::Forbidden(
+            "Device is not bound to this account, bind it before requesting a trial".to_string(),
+        ));
+    }
+
+    let ip = client_ip(
+        &headers,
+        connect_info.map(|ConnectInfo(addr)| addr),
+        &state.config.server.trusted_proxies,
+    );
+    let subnet = ip.as_ref().map(ip_subnet);
+    let identity = email_identity(&user.email);
+
+    let signals = collect_signals(
+        pool,
+        &user.user_id,
+        device_id,
+        &device,
+        &identity,
+        ip.map(|ip| ip.to_string()).as_deref(),
+        subnet.as_deref(),
+    )
+    .await?;
+    let assessment = assess(&signals, &state.config.trial);
+
+    sqlx::query(
+        r#"
+        INSERT INTO trial_attempts
+            (id, user_id, device_id, email_identity, ip_address, ip_subnet, score, decision, reasons, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&user.user_id)
+    .bind(device_id)
+    .bind(&identity)
+    .bind(ip.map(|ip| ip.to_string()))
+    .bind(&subnet)
+    .bind(assessment.score)
+    .bind(assessment.decision.as_str())
+    .bind(serde_json::to_string(&assessment.reasons).map_err(anyhow::Error::from)?)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+
+    if assessment.ban_device {
+        let reason = format!(
+            "Free trial abuse (score {}: {})",
+            assessment.score,
+            assessment.reasons.join(", ")
+        );
+        log::warn!("banning device {} for user {}: {}", device_id, user.user_id, reason);
+        sqlx::query("CALL sp_ban_device(?, ?)")
+            .bind(device_id)
+            .bind(&reason)
+            .execute(pool)
+            .await?;
+    }
+
+    let trial_started = assessment.decision == TrialDecision::Allow;
+    if trial_started {
+        let mut tx = pool.begin().await?;
+        sqlx::query("UPDATE devices SET has_used_free_trial = 1, last_used_at = ? WHERE device_id = ?")
+            .bind(Utc::now())
+            .bind(device_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE users SET free_trial_used = 1 WHERE id = ?")
+            .bind(&user.user_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+    }
+
+    Ok(Json(TrialResponse {
+        decision: assessment.decision,
+        score: assessment.score,
+        reasons: assessment.reasons,
+        trial_started,
+    }))
+}
+
+async fn collect_signals(
+    pool: &MySqlPool,
+    user_id: &str,
+    device_id: &str,
+    device: &TrialDevice,
+    identity: &str,
+    ip: Option<&str>,
+    subnet: Option<&str>,
+) -> AppResult<TrialSignals> {
+    let since = Utc::now() - Duration::hours(24);
+
+    let account_trial_used: bool =
+        sqlx::query_scalar("SELECT free_trial_used FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?
+            .unwrap_or(false);
+
+    let fcm_token_trial_devices: i64 = match &device.fcm_token {
+        Some(token) => {
+            sqlx::query_scalar(
+                "SELECT COUNT(*) FROM devices WHERE fcm_token = ? AND device_id <> ? AND has_used_free_trial = 1",
+            )
+            .bind(token)
+            .bind(device_id)
+            .fetch_one(pool)
+            .await?
+        }
+        None => 0,
+    };
+
+    let email_identity_trial_accounts: i64 = sqlx::query_scalar(
+        "SELECT COUNT(DISTINCT user_id) FROM trial_attempts WHERE email_identity = ? AND user_id <> ? AND decision = 'allow'",
+    )
+    .bind(identity)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    let ip_recent_attempts: i64 = match ip {
+        Some(ip) => {
+            sqlx::query_scalar(
+                "SELECT COUNT(*) FROM trial_attempts WHERE ip_address = ? AND created_at > ? AND user_id <> ?",
+            )
+            .bind(ip)
+            .bind(since)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?
+        }
+        None => 0,
+    };
+
+    let subnet_recent_attempts: i64 = match subnet {
+        Some(subnet) => {
+            sqlx::query_scalar(
+                "SELECT COUNT(*) FROM trial_attempts WHERE ip_subnet = ? AND created_at > ? AND user_id <> ?",
+            )
+            .bind(subnet)
+            .bind(since)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?
+        }
+        None => 0,
+    };
+
+    let (usage_events, prior_trial_events): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*), COUNT(CASE WHEN event_type = 'TRIAL_STARTED' THEN 1 END)
+        FROM device_usage_logs
+        WHERE device_id = ?
+        "#,
+    )
+    .bind(device_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(TrialSignals {
+        device_banned: device.is_banned,
+        device_trial_used: device.has_used_free_trial,
+        account_trial_used,
+        platform: device.platform.clone(),
+        device_age_minutes: (Utc::now() - device.registered_at).num_minutes(),
+        fcm_token_trial_devices,
+        email_identity_trial_accounts,
+        ip_recent_attempts,
+        subnet_recent_attempts,
+        usage_events,
+        prior_trial_events,
+    })
+}
+
+/// 客戶端 IP
+///
+/// 連線來自 `TRUSTED_PROXIES` 時才看代理標頭：`X-Forwarded-For` 由右往左取第一個非代理位址，
+/// 其次 `X-Real-IP`；其他連線的標頭可以偽造，一律使用連線位址。
+pub fn client_ip(headers: &HeaderMap, peer: Option<SocketAddr>, trusted: &TrustedProxies) -> Option<IpAddr> {
+    let peer = peer.map(|addr| addr.ip());
+    if !peer.is_some_and(|ip| trusted.contains(ip)) {
+        return peer;
+    }
+
+    let forwarded: Vec<IpAddr> = headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.split(',').filter_map(|ip| ip.trim().parse().ok()).collect())
+        .unwrap_or_default();
+    let forwarded = forwarded
+        .iter()
+        .rev()
+        .find(|ip| !trusted.contains(**ip))
+        .or(forwarded.first())
+        .copied();
+    let real_ip = || {
+        headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+    };
+
+    forwarded.or_else(real_ip).or(peer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_ip_trusts_only_configured_proxies() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "198.51.100.7, 203.0.113.9, 10.0.0.2".parse().unwrap());
+        headers.insert("x-real-ip", "192.0.2.1".parse().unwrap());
+        let proxies: TrustedProxies = "10.0.0.0/8".parse().unwrap();
+        let proxy: SocketAddr = "10.0.0.1:50000".parse().unwrap();
+        let direct: SocketAddr = "203.0.113.50:50000".parse().unwrap();
+
+        // 直連的客戶端自帶標頭不採用
+        assert_eq!(client_ip(&headers, Some(direct), &proxies), Some(direct.ip()));
+        assert_eq!(client_ip(&headers, Some(proxy), &TrustedProxies::default()), Some(proxy.ip()));
+        // 經由代理時取最右邊的非代理位址，最左邊的值可由客戶端偽造
+        assert_eq!(client_ip(&headers, Some(proxy), &proxies), "203.0.113.9".parse().ok());
+
+        headers.remove("x-forwarded-for");
+        assert_eq!(client_ip(&headers, Some(proxy), &proxies), "192.0.2.1".parse().ok());
+    }
+}
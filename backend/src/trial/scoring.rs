@@ -0,0 +1,252 @@
+// src/trial/scoring.rs
+
+use crate::config::TrialConfig;
+use serde::Serialize;
+use std::net::IpAddr;
+
+/// 試用申請時可取得的風險訊號
+#[derive(Debug, Default, Clone)]
+pub struct TrialSignals {
+    pub device_banned: bool,
+    pub device_trial_used: bool,
+    pub account_trial_used: bool,
+    pub platform: String,
+    /// 設備註冊至今的分鐘數
+    pub device_age_minutes: i64,
+    /// 使用相同 FCM token 且已用過試用的其他設備數
+    pub fcm_token_trial_devices: i64,
+    /// 正規化後信箱相同、已取得試用的其他帳號數
+    pub email_identity_trial_accounts: i64,
+    /// 24 小時內同一 IP 其他帳號的試用申請數
+    pub ip_recent_attempts: i64,
+    /// 24 小時內同一網段其他帳號的試用申請數
+    pub subnet_recent_attempts: i64,
+    /// 設備的使用記錄總數
+    pub usage_events: i64,
+    /// 設備曾送出的 TRIAL_STARTED 事件數
+    pub prior_trial_events: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrialDecision {
+    Allow,
+    Review,
+    Deny,
+}
+
+impl TrialDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrialDecision::Allow => "allow",
+            TrialDecision::Review => "review",
+            TrialDecision::Deny => "deny",
+        }
+    }
+}
+
+/// 評分結果
+#[derive(Debug, Clone, Serialize)]
+pub struct TrialAssessment {
+    pub decision: TrialDecision,
+    pub score: u32,
+    pub reasons: Vec<String>,
+    /// 設備本身的濫用分數超過封禁門檻，呼叫端應封禁設備
+    #[serde(skip)]
+    pub ban_device: bool,
+}
+
+/// 依訊號計分並決定 allow / review / deny
+///
+/// 封禁只看設備本身的濫用訊號 (FCM token、信箱別名、設備上的試用事件)；
+/// 帳號已用過試用等訊號只會拒絕，避免重複申請就把正常設備封禁。
+pub fn assess(signals: &TrialSignals, config: &TrialConfig) -> TrialAssessment {
+    let mut score = 0u32;
+    let mut abuse_score = 0u32;
+    let mut reasons = Vec::new();
+    let mut add = |points: u32, reason: &str, abuse: bool| {
+        score += points;
+        if abuse {
+            abuse_score += points;
+        }
+        reasons.push(reason.to_string());
+    };
+
+    if signals.device_banned {
+        add(config.ban_score.max(config.deny_score), "device_banned", false);
+    }
+    if signals.device_trial_used {
+        add(100, "device_trial_used", false);
+    }
+    if signals.account_trial_used {
+        add(100, "account_trial_used", false);
+    }
+    if signals.fcm_token_trial_devices > 0 {
+        add(
+            (40 * signals.fcm_token_trial_devices.min(3)) as u32,
+            "fcm_token_reused",
+            true,
+        );
+    }
+    if signals.email_identity_trial_accounts > 0 {
+        add(
+            (50 * signals.email_identity_trial_accounts.min(3)) as u32,
+            "email_alias_reused",
+            true,
+        );
+    }
+    if signals.ip_recent_attempts >= 3 {
+        add(30, "ip_velocity", false);
+    }
+    if signals.subnet_recent_attempts >= 10 {
+        add(20, "subnet_velocity", false);
+    }
+    if signals.prior_trial_events > 0 {
+        add(30, "prior_trial_events", true);
+    }
+    if signals.usage_events == 0 {
+        add(10, "no_usage_history", false);
+    }
+    if signals.device_age_minutes < 10 {
+        add(10, "new_device", false);
+    }
+    if signals.platform == "web" {
+        add(10, "web_platform", false);
+    }
+
+    let decision = if score >= config.deny_score {
+        TrialDecision::Deny
+    } else if score >= config.review_score {
+        TrialDecision::Review
+    } else {
+        TrialDecision::Allow
+    };
+
+    TrialAssessment {
+        decision,
+        score,
+        ban_device: !signals.device_banned && abuse_score >= config.ban_score,
+        reasons,
+    }
+}
+
+/// 信箱身分正規化：忽略大小寫與 `+tag`，Gmail 另外忽略本地部分的 `.`
+pub fn email_identity(email: &str) -> String {
+    let email = email.trim().to_lowercase();
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return email;
+    };
+
+    let local = local.split('+').next().unwrap_or(local);
+    let (local, domain) = match domain {
+        "gmail.com" | "googlemail.com" => (local.replace('.', ""), "gmail.com"),
+        _ => (local.to_string(), domain),
+    };
+    format!("{}@{}", local, domain)
+}
+
+/// IPv4 取 /24、IPv6 取 /64 作為網段
+pub fn ip_subnet(ip: &IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, _] = v4.octets();
+            format!("{}.{}.{}.0/24", a, b, c)
+        }
+        IpAddr::V6(v6) => {
+            let s = v6.segments();
+            format!("{:x}:{:x}:{:x}:{:x}::/64", s[0], s[1], s[2], s[3])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TrialConfig {
+        TrialConfig {
+            review_score: 30,
+            deny_score: 60,
+            ban_score: 120,
+        }
+    }
+
+    fn clean_signals() -> TrialSignals {
+        TrialSignals {
+            platform: "android".to_string(),
+            device_age_minutes: 60 * 24,
+            usage_events: 5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_clean_request_is_allowed() {
+        let assessment = assess(&clean_signals(), &config());
+        assert_eq!(assessment.decision, TrialDecision::Allow);
+        assert_eq!(assessment.score, 0);
+        assert!(!assessment.ban_device);
+    }
+
+    #[test]
+    fn test_reused_trial_is_denied() {
+        let signals = TrialSignals {
+            device_trial_used: true,
+            ..clean_signals()
+        };
+        let assessment = assess(&signals, &config());
+        assert_eq!(assessment.decision, TrialDecision::Deny);
+        assert_eq!(assessment.reasons, vec!["device_trial_used"]);
+    }
+
+    #[test]
+    fn test_velocity_signals_go_to_review() {
+        let signals = TrialSignals {
+            ip_recent_attempts: 4,
+            ..clean_signals()
+        };
+        assert_eq!(assess(&signals, &config()).decision, TrialDecision::Review);
+    }
+
+    #[test]
+    fn test_heavy_abuse_bans_device() {
+        let signals = TrialSignals {
+            fcm_token_trial_devices: 2,
+            email_identity_trial_accounts: 1,
+            ..clean_signals()
+        };
+        let assessment = assess(&signals, &config());
+        assert_eq!(assessment.decision, TrialDecision::Deny);
+        assert!(assessment.ban_device);
+    }
+
+    #[test]
+    fn test_repeat_request_is_denied_without_ban() {
+        let signals = TrialSignals {
+            device_trial_used: true,
+            account_trial_used: true,
+            ip_recent_attempts: 5,
+            ..clean_signals()
+        };
+        let assessment = assess(&signals, &config());
+        assert_eq!(assessment.decision, TrialDecision::Deny);
+        assert!(assessment.score >= config().ban_score);
+        assert!(!assessment.ban_device);
+    }
+
+    #[test]
+    fn test_email_identity() {
+        assert_eq!(email_identity(" John.Doe+trial@GMail.com"), "johndoe@gmail.com");
+        assert_eq!(email_identity("j.doe+x@googlemail.com"), "jdoe@gmail.com");
+        assert_eq!(email_identity("a.b+c@example.com"), "a.b@example.com");
+    }
+
+    #[test]
+    fn test_ip_subnet() {
+        assert_eq!(ip_subnet(&"203.0.113.77".parse().unwrap()), "203.0.113.0/24");
+        assert_eq!(
+            ip_subnet(&"2001:db8:1:2:3:4:5:6".parse().unwrap()),
+            "2001:db8:1:2::/64"
+        );
+    }
+}
@@ -0,0 +1,68 @@
+// src/user/mod.rs
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::level::{self, LevelState};
+use crate::state::AppState;
+use crate::subscription::{self, SubscriptionInfo};
+use axum::{extract::State, Json};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct LevelSummary {
+    current: i32,
+    total_score: i32,
+    consecutive_wins: i32,
+    cumulative_practices: i32,
+    level_5_unlocked: bool,
+    level_10_unlocked: bool,
+}
+
+impl From<LevelState> for LevelSummary {
+    fn from(level: LevelState) -> Self {
+        Self {
+            current: level.current_level,
+            total_score: level.total_score,
+            consecutive_wins: level.consecutive_wins,
+            cumulative_practices: level.cumulative_practices,
+            level_5_unlocked: level.level_5_unlocked,
+            level_10_unlocked: level.level_10_unlocked,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Profile {
+    id: String,
+    email: String,
+    name: Option<String>,
+    avatar_url: Option<String>,
+    level: LevelSummary,
+    subscription: SubscriptionInfo,
+}
+
+#[derive(Serialize)]
+pub struct ProfileResponse {
+    user: Profile,
+}
+
+/// `GET /api/v1/user/profile` 用戶資料、等級與訂閱狀態
+pub async fn get_profile(State(state): State<AppState>, user: AuthUser) -> AppResult<Json<ProfileResponse>> {
+    let row: Option<(String, String, Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT id, email, name, avatar_url FROM users WHERE id = ?")
+            .bind(&user.user_id)
+            .fetch_optional(&state.pool)
+            .await?;
+    let (id, email, name, avatar_url) = row.ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    Ok(Json(ProfileResponse {
+        user: Profile {
+            id,
+            email,
+            name,
+            avatar_url,
+            level: level::load_level(&state.pool, &user.user_id).await?.into(),
+            subscription: subscription::load_subscription_info(&state, &user.user_id).await?,
+        },
+    }))
+}
@@ -0,0 +1,314 @@
+// src/vocabulary/mod.rs
+
+mod sm2;
+
+pub use sm2::{end_of_today, Schedule, INITIAL_EASE, MAX_GRADE, MAX_MASTERY, MIN_EASE};
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySql, Transaction};
+
+/// 複習佇列每次預設回傳的單字數
+const DEFAULT_QUEUE_LIMIT: i64 = 20;
+const MAX_QUEUE_LIMIT: i64 = 100;
+/// `vocabulary.word` 欄位長度
+const MAX_WORD_CHARS: usize = 100;
+
+/// `dialogues.vocabulary` 的項目：單字字串，或帶有解釋與例句的物件
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum VocabularyItem {
+    Word(String),
+    Detailed {
+        word: String,
+        definition: Option<String>,
+        example: Option<String>,
+    },
+}
+
+/// 情境對話中要收錄的單字
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NewWord {
+    pub word: String,
+    pub definition: Option<String>,
+    pub example: Option<String>,
+}
+
+/// 解析 `dialogues.vocabulary` JSON，略過空白、過長與重複 (不分大小寫) 的單字
+pub fn parse_vocabulary<'a>(documents: impl IntoIterator<Item = &'a str>) -> Vec<NewWord> {
+    let mut words: Vec<NewWord> = Vec::new();
+    for json in documents {
+        let items = match serde_json::from_str::<Vec<VocabularyItem>>(json) {
+            Ok(items) => items,
+            Err(err) => {
+                log::warn!("invalid dialogues.vocabulary JSON: {}", err);
+                continue;
+            }
+        };
+        for item in items {
+            let (word, definition, example) = match item {
+                VocabularyItem::Word(word) => (word, None, None),
+                VocabularyItem::Detailed {
+                    word,
+                    definition,
+                    example,
+                } => (word, definition, example),
+            };
+            let word = word.split_whitespace().collect::<Vec<_>>().join(" ");
+            if word.is_empty()
+                || word.chars().count() > MAX_WORD_CHARS
+                || words.iter().any(|known| known.word.eq_ignore_ascii_case(&word))
+            {
+                continue;
+            }
+            words.push(NewWord {
+                word,
+                definition: definition.filter(|text| !text.trim().is_empty()),
+                example: example.filter(|text| !text.trim().is_empty()),
+            });
+        }
+    }
+    words
+}
+
+/// 在練習完成的交易中，把情境對話的重點單字加入用戶單字本 (已收錄的不變)，回傳新加入的數量
+pub async fn enrol_scenario(
+    tx: &mut Transaction<'_, MySql>,
+    user_id: &str,
+    scenario_id: &str,
+) -> AppResult<u64> {
+    let documents: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT CAST(vocabulary AS CHAR) FROM dialogues
+        WHERE scenario_id = ? AND vocabulary IS NOT NULL
+        ORDER BY sequence_number
+        "#,
+    )
+    .bind(scenario_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let now = Utc::now();
+    let mut enrolled = 0;
+    for word in parse_vocabulary(documents.iter().map(String::as_str)) {
+        sqlx::query(
+            r#"
+            INSERT INTO vocabulary (id, word, definition, example_sentence) VALUES (?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                definition = COALESCE(definition, VALUES(definition)),
+                example_sentence = COALESCE(example_sentence, VALUES(example_sentence))
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(&word.word)
+        .bind(&word.definition)
+        .bind(&word.example)
+        .execute(&mut **tx)
+        .await?;
+
+        // 新單字立即列入今天的複習
+        let inserted = sqlx::query(
+            r#"
+            INSERT IGNORE INTO user_vocabulary (id, user_id, vocabulary_id, scenario_id, due_at)
+            SELECT ?, ?, id, ?, ? FROM vocabulary WHERE word = ?
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(scenario_id)
+        .bind(now)
+        .bind(&word.word)
+        .execute(&mut **tx)
+        .await?;
+        enrolled += inserted.rows_affected();
+    }
+    Ok(enrolled)
+}
+
+#[derive(Deserialize)]
+pub struct QueueQuery {
+    limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct GradeRequest {
+    /// 0 (完全忘記) - 5 (輕鬆答對)
+    grade: u8,
+}
+
+/// 單字卡
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ReviewCard {
+    id: String,
+    word: String,
+    phonetic: Option<String>,
+    definition: Option<String>,
+    #[serde(rename = "example")]
+    example_sentence: Option<String>,
+    audio_url: Option<String>,
+    mastery_level: i32,
+    review_count: i32,
+    ease_factor: f64,
+    interval_days: i32,
+    repetitions: i32,
+    due_at: DateTime<Utc>,
+    last_reviewed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+pub struct ReviewQueue {
+    /// 今天 (台灣時間) 到期的單字總數
+    due_count: i64,
+    words: Vec<ReviewCard>,
+}
+
+const CARD_QUERY: &str = r#"
+    SELECT uv.id, v.word, v.phonetic, v.definition, v.example_sentence, v.audio_url,
+           uv.mastery_level, uv.review_count, uv.ease_factor, uv.interval_days, uv.repetitions,
+           uv.due_at, uv.last_reviewed_at
+    FROM user_vocabulary uv
+    JOIN vocabulary v ON v.id = uv.vocabulary_id
+"#;
+
+/// `GET /api/v1/vocabulary/review` 今天到期的單字，最早到期的在前
+pub async fn review_queue(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<QueueQuery>,
+) -> AppResult<Json<ReviewQueue>> {
+    let limit = query.limit.unwrap_or(DEFAULT_QUEUE_LIMIT).clamp(1, MAX_QUEUE_LIMIT);
+    let due_before = end_of_today(Utc::now());
+
+    let due_count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM user_vocabulary WHERE user_id = ? AND due_at < ?")
+            .bind(&user.user_id)
+            .bind(due_before)
+            .fetch_one(&state.pool)
+            .await?;
+    let words: Vec<ReviewCard> = sqlx::query_as(&format!(
+        "{} WHERE uv.user_id = ? AND uv.due_at < ? ORDER BY uv.due_at, v.word LIMIT ?",
+        CARD_QUERY
+    ))
+    .bind(&user.user_id)
+    .bind(due_before)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(ReviewQueue { due_count, words }))
+}
+
+/// 只能複習今天 (台灣時間) 到期的單字，提早複習不會推進排程
+fn ensure_due(due_at: DateTime<Utc>, now: DateTime<Utc>) -> AppResult<()> {
+    if due_at >= end_of_today(now) {
+        return Err(AppError::Conflict(format!(
+            "Word is not due for review until {}",
+            due_at.to_rfc3339()
+        )));
+    }
+    Ok(())
+}
+
+/// `POST /api/v1/vocabulary/:id/review` 提交複習評分，依 SM-2 更新難易度與下次複習時間
+pub async fn submit_review(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<GradeRequest>,
+) -> AppResult<Json<ReviewCard>> {
+    if payload.grade > MAX_GRADE {
+        return Err(AppError::Validation(format!(
+            "Grade must be between 0 and {}",
+            MAX_GRADE
+        )));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let current: Option<(f64, i32, i32, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        SELECT ease_factor, interval_days, repetitions, due_at FROM user_vocabulary
+        WHERE id = ? AND user_id = ?
+        FOR UPDATE
+        "#,
+    )
+    .bind(&id)
+    .bind(&user.user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let (ease_factor, interval_days, repetitions, due_at) =
+        current.ok_or_else(|| AppError::NotFound("Word not found".to_string()))?;
+
+    let now = Utc::now();
+    ensure_due(due_at, now)?;
+    let next = Schedule {
+        ease_factor,
+        interval_days,
+        repetitions,
+    }
+    .review(payload.grade);
+    sqlx::query(
+        r#"
+        UPDATE user_vocabulary
+        SET ease_factor = ?, interval_days = ?, repetitions = ?, mastery_level = ?,
+            review_count = review_count + 1, last_reviewed_at = ?, due_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(next.ease_factor)
+    .bind(next.interval_days)
+    .bind(next.repetitions)
+    .bind(next.mastery_level())
+    .bind(now)
+    .bind(next.due_at(now))
+    .bind(&id)
+    .execute(&mut *tx)
+    .await?;
+
+    let card: ReviewCard = sqlx::query_as(&format!("{} WHERE uv.id = ?", CARD_QUERY))
+        .bind(&id)
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(Json(card))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vocabulary() {
+        let words = parse_vocabulary([
+            r#"["walk through", "  change ", ""]"#,
+            r#"[{"word": "refactor", "definition": "Restructure code", "example": "We need to refactor this module"}, "Change"]"#,
+            "not json",
+        ]);
+        assert_eq!(
+            words.iter().map(|word| word.word.as_str()).collect::<Vec<_>>(),
+            vec!["walk through", "change", "refactor"]
+        );
+        assert_eq!(words[2].definition.as_deref(), Some("Restructure code"));
+        assert_eq!(words[0].definition, None);
+    }
+
+    #[test]
+    fn test_only_due_words_can_be_reviewed() {
+        use chrono::TimeZone;
+        // 台灣時間 2024-03-01 10:00
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 2, 0, 0).unwrap();
+        assert!(ensure_due(now - chrono::Duration::days(3), now).is_ok());
+        // 今天稍晚到期的也列在今天的複習
+        assert!(ensure_due(Utc.with_ymd_and_hms(2024, 3, 1, 15, 0, 0).unwrap(), now).is_ok());
+        assert!(matches!(
+            ensure_due(Utc.with_ymd_and_hms(2024, 3, 1, 16, 0, 0).unwrap(), now),
+            Err(AppError::Conflict(_))
+        ));
+    }
+}
@@ -0,0 +1,153 @@
+// src/vocabulary/sm2.rs
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveTime, Utc};
+use serde::Serialize;
+
+/// 新單字的難易度係數
+pub const INITIAL_EASE: f64 = 2.5;
+/// 難易度係數下限
+pub const MIN_EASE: f64 = 1.3;
+/// 評分範圍 0-5；3 以上視為答對
+pub const MAX_GRADE: u8 = 5;
+const PASSING_GRADE: u8 = 3;
+/// 熟練度上限 (`user_vocabulary.mastery_level`)
+pub const MAX_MASTERY: i32 = 5;
+/// 複習間隔上限，避免連續答對讓 `due_at` 超出 DATETIME 範圍
+pub const MAX_INTERVAL_DAYS: i32 = 365;
+
+/// 一張單字卡的複習排程
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Schedule {
+    pub ease_factor: f64,
+    pub interval_days: i32,
+    /// 連續答對次數
+    pub repetitions: i32,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            ease_factor: INITIAL_EASE,
+            interval_days: 0,
+            repetitions: 0,
+        }
+    }
+}
+
+impl Schedule {
+    /// SM-2：答對時間隔依序為 1 天、6 天，之後乘上難易度係數 (最多 `MAX_INTERVAL_DAYS`)；答錯從 1 天重新開始
+    ///
+    /// 難易度係數每次依評分調整 (答錯也會調降)，不低於 `MIN_EASE`。
+    pub fn review(&self, grade: u8) -> Schedule {
+        let grade = grade.min(MAX_GRADE);
+        let miss = f64::from(MAX_GRADE - grade);
+        let ease_factor = (self.ease_factor + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE);
+
+        if grade < PASSING_GRADE {
+            return Schedule {
+                ease_factor,
+                interval_days: 1,
+                repetitions: 0,
+            };
+        }
+        let interval_days = match self.repetitions {
+            0 => 1,
+            1 => 6,
+            _ => (f64::from(self.interval_days) * self.ease_factor)
+                .round()
+                .min(f64::from(MAX_INTERVAL_DAYS)) as i32,
+        };
+        Schedule {
+            ease_factor,
+            interval_days: interval_days.clamp(1, MAX_INTERVAL_DAYS),
+            repetitions: self.repetitions + 1,
+        }
+    }
+
+    pub fn due_at(&self, reviewed_at: DateTime<Utc>) -> DateTime<Utc> {
+        reviewed_at + Duration::days(i64::from(self.interval_days))
+    }
+
+    pub fn mastery_level(&self) -> i32 {
+        self.repetitions.min(MAX_MASTERY)
+    }
+}
+
+/// 台灣時間今天結束的時刻，`due_at` 在此之前的單字都列入今天的複習
+pub fn end_of_today(now: DateTime<Utc>) -> DateTime<Utc> {
+    let taipei = FixedOffset::east_opt(8 * 3600).expect("valid offset");
+    let tomorrow = now.with_timezone(&taipei).date_naive() + Duration::days(1);
+    tomorrow
+        .and_time(NaiveTime::MIN)
+        .and_local_timezone(taipei)
+        .single()
+        .map(|end| end.with_timezone(&Utc))
+        .unwrap_or(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_intervals_grow_with_ease() {
+        let first = Schedule::default().review(5);
+        assert_eq!((first.interval_days, first.repetitions), (1, 1));
+        assert!((first.ease_factor - 2.6).abs() < 1e-9);
+
+        let second = first.review(4);
+        assert_eq!((second.interval_days, second.repetitions), (6, 2));
+        assert!((second.ease_factor - 2.6).abs() < 1e-9);
+
+        // 6 × 2.6 = 15.6
+        let third = second.review(3);
+        assert_eq!(third.interval_days, 16);
+        assert!((third.ease_factor - 2.46).abs() < 1e-9);
+        assert_eq!(third.mastery_level(), 3);
+    }
+
+    #[test]
+    fn test_lapse_resets_repetitions() {
+        let learned = Schedule {
+            ease_factor: 2.5,
+            interval_days: 30,
+            repetitions: 6,
+        };
+        assert_eq!(learned.mastery_level(), MAX_MASTERY);
+        let lapsed = learned.review(1);
+        assert_eq!((lapsed.interval_days, lapsed.repetitions), (1, 0));
+        assert!((lapsed.ease_factor - 1.96).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_interval_is_capped() {
+        let mut schedule = Schedule::default();
+        for _ in 0..100 {
+            schedule = schedule.review(MAX_GRADE);
+            assert!(schedule.interval_days <= MAX_INTERVAL_DAYS);
+        }
+        assert_eq!(schedule.interval_days, MAX_INTERVAL_DAYS);
+        let reviewed_at = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        assert_eq!(schedule.due_at(reviewed_at), Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_ease_has_a_floor() {
+        let mut schedule = Schedule::default();
+        for _ in 0..10 {
+            schedule = schedule.review(0);
+        }
+        assert_eq!(schedule.ease_factor, MIN_EASE);
+    }
+
+    #[test]
+    fn test_end_of_today_uses_taipei_time() {
+        // 台灣時間 2024-03-01 23:30
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 15, 30, 0).unwrap();
+        assert_eq!(end_of_today(now), Utc.with_ymd_and_hms(2024, 3, 1, 16, 0, 0).unwrap());
+        // 台灣時間 2024-03-02 00:30
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 16, 30, 0).unwrap();
+        assert_eq!(end_of_today(now), Utc.with_ymd_and_hms(2024, 3, 2, 16, 0, 0).unwrap());
+    }
+}